@@ -0,0 +1,232 @@
+//! Compares two JSON reports (`siteprobe compare`) to find regressions between runs,
+//! e.g. of a nightly job: URLs that started failing, URLs that were fixed, and URLs
+//! whose response time changed.
+
+use crate::options::CompareArgs;
+use crate::report::{load_json_report, Response};
+use crate::term::style;
+use crate::utils;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// The result of a URL in one report. A URL probed several times (`--repeat`) has its
+/// highest status code and mean response time, and counts as failed if any probe failed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UrlResult {
+    pub status_code: u16,
+    pub failed: bool,
+    pub response_time: Duration,
+}
+
+/// A URL that started failing, or was fixed, between the baseline and the current report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusChange {
+    pub url: String,
+    /// The status code in the baseline, `None` if the URL is new in the current report.
+    pub baseline: Option<u16>,
+    pub current: u16,
+}
+
+/// A URL whose response time changed by more than the threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeChange {
+    pub url: String,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl TimeChange {
+    /// The change in milliseconds, positive if the URL got slower.
+    pub fn delta_ms(&self) -> i128 {
+        self.current.as_millis() as i128 - self.baseline.as_millis() as i128
+    }
+}
+
+/// The differences between two reports, each list sorted by URL.
+#[derive(Debug, Default)]
+pub struct Comparison {
+    /// URLs failing in the current report that didn't fail in the baseline, including
+    /// URLs that are new in the current report.
+    pub new_failures: Vec<StatusChange>,
+    /// URLs that failed in the baseline and don't fail in the current report.
+    pub fixed: Vec<StatusChange>,
+    /// URLs that got slower by more than the threshold.
+    pub slower: Vec<TimeChange>,
+    /// URLs that got faster by more than the threshold.
+    pub faster: Vec<TimeChange>,
+}
+
+impl Comparison {
+    /// Whether the current report regressed: a URL started failing or got slower.
+    pub fn has_regressions(&self) -> bool {
+        !self.new_failures.is_empty() || !self.slower.is_empty()
+    }
+
+    /// Prints the new failures, fixed URLs and response time changes, followed by a
+    /// summary line.
+    pub fn print(&self, threshold: Duration) {
+        let was = |status: Option<u16>| {
+            status.map_or_else(|| "new URL".to_string(), |s| format!("was {}", s))
+        };
+
+        if !self.new_failures.is_empty() {
+            println!("{}\n", style("New Failures:").bold());
+            for change in &self.new_failures {
+                println!(
+                    "{} {} {}",
+                    style(format!("{}:", change.current)).bold().red(),
+                    change.url,
+                    style(format!("({})", was(change.baseline))).dim()
+                );
+            }
+            println!();
+        }
+
+        if !self.fixed.is_empty() {
+            println!("{}\n", style("Fixed URLs:").bold());
+            for change in &self.fixed {
+                println!(
+                    "{} {} {}",
+                    style(format!("{}:", change.current)).bold().green(),
+                    change.url,
+                    style(format!("({})", was(change.baseline))).dim()
+                );
+            }
+            println!();
+        }
+
+        if !self.slower.is_empty() || !self.faster.is_empty() {
+            println!(
+                "{}\n",
+                style(format!(
+                    "Response Time Changes (more than {}ms):",
+                    threshold.as_millis()
+                ))
+                .bold()
+            );
+            for change in self.slower.iter().chain(&self.faster) {
+                let delta = format!("{:+}ms", change.delta_ms());
+                let delta = if change.delta_ms() > 0 {
+                    style(delta).bold().red()
+                } else {
+                    style(delta).bold().green()
+                };
+                println!(
+                    "{} {} {}",
+                    delta,
+                    change.url,
+                    style(format!(
+                        "({}ms → {}ms)",
+                        change.baseline.as_millis(),
+                        change.current.as_millis()
+                    ))
+                    .dim()
+                );
+            }
+            println!();
+        }
+
+        let summary = if self.has_regressions() {
+            style(format!(
+                "❌ {} new failure{}, {} slower — exit 1",
+                self.new_failures.len(),
+                if self.new_failures.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                self.slower.len()
+            ))
+            .red()
+            .bold()
+        } else {
+            style(format!(
+                "✅ No regressions, {} fixed — exit 0",
+                self.fixed.len()
+            ))
+            .green()
+            .bold()
+        };
+        println!("{}", summary);
+    }
+}
+
+/// Groups responses by their requested URL, without the `cache_bust_param` cache
+/// buster of `--append-timestamp`.
+pub fn summarize(responses: &[Response], cache_bust_param: &str) -> BTreeMap<String, UrlResult> {
+    let mut grouped: BTreeMap<String, Vec<&Response>> = BTreeMap::new();
+    for r in responses {
+        let url = utils::strip_cache_buster(&r.requested_url, cache_bust_param);
+        grouped.entry(url.to_string()).or_default().push(r);
+    }
+    grouped
+        .into_iter()
+        .map(|(url, probes)| {
+            let result = UrlResult {
+                status_code: probes
+                    .iter()
+                    .map(|r| r.status_code.as_u16())
+                    .max()
+                    .unwrap_or_default(),
+                failed: probes.iter().any(|r| r.is_failed()),
+                response_time: probes.iter().map(|r| r.response_time).sum::<Duration>()
+                    / probes.len() as u32,
+            };
+            (url, result)
+        })
+        .collect()
+}
+
+/// Compares the responses of the `current` report with the `baseline`. URLs missing
+/// from the current report are not compared. A response time change is reported if it
+/// is larger than `threshold`.
+pub fn compare(
+    baseline: &[Response],
+    current: &[Response],
+    threshold: Duration,
+    cache_bust_param: &str,
+) -> Comparison {
+    let baseline = summarize(baseline, cache_bust_param);
+    let current = summarize(current, cache_bust_param);
+    let mut comparison = Comparison::default();
+
+    for (url, now) in current {
+        let before = baseline.get(&url);
+        let change = StatusChange {
+            url: url.clone(),
+            baseline: before.map(|b| b.status_code),
+            current: now.status_code,
+        };
+        let failed_before = before.is_some_and(|b| b.failed);
+        if now.failed && !failed_before {
+            comparison.new_failures.push(change);
+        } else if failed_before && !now.failed {
+            comparison.fixed.push(change);
+        }
+
+        let Some(before) = before else { continue };
+        let time_change = TimeChange {
+            url,
+            baseline: before.response_time,
+            current: now.response_time,
+        };
+        if now.response_time > before.response_time + threshold {
+            comparison.slower.push(time_change);
+        } else if before.response_time > now.response_time + threshold {
+            comparison.faster.push(time_change);
+        }
+    }
+    comparison
+}
+
+/// Loads the two JSON reports of `siteprobe compare` and compares them.
+pub fn compare_reports(args: &CompareArgs) -> Result<Comparison, String> {
+    let baseline = load_json_report(&args.baseline)?;
+    let current = load_json_report(&args.current)?;
+    Ok(compare(
+        &baseline,
+        &current,
+        Duration::from_millis(args.threshold_ms),
+        &args.cache_bust_param,
+    ))
+}
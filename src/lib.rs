@@ -1,10 +1,13 @@
 // Library interface for siteprobe
 // This allows integration tests to access the modules
 
+pub mod compare;
 pub mod metrics;
 pub mod network;
 pub mod options;
 pub mod report;
 pub mod sitemap;
 pub mod storage;
+pub mod term;
 pub mod utils;
+pub mod warnings;
@@ -1,18 +1,32 @@
+mod compare;
 mod metrics;
 mod network;
 mod options;
 mod report;
 mod sitemap;
 mod storage;
+mod term;
 mod utils;
+mod warnings;
 
 use std::error::Error;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::sitemap::{fetch_and_generate_report, get_sitemap_urls};
+use crate::options::SitemapSource;
+use crate::sitemap::{
+    check_url_count, dry_run, fetch_and_generate_report, filter_changed_urls,
+    filter_modified_since, filter_urls_by_host, filter_urls_by_pattern, get_sitemap_urls,
+    merge_sitemap_urls, read_urls_file, read_urls_stdin, strip_query_params, url_overview,
+    url_structure,
+};
+use crate::warnings::Warnings;
 use clap::Parser;
 use console::style;
+use serde_json::json;
 use tokio::time::Instant;
 
 #[tokio::main]
@@ -20,39 +34,361 @@ async fn main() -> Result<ExitCode, Box<dyn Error>> {
     // Parse terminal arguments.
     let mut options = options::Cli::parse();
 
-    // Load config file and apply values (CLI args take priority).
-    let config = options::ConfigFile::load(options.config.as_ref()).unwrap_or_else(|e| {
-        eprintln!("{} {}", style("[ERROR]").red(), e);
-        std::process::exit(1);
+    // Disable colors with --no-color or the NO_COLOR convention (https://no-color.org).
+    if options.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+
+    if options.version {
+        println!("{}", options::version_info(options.verbose));
+        return Ok(ExitCode::SUCCESS);
+    }
+    if options.explain_exit_codes {
+        println!("{}", options::EXIT_CODES_HELP);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Compare two earlier reports instead of probing a sitemap (`siteprobe compare`).
+    if let Some(options::Command::Compare(args)) = options.command.as_ref() {
+        let comparison = compare::compare_reports(args).unwrap_or_else(|e| fail(None, e));
+        comparison.print(Duration::from_millis(args.threshold_ms));
+        return Ok(if comparison.has_regressions() {
+            ExitCode::from(1)
+        } else {
+            ExitCode::SUCCESS
+        });
+    }
+
+    // Write the status file if the run is interrupted with Ctrl-C before probing.
+    // While probing, Ctrl-C produces a partial report instead.
+    let interrupt_handler = options.status_file.clone().map(|path| {
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                write_status(&path, json!({"exitCode": 130, "interrupted": true}));
+                std::process::exit(130);
+            }
+        })
     });
-    options.apply_config(&config);
+
+    // Load config file and apply values (CLI args take priority).
+    let config = options::ConfigFile::load(&options.config)
+        .unwrap_or_else(|e| fail(options.status_file.as_ref(), e));
+    let warnings = Warnings::default();
+    options.apply_config(&config, &warnings);
+    options.apply_report_dir();
 
     // Build the HTTP client.
-    let client = Arc::new(network::build_client(&options)?);
+    let client = Arc::new(
+        network::build_client(&options).unwrap_or_else(|e| fail(options.status_file.as_ref(), e)),
+    );
     let start_time = Instant::now();
 
-    // Fetch all URLs from the sitemap.
-    let urls = get_sitemap_urls(options.sitemap_url.as_str(), &client, options.json)
-        .await
-        .unwrap_or_else(|e| {
-            eprintln!("{} {}", style("[ERROR]").red(), e);
-            std::process::exit(1);
-        });
+    // Always shown, even with --json, so an insecure run doesn't go unnoticed.
+    if options.insecure {
+        let message = "TLS certificate verification is disabled (--insecure). The results don't prove that the site's certificate is valid.";
+        eprintln!(
+            "{} {}",
+            style("[WARNING]").bold().yellow().for_stderr(),
+            message
+        );
+        warnings.push(message);
+    }
 
-    // Fetch URLs concurrently and generate a report.
-    let report = fetch_and_generate_report(urls, &client, &options, &start_time).await?;
+    // Log in first if the site requires a form-based session.
+    if let Some(login_url) = options.login_url.as_ref() {
+        network::login(&client, login_url, &options.login_data)
+            .await
+            .unwrap_or_else(|e| fail(options.status_file.as_ref(), e));
+    }
 
-    if options.json {
-        // Print clean JSON to stdout for piping.
-        println!("{}", report.to_json_string(&options)?);
+    // Fetch all URLs from the sitemaps, or read them from a URL list. The URLs of
+    // several sitemaps are merged into one set.
+    let mut sitemap_urls = if let Some(path) = options.urls_file.as_ref() {
+        read_urls_file(path).unwrap_or_else(|e| fail(options.status_file.as_ref(), e))
     } else {
-        // Display the report.
-        report.show_text_report(&options);
+        let mut sources = Vec::with_capacity(options.sitemap_urls.len());
+        for source in &options.sitemap_urls {
+            let urls = match source {
+                SitemapSource::Stdin => read_urls_stdin(),
+                SitemapSource::Url(sitemap_url) => {
+                    get_sitemap_urls(
+                        sitemap_url.as_str(),
+                        &client,
+                        options.quiet(),
+                        options.discover,
+                        options.html_sitemap,
+                        options
+                            .sitemap_concurrency
+                            .unwrap_or(options.concurrency_limit) as usize,
+                        &warnings,
+                    )
+                    .await
+                }
+            };
+            sources.push(urls.unwrap_or_else(|e| fail(options.status_file.as_ref(), e)));
+        }
+        merge_sitemap_urls(sources)
+    };
+
+    // Summarize the hosts and schemes of the sitemap URLs before probing.
+    let overview = url_overview(&sitemap_urls.urls);
+    if !options.quiet() {
+        println!(
+            "{}",
+            style(format!(
+                "The sitemap lists {} URLs on {} host{} ({} https, {} http).",
+                overview.urls,
+                overview.hosts,
+                if overview.hosts == 1 { "" } else { "s" },
+                overview.https,
+                overview.http
+            ))
+            .dim()
+        );
+    }
+
+    // Guard against sitemap generation bugs (--expect-url-count).
+    if let Some(expected) = options.expect_url_count {
+        let actual = sitemap_urls.urls.len();
+        if let Err(e) = check_url_count(actual, expected, options.url_count_tolerance) {
+            fail(options.status_file.as_ref(), e);
+        }
+        if !options.quiet() {
+            println!(
+                "{}",
+                style(format!(
+                    "Found {} URLs in the sitemap, expected {}.",
+                    actual, expected
+                ))
+                .dim()
+            );
+        }
+    }
+
+    // Skip URLs whose <lastmod> is older than --modified-since. This runs before the
+    // query parameters are removed, as <lastmod> is recorded for the original URLs.
+    let mut skipped_unmodified = None;
+    if let Some(since) = options.modified_since.as_ref() {
+        let (urls, skipped) = filter_modified_since(
+            std::mem::take(&mut sitemap_urls.urls),
+            &sitemap_urls.lastmod,
+            since,
+        );
+        sitemap_urls.urls = urls;
+        skipped_unmodified = Some(skipped);
+        if !options.quiet() {
+            println!(
+                "{}",
+                style(format!(
+                    "Skipped {} URLs not modified since {}.",
+                    skipped, since
+                ))
+                .dim()
+            );
+        }
+    }
+
+    // Remove tracking and other query parameters (--ignore-query, --ignore-param).
+    let mut collapsed_urls = None;
+    if options.ignore_query || !options.ignore_params.is_empty() {
+        let (urls, collapsed) = strip_query_params(
+            std::mem::take(&mut sitemap_urls.urls),
+            &options.ignore_params,
+            options.ignore_query,
+        );
+        sitemap_urls.urls = urls;
+        collapsed_urls = Some(collapsed);
+        if !options.quiet() {
+            println!(
+                "{}",
+                style(format!(
+                    "Collapsed {} URLs that only differ in ignored query parameters.",
+                    collapsed
+                ))
+                .dim()
+            );
+        }
+    }
+
+    // Summarize the path depth and length of the sitemap URLs (--url-structure).
+    let url_structure = options
+        .url_structure
+        .then(|| url_structure(&sitemap_urls.urls, options::defaults::URL_STRUCTURE_NUM));
+
+    // Drop URLs on hosts excluded by --allow-host/--deny-host.
+    let (urls, skipped) =
+        filter_urls_by_host(sitemap_urls.urls, &options.allow_hosts, &options.deny_hosts);
+    if skipped > 0 && !options.quiet() {
+        println!(
+            "{}",
+            style(format!("Skipped {} URLs excluded by host filter.", skipped)).dim()
+        );
+    }
+
+    // Drop URLs excluded by --include/--exclude.
+    let (mut urls, skipped) =
+        filter_urls_by_pattern(urls, &options.include_patterns, &options.exclude_patterns);
+    if skipped > 0 && !options.quiet() {
+        println!(
+            "{}",
+            style(format!(
+                "Skipped {} URLs excluded by --include/--exclude.",
+                skipped
+            ))
+            .dim()
+        );
+    }
+
+    // Skip URLs that were healthy in a previous run (--changed-since).
+    let mut skipped_unchanged = None;
+    if let Some(path) = options.changed_since.as_ref() {
+        let previous = report::load_previous_failures(path, &options.cache_bust_param)
+            .unwrap_or_else(|e| fail(options.status_file.as_ref(), e));
+        let (changed, skipped) = filter_changed_urls(urls, &previous);
+        urls = changed;
+        skipped_unchanged = Some(skipped);
+        if !options.quiet() {
+            println!(
+                "{}",
+                style(format!(
+                    "Skipped {} URLs that were unchanged and healthy in {}.",
+                    skipped,
+                    path.display()
+                ))
+                .dim()
+            );
+        }
+    }
+
+    // Only probe the first N of the remaining URLs (--max-urls).
+    if let Some(max) = options.max_urls {
+        let max = usize::try_from(max).unwrap_or(usize::MAX);
+        if urls.len() > max {
+            let dropped = urls.len() - max;
+            urls.truncate(max);
+            if !options.silent_on_success {
+                eprintln!(
+                    "{} Probing the first {} URLs (--max-urls), skipping {} more.",
+                    style("[INFO]").cyan().for_stderr(),
+                    max,
+                    dropped
+                );
+            }
+        }
+    }
+
+    // Only print the URLs that would be probed (--list-urls).
+    if options.list_urls {
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(&urls)?);
+        } else {
+            urls.iter().for_each(|url| println!("{}", url));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Size the run without probing (--dry-run).
+    if options.dry_run {
+        let summary = dry_run(&urls, &sitemap_urls.sitemaps, &options);
+        if options.json {
+            let data = json!({
+                "urls": summary.urls,
+                "requests": summary.requests,
+                "sitemaps": summary.sitemaps.iter()
+                    .map(|(url, urls)| json!({"url": url, "urls": urls}))
+                    .collect::<Vec<_>>(),
+                "estimatedTime": {
+                    "minSecs": summary.min_time.as_secs_f64(),
+                    "maxSecs": summary.max_time.as_secs_f64(),
+                },
+            });
+            println!("{}", serde_json::to_string_pretty(&data)?);
+        } else {
+            println!("{}", summary);
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Fetch URLs concurrently and generate a report.
+    if let Some(handler) = interrupt_handler {
+        handler.abort();
+    }
+    let mut report = fetch_and_generate_report(urls, &client, &options, &start_time).await?;
+    report.url_overview = Some(overview);
+    report.collapsed_urls = collapsed_urls;
+    report.skipped_unchanged = skipped_unchanged;
+    report.skipped_unmodified = skipped_unmodified;
+    report.duplicates = sitemap_urls.duplicates;
+    report.url_structure = url_structure;
+    if report.byte_capped {
+        warnings.push(format!(
+            "Stopped probing after downloading {} bytes, exceeding --max-total-bytes {}.",
+            report.total_bytes(),
+            options.max_total_bytes.unwrap_or_default()
+        ));
+    }
+    let storage_errors: Vec<_> = report
+        .responses
+        .iter()
+        .filter_map(|r| r.storage_error.as_ref())
+        .collect();
+    if let Some(first) = storage_errors.first() {
+        warnings.push(format!(
+            "{} documents could not be stored in --output-dir, e.g. {}",
+            storage_errors.len(),
+            first
+        ));
+    }
+    let truncated = report.responses.iter().filter(|r| r.truncated).count();
+    if truncated > 0 {
+        warnings.push(format!(
+            "{} response bodies were truncated at --max-body-size {}; their size is the cap.",
+            truncated,
+            options.max_body_size.unwrap_or_default()
+        ));
+    }
+    if report.time_capped {
+        warnings.push(format!(
+            "Stopped probing at --max-duration {}s; the report covers {} responses.",
+            options.max_duration.unwrap_or_default(),
+            report.responses.len()
+        ));
+    }
+    if report.interrupted {
+        warnings.push(format!(
+            "Interrupted with Ctrl-C; the report covers {} responses.",
+            report.responses.len()
+        ));
+    }
+    report.warnings = warnings.to_vec();
+
+    if let Some(r) = report.first_error.as_ref() {
+        eprintln!(
+            "{} Stopped on first error: {} {}",
+            style("[ERROR]").red(),
+            r.status_code,
+            r.url
+        );
+    }
+
+    // With --silent-on-success, a run without errors or slow URLs prints nothing.
+    let exit_code = report.exit_code(options.slow_threshold);
+    let silent = options.silent_on_success && exit_code == ExitCode::SUCCESS;
+
+    if !silent {
+        if options.json {
+            // Print clean JSON to stdout for piping.
+            println!("{}", report.to_json_string(&options)?);
+        } else {
+            // Display the report.
+            report.show_text_report(&options);
+        }
     }
 
     // Optionally, write the report to CSV file.
     if let Some(path) = options.report_path.as_ref() {
-        report.write_csv_report(path, options.json)?;
+        report.write_csv_report(path, options.quiet())?;
     }
 
     // Optionally, write the report to JSON file.
@@ -60,10 +396,112 @@ async fn main() -> Result<ExitCode, Box<dyn Error>> {
         report.write_json_report(&options, path)?;
     }
 
+    // Optionally, append the report to a JSON Lines file.
+    if let Some(path) = options.report_path_jsonl.as_ref() {
+        report.write_jsonl_report(&options, path)?;
+    }
+
+    // Optionally, write the report to a JUnit XML file.
+    if let Some(path) = options.report_path_junit.as_ref() {
+        report.write_junit_report(path, options.quiet())?;
+    }
+
+    // Optionally, write the report to a Markdown file.
+    if let Some(path) = options.report_path_md.as_ref() {
+        report.write_markdown_report(&options, path)?;
+    }
+
     // Optionally, write the report to HTML file.
     if let Some(path) = options.report_path_html.as_ref() {
         report.write_html_report(&options, path)?;
     }
 
-    Ok(report.exit_code(options.slow_threshold))
+    // Optionally, write the failed URLs to probe them again with --urls-file.
+    if let Some(path) = options.failures_path.as_ref() {
+        report.write_failures(path, options.quiet())?;
+    }
+
+    // Consolidated warnings of the whole run, so they aren't lost in the output above.
+    if let Some(summary) = crate::warnings::summary(&report.warnings) {
+        eprintln!("\n{}", style(summary).yellow().for_stderr());
+    }
+
+    // Compare the success rate with --min-success-rate, whether the gate passed or not.
+    if let (Some(min), false) = (options.min_success_rate, silent) {
+        match report.success_rate_shortfall() {
+            Some((rate, _)) => eprintln!(
+                "\n{} Success rate {:.2}% is below the minimum of {}% (--min-success-rate).",
+                style("[ERROR]").red(),
+                rate,
+                min
+            ),
+            None => eprintln!(
+                "\n{}",
+                style(format!(
+                    "Success rate {:.2}% meets the minimum of {}% (--min-success-rate).",
+                    report.statistics(None).success_rate,
+                    min
+                ))
+                .green()
+                .for_stderr()
+            ),
+        }
+    }
+
+    // Name each exceeded performance budget (--max-p95-ms, --max-avg-ms, --max-error-rate).
+    for budget in report.exceeded_budgets() {
+        eprintln!(
+            "{} Performance budget exceeded: {}.",
+            style("[ERROR]").red(),
+            budget
+        );
+    }
+
+    if options.verbose {
+        eprintln!("{}", report.exit_reason(options.slow_threshold));
+    }
+
+    // Print a colored summary line; to stderr in JSON mode to keep stdout clean.
+    let summary = report.summary_line(options.slow_threshold);
+    let summary = if exit_code == ExitCode::SUCCESS {
+        style(summary).green().bold()
+    } else if exit_code == ExitCode::from(2) {
+        style(summary).yellow().bold()
+    } else {
+        style(summary).red().bold()
+    };
+    if !silent {
+        if options.json {
+            eprintln!("{}", summary.for_stderr());
+        } else {
+            println!("\n{}", summary);
+        }
+    }
+
+    // Write the status file as the last step, for CI jobs to pick up (--status-file).
+    if let Some(path) = options.status_file.as_ref() {
+        write_status(path, report.status_json(options.slow_threshold));
+    }
+
+    Ok(exit_code)
+}
+
+/// Prints a fatal error and exits with code 1, writing the `--status-file` first.
+fn fail(status_file: Option<&PathBuf>, message: impl Display) -> ! {
+    eprintln!("{} {}", style("[ERROR]").red(), message);
+    if let Some(path) = status_file {
+        write_status(path, json!({"exitCode": 1, "error": message.to_string()}));
+    }
+    std::process::exit(1);
+}
+
+fn write_status(path: &Path, status: serde_json::Value) {
+    if let Err(e) = report::write_status_file(path, status) {
+        eprintln!(
+            "{} Unable to write the status file {}: {}",
+            style("[ERROR]").red(),
+            path.display(),
+            e
+        );
+    }
 }
@@ -1,5 +1,8 @@
+#[cfg(feature = "cli")]
 use once_cell::sync::Lazy;
+#[cfg(feature = "cli")]
 use prettytable::format::{FormatBuilder, LinePosition, LineSeparator, TableFormat};
+#[cfg(feature = "cli")]
 use prettytable::{Cell, Row, Table};
 use serde::{Serialize, Serializer};
 use serde_json::Value;
@@ -18,19 +21,26 @@ use std::collections::HashMap;
 /// ## Usage
 ///
 /// 1. **Define Metrics**:
-///    Populate a list of key-value pairs using `LabeledValue` entries.
+///    Populate a list of labeled values using `Entry` items.
 ///
 /// 2. **Convert to Table**:
 ///    Use the `Metrics::build_table` method to generate the table.
 ///
 /// ### Example:
 ///
-/// ```rust,ignore
-/// use your_module_name::{LabeledValue, Metrics};
+/// ```rust
+/// use serde_json::json;
+/// use siteprobe::metrics::{Entry, Metrics};
 ///
+/// let entry = |label, value: &str, json_label, json_value| Entry {
+///     label,
+///     value: value.to_string(),
+///     json_label,
+///     json_value,
+/// };
 /// let metrics = Metrics(vec![
-///     LabeledValue { label: "Key1", value: "Value1".to_string() },
-///     LabeledValue { label: "Key2", value: "Value2".to_string() },
+///     entry("Key1", "Value1", "key1", json!(1)),
+///     entry("Key2", "Value2", "key2", json!(2)),
 /// ]);
 ///
 /// println!("{}", metrics.build_table());
@@ -49,7 +59,9 @@ use std::collections::HashMap;
 /// - **once_cell**: Used for the lazy initialization of the formatting configuration.
 /// - **prettytable**: Provides functionalities for defining table formats and rendering tabular data.
 ///
-///
+/// Rendering tables requires the `cli` feature; the `Metrics` data itself is always
+/// available and serializes to JSON.
+#[cfg(feature = "cli")]
 static TABLE_FORMAT: Lazy<TableFormat> = Lazy::new(|| {
     FormatBuilder::new()
         .column_separator('│')
@@ -63,20 +75,30 @@ static TABLE_FORMAT: Lazy<TableFormat> = Lazy::new(|| {
         .build()
 });
 
+#[cfg(feature = "cli")]
 pub static CLEAN_FORMAT: Lazy<TableFormat> =
     Lazy::new(|| FormatBuilder::new().padding(0, 3).build());
 
+/// A labeled value, shown as `label` and `value` in the text and HTML reports, and as
+/// `json_label: json_value` in the JSON report.
 #[derive(Debug, Serialize)]
 pub struct Entry {
+    /// The label in the text and HTML reports, e.g. "⏰ Average Response Time".
     pub label: &'static str,
+    /// The formatted value in the text and HTML reports, e.g. "123.45ms".
     pub value: String,
+    /// The key in the JSON report, e.g. `avgMs`.
     pub json_label: &'static str,
+    /// The raw value in the JSON report, e.g. `123`.
     pub json_value: Value,
 }
 
+/// A list of labeled values, rendered as a table in the text report and serialized to
+/// a `{json_label: json_value}` object in the JSON report.
 #[derive(Debug)]
 pub struct Metrics(pub Vec<Entry>);
 
+#[cfg(feature = "cli")]
 impl Metrics {
     pub fn build_table(&self) -> String {
         let mut table = Table::new();
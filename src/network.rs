@@ -1,9 +1,14 @@
-use crate::options::Cli;
+use crate::options::{Cli, LoginField};
 use crate::report::Response;
-use crate::storage::store_response_on_disk;
+use crate::storage::{format_headers, store_response_on_disk, OutputDir};
 use base64::Engine;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderValue};
+use sha2::{Digest, Sha256};
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
-use std::path::PathBuf;
+use std::net::{SocketAddr, TcpListener};
 use std::time::Duration;
 
 /// Builds and configures the HTTP client based on the provided CLI options.
@@ -18,14 +23,207 @@ use std::time::Duration;
 pub fn build_client(options: &Cli) -> Result<reqwest::Client, Box<dyn Error>> {
     let mut client_builder = reqwest::Client::builder()
         .redirect(reqwest::redirect::Policy::none())
-        .user_agent(options.user_agent.as_str())
-        .timeout(Duration::from_secs(options.request_timeout as u64));
+        .user_agent(options.user_agent.as_str());
 
-    if options.follow_redirects {
-        client_builder = client_builder.redirect(reqwest::redirect::Policy::limited(10));
+    // A timeout of 0 disables the per-request timeout.
+    if options.request_timeout > 0 {
+        client_builder = client_builder.timeout(Duration::from_secs(options.request_timeout));
     }
 
-    let mut headers = reqwest::header::HeaderMap::new();
+    // A limit of 0 (--max-redirects) leaves redirects unfollowed.
+    if options.max_redirects > 0 && (options.follow_redirects || options.same_host_redirects_only) {
+        client_builder = client_builder.redirect(redirect_policy(
+            options.max_redirects,
+            options.same_host_redirects_only,
+        ));
+    }
+
+    if let Some(max_idle) = options.pool_max_idle_per_host {
+        client_builder = client_builder.pool_max_idle_per_host(max_idle as usize);
+    }
+
+    if let Some(idle_timeout) = options.pool_idle_timeout {
+        client_builder = client_builder.pool_idle_timeout(Duration::from_secs(idle_timeout));
+    }
+
+    if options.no_keepalive {
+        client_builder = client_builder.pool_max_idle_per_host(0);
+    }
+
+    if options.insecure {
+        client_builder = client_builder.danger_accept_invalid_certs(true);
+    }
+
+    // DNS overrides (--resolve). The port is set to 0 so the port of the URL is used.
+    let mut overrides: HashMap<&str, Vec<SocketAddr>> = HashMap::new();
+    for entry in &options.resolve {
+        overrides
+            .entry(entry.host.as_str())
+            .or_default()
+            .push(SocketAddr::new(entry.ip, 0));
+    }
+    for (host, addrs) in overrides {
+        client_builder = client_builder.resolve_to_addrs(host, &addrs);
+    }
+
+    // Requests only bind to the local address when connecting, so check up front
+    // that it belongs to this host for a clear error message.
+    if let Some(local_address) = options.local_address {
+        TcpListener::bind((local_address, 0)).map_err(|e| {
+            format!(
+                "Unable to use local address {}: {}. Check that it is assigned to a network interface.",
+                local_address, e
+            )
+        })?;
+        client_builder = client_builder.local_address(local_address);
+    }
+
+    // Keep the session cookies of a form login for all later requests.
+    if options.login_url.is_some() {
+        client_builder = client_builder.cookie_store(true);
+    }
+
+    let headers = build_default_headers(options)?;
+    if !headers.is_empty() {
+        client_builder = client_builder.default_headers(headers);
+    }
+
+    Ok(client_builder.build()?)
+}
+
+/// Whether a status redirects to another URL: a 3xx other than `304 Not Modified`, which
+/// answers a conditional request (e.g. `If-None-Match`) with the cached copy instead.
+pub fn is_redirect(status: reqwest::StatusCode) -> bool {
+    status.is_redirection() && status != reqwest::StatusCode::NOT_MODIFIED
+}
+
+/// Whether a response was served from a cache rather than the origin: a `304 Not
+/// Modified`, a cache status header (`X-Cache`, `X-Cache-Status`, `CF-Cache-Status`)
+/// reporting a hit, or an `Age` greater than 0.
+pub fn is_cache_hit(status: reqwest::StatusCode, headers: &HeaderMap) -> bool {
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        return true;
+    }
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let reports_hit = ["x-cache", "x-cache-status", "cf-cache-status"]
+        .iter()
+        .filter_map(|name| header(name))
+        .any(|value| value.to_ascii_lowercase().contains("hit"));
+    let aged = header("age")
+        .and_then(|age| age.trim().parse::<u64>().ok())
+        .is_some_and(|age| age > 0);
+    reports_hit || aged
+}
+
+/// The longest `Retry-After` wait honored before a retry. A server asking to wait longer
+/// isn't retried, so a `Retry-After: 86400` doesn't hold up the run for a day.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Parses a `Retry-After` header value, either a number of seconds or an HTTP date.
+/// A date in the past results in no wait.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?;
+    let at = Duration::from_secs(u64::try_from(date.timestamp()).ok()?);
+    Some(at.saturating_sub(now))
+}
+
+/// The redirects followed by a request, see [`redirect_policy`].
+#[derive(Debug, Default, Clone, Copy)]
+struct Redirects {
+    count: usize,
+    looped: bool,
+    /// The status of the last redirect response, kept for a chain that is cut off.
+    last_status: Option<reqwest::StatusCode>,
+}
+
+tokio::task_local! {
+    /// The redirects of the request in flight. reqwest runs the redirect policy while
+    /// the request future is polled, so the policy records them here for
+    /// [`get_url_response`].
+    static REDIRECTS: Cell<Redirects>;
+}
+
+/// Records a redirect of the request in flight, if it is tracked.
+fn record_redirect(update: impl FnOnce(&mut Redirects)) {
+    let _ = REDIRECTS.try_with(|cell| {
+        let mut redirects = cell.get();
+        update(&mut redirects);
+        cell.set(redirects);
+    });
+}
+
+/// Follows up to `max` redirects. A longer chain is an error. With `same_host_only`
+/// (`--same-host-redirects-only`), a redirect to another host than the one of the
+/// requested URL is not followed, so the 3xx response becomes the result.
+///
+/// A redirect back to a URL of the chain is a loop. It's not followed, but recorded
+/// along with the number of followed redirects.
+fn redirect_policy(max: usize, same_host_only: bool) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().contains(attempt.url()) {
+            record_redirect(|r| r.looped = true);
+            return attempt.stop();
+        }
+        if attempt.previous().len() > max {
+            let status = attempt.status();
+            record_redirect(|r| r.last_status = Some(status));
+            return attempt.error("too many redirects");
+        }
+        let original_host = attempt.previous().first().and_then(|url| url.host_str());
+        if same_host_only && attempt.url().host_str() != original_host {
+            return attempt.stop();
+        }
+        record_redirect(|r| r.count += 1);
+        attempt.follow()
+    })
+}
+
+/// Logs in by POSTing the `--login-data` form fields to `--login-url`.
+///
+/// The session cookies set by the response are stored in the client's cookie jar
+/// and sent with all later requests. A redirect (e.g. to a dashboard) counts as a
+/// successful login; a 4xx/5xx response or a network error is reported as failure.
+pub async fn login(
+    client: &reqwest::Client,
+    login_url: &url::Url,
+    fields: &[LoginField],
+) -> Result<(), String> {
+    let form: Vec<(&str, &str)> = fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.value.as_str()))
+        .collect();
+    let response = client
+        .post(login_url.as_str())
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| format!("Login failed: {}", e))?;
+
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        return Err(format!(
+            "Login failed: {} returned {}. Check --login-data.",
+            login_url, status
+        ));
+    }
+    Ok(())
+}
+
+/// Builds the headers sent with every request:
+/// - `Authorization` from `--basic-auth` or `--bearer-token`.
+/// - `Cache-Control: no-cache` for `--append-timestamp` with `--cache-bust-header`.
+/// - `Accept` from `--accept`, or `application/json` with `--json-api`.
+/// - The `--header` headers, overriding any of the above.
+/// - `Connection: close` with `--no-keepalive`.
+pub fn build_default_headers(options: &Cli) -> Result<HeaderMap, Box<dyn Error>> {
+    let mut headers = HeaderMap::new();
 
     if let Some(auth) = &options.basic_auth {
         if !auth.is_empty() {
@@ -36,94 +234,422 @@ pub fn build_client(options: &Cli) -> Result<reqwest::Client, Box<dyn Error>> {
         }
     }
 
+    if let Some(token) = &options.bearer_token {
+        let auth_value = format!("Bearer {}", token).parse()?;
+        headers.insert(reqwest::header::AUTHORIZATION, auth_value);
+    }
+
+    // Set before the custom headers, so `-H 'Accept: ...'` takes precedence.
+    if options.append_timestamp && options.cache_bust_header {
+        headers.insert(
+            reqwest::header::CACHE_CONTROL,
+            HeaderValue::from_static("no-cache"),
+        );
+    }
+    if options.json_api {
+        headers.insert(
+            reqwest::header::ACCEPT,
+            HeaderValue::from_static("application/json"),
+        );
+    } else if let Some(accept) = &options.accept {
+        headers.insert(reqwest::header::ACCEPT, accept.parse()?);
+    }
+
     for header_str in &options.headers {
         if let Some(colon_pos) = header_str.find(':') {
             let name = header_str[..colon_pos].trim();
             let value = header_str[colon_pos + 1..].trim();
             if let (Ok(header_name), Ok(header_value)) = (
                 reqwest::header::HeaderName::from_bytes(name.as_bytes()),
-                reqwest::header::HeaderValue::from_str(value),
+                HeaderValue::from_str(value),
             ) {
                 headers.insert(header_name, header_value);
             }
         }
     }
 
-    if !headers.is_empty() {
-        client_builder = client_builder.default_headers(headers);
+    if options.no_keepalive {
+        headers.insert(
+            reqwest::header::CONNECTION,
+            HeaderValue::from_static("close"),
+        );
     }
 
-    Ok(client_builder.build()?)
+    Ok(headers)
 }
 
-/// Fetches the content of a given URL as a `String`.
-///
-/// This function sends a GET request to the specified URL using the provided
-/// asynchronous HTTP client (`reqwest::Client`). It ensures the response has
-/// a successful HTTP status code, then retrieves the response body as text
-/// and returns it.
-///
-/// # Arguments
-///
-/// * `url` - A string slice that holds the URL to be fetched.
-/// * `client` - A reference to a `reqwest::Client` used to perform the HTTP request.
-///
-/// # Returns
-///
-/// On success, returns a `Result` containing the content of the URL as a `String`.
-/// On failure, returns a `reqwest::Error` wrapped in a `Result::Err`.
-///
-/// # Errors
+/// Returns all headers siteprobe sets on each request, including the `User-Agent`.
+/// Used to record the request side when `--dump-headers` is enabled.
+pub fn build_request_headers(options: &Cli) -> Result<HeaderMap, Box<dyn Error>> {
+    let mut headers = build_default_headers(options)?;
+    headers.insert(
+        reqwest::header::USER_AGENT,
+        HeaderValue::from_str(&options.user_agent)?,
+    );
+    Ok(headers)
+}
+
+/// Converts a `HeaderMap` into a JSON object, replacing the values of headers listed
+/// in `redact` with `[REDACTED]`. Repeated headers are joined with `, `.
+pub fn headers_to_json(headers: &HeaderMap, redact: &[String]) -> serde_json::Value {
+    let mut map: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in headers {
+        let value = if redact.iter().any(|r| name.as_str().eq_ignore_ascii_case(r)) {
+            "[REDACTED]".to_string()
+        } else {
+            String::from_utf8_lossy(value.as_bytes()).into_owned()
+        };
+        map.entry(name.as_str().to_string())
+            .and_modify(|existing| {
+                existing.push_str(", ");
+                existing.push_str(&value);
+            })
+            .or_insert(value);
+    }
+    serde_json::json!(map)
+}
+
+/// What [`read_body`] learned about a response body.
+#[derive(Debug, Default)]
+struct Body {
+    /// Number of bytes received, at most the cap.
+    size: usize,
+    /// Whether the body was cut off at `--max-body-size`.
+    truncated: bool,
+    /// The body itself, only kept when it's stored with `--output-dir`.
+    bytes: Option<Vec<u8>>,
+    /// SHA-256 hash of the body (hex), only computed with `--hash-bodies`.
+    hash: Option<String>,
+}
+
+/// Streams the body chunk by chunk, counting (and optionally hashing) the bytes as
+/// they arrive. The bytes are only held in memory when `keep` is set, so memory use
+/// doesn't grow with concurrency × page size. Reading stops once `max` bytes arrived.
+/// A failed read ends the body early.
+async fn read_body(
+    mut resp: reqwest::Response,
+    max: Option<usize>,
+    keep: bool,
+    hash: bool,
+) -> Body {
+    let max = max.unwrap_or(usize::MAX);
+    let mut hasher = hash.then(Sha256::new);
+    let mut body = Body {
+        bytes: keep.then(Vec::new),
+        ..Default::default()
+    };
+    while let Ok(Some(mut chunk)) = resp.chunk().await {
+        if body.size + chunk.len() > max {
+            chunk.truncate(max - body.size);
+            body.truncated = true;
+        }
+        body.size += chunk.len();
+        if let Some(hasher) = hasher.as_mut() {
+            hasher.update(&chunk);
+        }
+        if let Some(bytes) = body.bytes.as_mut() {
+            bytes.extend_from_slice(&chunk);
+        }
+        if body.truncated {
+            break;
+        }
+    }
+    body.hash = hasher.map(|h| format!("{:x}", h.finalize()));
+    body
+}
+
+/// What the body of a 2xx response must contain: every `--expect-contains` marker,
+/// and a match for every `--expect-matches` pattern. A 200 containing a
+/// `--soft-404-marker` is a soft 404.
+#[derive(Debug, Clone, Default)]
+pub struct ContentChecks {
+    pub contains: Vec<String>,
+    pub matches: Vec<Regex>,
+    pub soft_404_markers: Vec<String>,
+}
+
+impl ContentChecks {
+    pub fn is_empty(&self) -> bool {
+        self.contains.is_empty() && self.matches.is_empty() && self.soft_404_markers.is_empty()
+    }
+
+    /// Whether `body` contains any of the `--soft-404-marker` strings.
+    pub fn is_soft_404(&self, body: &[u8]) -> bool {
+        self.soft_404_markers
+            .iter()
+            .any(|marker| contains_bytes(body, marker.as_bytes()))
+    }
+
+    /// Returns the markers missing from `body`, and the patterns without a match.
+    pub fn check(&self, body: &[u8]) -> (Vec<String>, Vec<String>) {
+        let missing = self
+            .contains
+            .iter()
+            .filter(|marker| !contains_bytes(body, marker.as_bytes()))
+            .cloned()
+            .collect();
+        let unmatched = if self.matches.is_empty() {
+            Vec::new()
+        } else {
+            let text = String::from_utf8_lossy(body);
+            self.matches
+                .iter()
+                .filter(|pattern| !pattern.is_match(&text))
+                .map(|pattern| pattern.to_string())
+                .collect()
+        };
+        (missing, unmatched)
+    }
+}
+
+/// Whether `needle` occurs in `haystack`. An empty needle is always found.
+fn contains_bytes(haystack: &[u8], needle: &[u8]) -> bool {
+    needle.is_empty()
+        || haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+}
+
+/// The size the server declared in its `Content-Length` header, if any.
 ///
-/// Fetches the content at the specified URL using the given HTTP client.
+/// This is what `response_size` reports when present, so a body the server cut short
+/// still shows its full size. Without it (e.g. chunked responses), the size falls back
+/// to the number of bytes received.
+pub fn content_length(headers: &HeaderMap) -> Option<usize> {
+    headers
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Response headers recorded for every URL and listed per response with
+/// `--capture-headers`, to debug CDN and caching behavior.
+pub const CAPTURED_HEADERS: [&str; 4] = ["server", "cache-control", "content-type", "x-cache"];
+
+/// Picks the [`CAPTURED_HEADERS`] present in `headers`. Repeated headers are joined
+/// with `, `.
+pub fn captured_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    CAPTURED_HEADERS
+        .iter()
+        .filter_map(|&name| {
+            let values: Vec<_> = headers
+                .get_all(name)
+                .iter()
+                .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned())
+                .collect();
+            (!values.is_empty()).then(|| (name.to_string(), values.join(", ")))
+        })
+        .collect()
+}
+
+/// Fetches the given URL and measures the response.
 ///
 /// This asynchronous function makes a GET request to the specified URL and captures:
 /// - The HTTP status code of the response.
-/// - The size of the response content (in bytes).
-/// - The total duration it took to complete the request.
+/// - The size of the response content (in bytes): the `Content-Length` header when
+///   present, otherwise the number of bytes received.
+/// - The total duration it took to complete the request, and the time to first byte.
 ///
 /// # Parameters
-/// - `url`: A string slice representing the URL to fetch.
-/// - `client`: A reference to a `reqwest::Client` instance used to perform the request.
+/// - `url`: The URL to fetch, including a cache buster added by `--append-timestamp`.
+/// - `client`: The `reqwest::Client` used to perform the request. Its redirect policy
+///   decides whether redirects are followed.
+/// - `head`: Send a HEAD request instead (`--head`). There is no body, so the size is
+///   taken from the `Content-Length` header, or 0 without one.
+/// - `max_body_size`: Stop reading the body after this many bytes (`--max-body-size`).
+///   The response is flagged as `truncated` and its size is the cap.
+/// - `output_dir`: Where the body, and with `--store-headers` the response headers, are
+///   stored (`--output-dir`). A storage failure is recorded in `storage_error`, the
+///   request itself still succeeds.
+/// - `dump_headers`: Keep all response headers in `response_headers`, to print them with
+///   `--dump-headers`.
+/// - `hash_body`: Compute the SHA-256 hash of the body into `body_hash`
+///   (`--hash-bodies`).
+/// - `content_checks`: What the body of a 2xx response must contain, see
+///   [`ContentChecks`].
+/// - `timeout`: The timeout of this request, overriding the client's
+///   `--request-timeout`, e.g. from a matching `--timeout-override` pattern.
 ///
 /// # Returns
-/// Returns a `Result` containing a [`Response`](crate::report::Response) struct with the
-/// request metadata on success, or a boxed error (`Box<dyn Error + Send + Sync>`) on failure.
+/// Returns a `Result` containing a [`Response`] struct with the request metadata on
+/// success, or the `reqwest::Error` on failure.
 ///
 /// # Error Handling
-/// In case of an HTTP error, such as connection issues, request timeouts, or client-related
-/// errors (e.g., malformed request), this function returns standardized HTTP status codes
-/// (e.g., 408 for timeout, 502 for connection errors, etc.).
-/// Any unexpected errors are propagated as `Err(Box<dyn Error + Send + Sync>)`.
+/// A request that fails without a response is still returned as a `Response`, with its
+/// `error` set: a timeout as 408, a connection error as 502, a malformed request as 400,
+/// and a redirect chain longer than `--max-redirects` with the status of its last
+/// redirect. Any other error is returned as `Err`.
+#[allow(clippy::too_many_arguments)]
 pub async fn get_url_response(
     url: &str,
     client: &reqwest::Client,
-    output_dir: &Option<PathBuf>,
+    head: bool,
+    max_body_size: Option<u64>,
+    output_dir: &Option<OutputDir>,
+    dump_headers: bool,
+    hash_body: bool,
+    content_checks: &ContentChecks,
+    timeout: Option<Duration>,
 ) -> Result<Response, reqwest::Error> {
     let start_time = tokio::time::Instant::now();
-    let response = client.get(url).send().await;
+    let mut request = if head {
+        client.head(url)
+    } else {
+        client.get(url)
+    };
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    let (response, redirects) = REDIRECTS
+        .scope(Cell::default(), async {
+            let response = request.send().await;
+            (response, REDIRECTS.with(Cell::get))
+        })
+        .await;
+    // `send` returns once the response headers arrived, before the body is read.
+    let ttfb = start_time.elapsed();
+    let requested_url = url.to_string();
 
-    let (status, url, content_length, body) = match response {
+    let mut redirected = false;
+    let mut cache_hit = false;
+    let mut redirect_target = None;
+    let mut content_type = None;
+    let mut captured = BTreeMap::new();
+    let mut retry_after = None;
+    let mut body = Body::default();
+    let mut stored_headers = None;
+    let mut missing_markers = Vec::new();
+    let mut unmatched_patterns = Vec::new();
+    let mut soft_404 = false;
+    let mut error = None;
+    let (status, url, headers) = match response {
         Ok(resp) => {
-            let url = Some(resp.url().clone());
             let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            (status, url, body.len(), Some(body))
+            // With `--follow-redirects`, reqwest only reports the final response, so a
+            // redirect shows as a changed URL.
+            redirected = is_redirect(status)
+                || url::Url::parse(url).is_ok_and(|requested| &requested != resp.url());
+            if is_redirect(status) {
+                redirect_target = resp
+                    .headers()
+                    .get(reqwest::header::LOCATION)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|location| resp.url().join(location).ok())
+                    .map(String::from);
+            }
+            let url = Some(resp.url().clone());
+            content_type = resp
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+            cache_hit = is_cache_hit(status, resp.headers());
+            captured = captured_headers(resp.headers());
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || status == reqwest::StatusCode::SERVICE_UNAVAILABLE
+            {
+                retry_after = resp
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_retry_after);
+            }
+            let headers = dump_headers.then(|| resp.headers().clone());
+            // With `--store-errors-only`, the body of a successful response isn't kept.
+            let output_dir = output_dir.as_ref().filter(|o| o.stores(status));
+            if output_dir.is_some_and(|o| o.store_headers) {
+                stored_headers = Some(format_headers(resp.version(), status, resp.headers()));
+            }
+            let declared_length = content_length(resp.headers());
+            if head {
+                body.size = declared_length.unwrap_or(0);
+            } else {
+                let max = max_body_size.map(|max| usize::try_from(max).unwrap_or(usize::MAX));
+                let check_content = status.is_success() && !content_checks.is_empty();
+                let keep = output_dir.is_some() || check_content;
+                body = read_body(resp, max, keep, hash_body).await;
+                if !body.truncated {
+                    body.size = declared_length.unwrap_or(body.size);
+                }
+                if check_content {
+                    let bytes = body.bytes.as_deref().unwrap_or_default();
+                    (missing_markers, unmatched_patterns) = content_checks.check(bytes);
+                    soft_404 =
+                        status == reqwest::StatusCode::OK && content_checks.is_soft_404(bytes);
+                }
+            }
+            (status, url, headers)
+        }
+        Err(e) if e.is_timeout() => {
+            error = Some(e.to_string());
+            (reqwest::StatusCode::REQUEST_TIMEOUT, None, None)
+        }
+        Err(e) if e.is_connect() => {
+            error = Some(e.to_string());
+            (reqwest::StatusCode::BAD_GATEWAY, None, None)
+        }
+        Err(e) if e.is_request() => {
+            error = Some(e.to_string());
+            (reqwest::StatusCode::BAD_REQUEST, None, None)
+        }
+        // A chain longer than --max-redirects ends at its last redirect.
+        Err(e) if e.is_redirect() => {
+            let Some(status) = redirects.last_status else {
+                return Err(e);
+            };
+            redirected = true;
+            error = Some(e.to_string());
+            (status, None, None)
         }
-        Err(e) if e.is_timeout() => (reqwest::StatusCode::REQUEST_TIMEOUT, None, 0, None),
-        Err(e) if e.is_connect() => (reqwest::StatusCode::BAD_GATEWAY, None, 0, None),
-        Err(e) if e.is_request() => (reqwest::StatusCode::BAD_REQUEST, None, 0, None),
         Err(e) => return Err(e),
     };
 
+    let body_hash = hash_body.then(|| {
+        body.hash
+            .take()
+            .unwrap_or_else(|| format!("{:x}", Sha256::digest([])))
+    });
+
+    let mut storage_error = None;
+    let output_dir = output_dir.as_ref().filter(|o| o.stores(status));
     if let (Some(output_dir), Some(url_ref)) = (output_dir, url.as_ref()) {
-        store_response_on_disk(output_dir, url_ref, body.unwrap_or_default().as_str()).await;
+        let bytes = body.bytes.take().unwrap_or_default();
+        storage_error = store_response_on_disk(
+            output_dir,
+            url_ref,
+            content_type.as_deref(),
+            stored_headers.as_deref(),
+            &bytes,
+        )
+        .await
+        .err()
+        .map(|e| e.to_string());
     }
 
     Ok(Response {
         response_time: start_time.elapsed(),
-        response_size: content_length,
-        url: url.unwrap().to_string(),
+        ttfb,
+        response_size: body.size,
+        // Without a response, e.g. after a timeout, the requested URL is all there is.
+        url: url.map_or_else(|| requested_url.clone(), String::from),
+        requested_url,
         status_code: status,
+        response_headers: headers,
+        redirected,
+        redirect_target,
+        redirect_count: redirects.count,
+        redirect_loop: redirects.looped,
+        cache_hit,
+        content_type,
+        body_hash,
+        headers: captured,
+        retry_after,
+        truncated: body.truncated,
+        storage_error,
+        missing_markers,
+        unmatched_patterns,
+        soft_404,
+        error,
+        ..Default::default()
     })
 }
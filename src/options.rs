@@ -1,8 +1,15 @@
-use crate::utils::validate_basic_auth;
+use crate::sitemap::parse_lastmod;
+#[cfg(feature = "cli")]
+use crate::utils::{validate_basic_auth, validate_query_param_name};
+use crate::warnings::Warnings;
+use chrono::{DateTime, FixedOffset};
+#[cfg(feature = "cli")]
 use clap::{value_parser, Parser, ValueHint};
+use regex::Regex;
 use serde::Deserialize;
 use std::fs;
-use std::path::PathBuf;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 use url::Url;
 
 /// Validates that a header string is in the format "Name: Value" with a non-empty name.
@@ -17,6 +24,225 @@ pub fn validate_header(s: &str) -> Result<String, String> {
     Ok(s.to_string())
 }
 
+/// A form field for `--login-data`, in the format `name=value`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginField {
+    pub name: String,
+    pub value: String,
+}
+
+/// Parses a `name=value` login form field. The value may contain `=`.
+pub fn parse_login_field(s: &str) -> Result<LoginField, String> {
+    let Some((name, value)) = s.split_once('=') else {
+        return Err("Login data must be in the format 'name=value'".to_string());
+    };
+    if name.trim().is_empty() {
+        return Err("Login field name must not be empty".to_string());
+    }
+    Ok(LoginField {
+        name: name.trim().to_string(),
+        value: value.to_string(),
+    })
+}
+
+/// A DNS override from `--resolve <host:port:ip>`, in the format of curl's `--resolve`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolveOverride {
+    pub host: String,
+    pub port: u16,
+    pub ip: IpAddr,
+}
+
+/// Parses a `host:port:ip` DNS override. IPv6 addresses may be wrapped in brackets,
+/// e.g. `example.com:443:[::1]`.
+pub fn parse_resolve_override(s: &str) -> Result<ResolveOverride, String> {
+    let mut parts = s.splitn(3, ':');
+    let (Some(host), Some(port), Some(ip)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err("Resolve override must be in the format 'host:port:ip'".to_string());
+    };
+    if host.trim().is_empty() {
+        return Err("Resolve override host must not be empty".to_string());
+    }
+    let port = port
+        .parse()
+        .map_err(|_| format!("Invalid port '{}' in resolve override", port))?;
+    let ip = ip
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .map_err(|_| format!("Invalid IP address '{}' in resolve override", ip))?;
+    Ok(ResolveOverride {
+        host: host.trim().to_lowercase(),
+        port,
+        ip,
+    })
+}
+
+/// A per-URL timeout override from `--timeout-override <pattern=seconds>`.
+#[derive(Debug, Clone)]
+pub struct TimeoutOverride {
+    pub pattern: Regex,
+    pub seconds: u64,
+}
+
+/// Parses a `pattern=seconds` timeout override. The pattern is a regular expression
+/// matched against the URL; it is split at the last `=` so patterns may contain `=`.
+pub fn parse_timeout_override(s: &str) -> Result<TimeoutOverride, String> {
+    let Some((pattern, seconds)) = s.rsplit_once('=') else {
+        return Err("Timeout override must be in the format 'pattern=seconds'".to_string());
+    };
+    let seconds: u64 = seconds
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid number of seconds.", seconds.trim()))?;
+    if seconds == 0 {
+        return Err("Timeout override must be at least 1 second".to_string());
+    }
+    let pattern =
+        Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+    Ok(TimeoutOverride { pattern, seconds })
+}
+
+/// Parses a regular expression: `--include`/`--exclude`, matched against the URL, or
+/// `--expect-matches`, matched against the body.
+pub fn parse_url_pattern(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))
+}
+
+/// Where the URLs to probe come from, given as the positional argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SitemapSource {
+    /// A sitemap, or a plain-text URL list, fetched from this URL. Local files are
+    /// given as `file://` URLs.
+    Url(Url),
+    /// A plain-text URL list read from stdin, given as `-`.
+    Stdin,
+}
+
+impl std::fmt::Display for SitemapSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SitemapSource::Url(url) => write!(f, "{}", url),
+            SitemapSource::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+/// Parses the positional argument: `-` for stdin, the path of an existing file, or
+/// a URL. A path is turned into an absolute `file://` URL.
+pub fn parse_sitemap_source(value: &str) -> Result<SitemapSource, String> {
+    if value == "-" {
+        return Ok(SitemapSource::Stdin);
+    }
+    let path = expand_path(value)?;
+    if path.is_file() {
+        let path = std::fs::canonicalize(&path)
+            .map_err(|e| format!("Unable to resolve '{}': {}", value, e))?;
+        return Url::from_file_path(&path)
+            .map(SitemapSource::Url)
+            .map_err(|_| format!("Unable to use '{}' as a file URL", path.display()));
+    }
+    Url::parse(value).map(SitemapSource::Url).map_err(|e| {
+        format!(
+            "'{}' is neither a URL, an existing file nor `-` for stdin: {}",
+            value, e
+        )
+    })
+}
+
+/// How `--flag-empty-body` treats 2xx responses with an empty body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum EmptyBodyMode {
+    /// List them as "Empty Responses" in the report.
+    Warn,
+    /// List them and count them as errors for the exit code.
+    Error,
+}
+
+/// How `--output-dir` arranges the stored documents, selected with `--storage-layout`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum StorageLayout {
+    /// One `.html` file per URL, named after its path: `/a/b/c` as `a/b/c.html`.
+    #[default]
+    Flat,
+    /// Every URL path as a directory with an `index.html`: `/a/b/c` as
+    /// `a/b/c/index.html`, like a web server's document root.
+    Mirror,
+}
+
+/// A block of the text report, selected with `--sections`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ReportSection {
+    /// Base metrics and status codes.
+    Summary,
+    /// Response time and performance statistics, including the distribution.
+    Performance,
+    /// Duplicate URLs and the URL structure of the sitemap(s).
+    Sitemap,
+    /// Error, empty and non-JSON responses, and excessive redirects.
+    Errors,
+    /// Slow responses.
+    Slow,
+}
+
+/// A report file written to `--report-dir`, selected with `--report-formats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ReportFormat {
+    /// `report.csv`, like --report-path
+    Csv,
+    /// `report.json`, like --report-path-json
+    Json,
+    /// `report.jsonl`, like --report-path-jsonl
+    Jsonl,
+    /// `report.html`, like --report-path-html
+    Html,
+    /// `report.junit.xml`, like --report-path-junit
+    Junit,
+    /// `report.md`, like --report-path-md
+    Md,
+}
+
+/// Validates a header name (without value) and normalizes it to lowercase.
+pub fn validate_header_name(s: &str) -> Result<String, String> {
+    let name = s.trim();
+    if name.is_empty() {
+        return Err("Header name must not be empty".to_string());
+    }
+    reqwest::header::HeaderName::from_bytes(name.as_bytes())
+        .map(|h| h.as_str().to_string())
+        .map_err(|_| format!("'{}' is not a valid header name", name))
+}
+
+/// Validates a `--bearer-token`, which must be usable as a header value.
+pub fn validate_bearer_token(s: &str) -> Result<String, String> {
+    let token = s.trim();
+    if token.is_empty() {
+        return Err("Bearer token must not be empty".to_string());
+    }
+    reqwest::header::HeaderValue::from_str(token)
+        .map(|_| token.to_string())
+        .map_err(|_| "Bearer token contains invalid characters".to_string())
+}
+
+/// Validates a host name for `--allow-host`/`--deny-host` and normalizes it to lowercase.
+pub fn validate_host(s: &str) -> Result<String, String> {
+    let host = s.trim();
+    if host.is_empty() {
+        return Err("Host must not be empty".to_string());
+    }
+    if host.contains("://") || host.contains('/') {
+        return Err(format!(
+            "'{}' is not a host name. Pass only the host, e.g. 'example.com'.",
+            host
+        ));
+    }
+    Ok(host.to_lowercase())
+}
+
 /// Default values used throughout the project.
 pub mod defaults {
     /// Maximum number of concurrent network requests.
@@ -35,8 +261,42 @@ pub mod defaults {
     /// The maximum number of slow documents to show
     pub const SLOW_NUM: u32 = 100;
 
+    /// The number of deepest and longest URLs listed with `--url-structure`.
+    pub const URL_STRUCTURE_NUM: usize = 10;
+
+    /// The default query parameter name of the `--append-timestamp` cache buster.
+    pub const CACHE_BUST_PARAM: &str = "ts";
+
+    /// The default number of redirects followed with `--follow-redirects`.
+    pub const MAX_REDIRECTS: usize = 10;
+
     /// The default number of retries for failed requests.
     pub const RETRIES: u8 = 0;
+
+    /// The default pause before the first retry, in milliseconds.
+    pub const RETRY_BACKOFF_MS: u64 = 1000;
+
+    /// The default number of times each URL is probed.
+    pub const REPEAT: u32 = 1;
+
+    /// The text report width used when it can't be detected from the terminal.
+    pub const REPORT_WIDTH: u16 = 80;
+
+    /// Columns reserved for the status code and response time next to a URL in the
+    /// text report, when the URL width is derived from the terminal width.
+    pub const URL_TRUNCATE_MARGIN: usize = 30;
+
+    /// The minimum URL width derived from the terminal width.
+    pub const URL_TRUNCATE_MIN: usize = 20;
+
+    /// The default bucket size for throughput over time, in seconds.
+    pub const THROUGHPUT_INTERVAL: u64 = 1;
+
+    /// The default number of warm-up requests per URL.
+    pub const WARMUP: u32 = 0;
+
+    /// The response time change, in milliseconds, reported by `siteprobe compare`.
+    pub const COMPARE_THRESHOLD_MS: u64 = 250;
 }
 
 /// Expands shell-style tilde (`~`) in paths to the user's home directory.
@@ -44,21 +304,15 @@ pub fn expand_path(s: &str) -> Result<PathBuf, String> {
     Ok(PathBuf::from(shellexpand::tilde(s).into_owned()))
 }
 
-fn validate_output_dir_str(s: &str) -> Result<PathBuf, String> {
+pub fn validate_output_dir_str(s: &str) -> Result<PathBuf, String> {
     let path = expand_path(s)?;
-    if path.exists() && path.is_dir() {
-        println!(
-            "\n⚠️ The output directory '{}' already exists. Existing documents will be overwritten.\n",
-            path.display()
-        );
-        Ok(path)
-    } else if path.exists() && !path.is_dir() {
+    // The directory itself is created on the first write, see `store_response_on_disk`.
+    if path.exists() && !path.is_dir() {
         Err(format!(
             "❌ The output path '{}' is not a directory or is not writable.",
             path.display()
         ))
     } else {
-        fs::create_dir_all(&path).map_err(|e| format!("Failed to create directory: {}", e))?;
         Ok(path)
     }
 }
@@ -114,7 +368,7 @@ pub fn parse_rate_limit(value: &str) -> Result<u32, String> {
     Ok(requests_per_minute)
 }
 
-fn parse_slow_threshold(value: &str) -> Result<f64, String> {
+pub fn parse_slow_threshold(value: &str) -> Result<f64, String> {
     let parsed: f64 = value
         .parse()
         .map_err(|_| format!("'{}' is not a valid number.", value))?;
@@ -127,160 +381,1129 @@ fn parse_slow_threshold(value: &str) -> Result<f64, String> {
     Ok(parsed)
 }
 
-#[derive(Debug, Parser)]
-#[command(
-    term_width = 80,
-    version,
-    after_help = "\
+/// Parses a percentage from 0 to 100, e.g. for `--min-success-rate`.
+pub fn parse_percent(value: &str) -> Result<f64, String> {
+    let parsed: f64 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid number.", value))?;
+    if !(0.0..=100.0).contains(&parsed) {
+        return Err(format!("Value '{}' must be between 0 and 100.", value));
+    }
+    Ok(parsed)
+}
+
+/// Parses `--url-truncate`: 0 to disable truncation, or a width of at least 10 columns.
+pub fn parse_url_truncate(value: &str) -> Result<usize, String> {
+    let cols: usize = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid number.", value))?;
+    if cols != 0 && cols < 10 {
+        return Err(format!(
+            "Value '{}' must be 0 (no truncation) or at least 10.",
+            value
+        ));
+    }
+    Ok(cols)
+}
+
+/// Parses `--modified-since`: a date (`2024-01-01`) or an RFC 3339 date and time
+/// (`2024-01-01T12:00:00+02:00`), like a sitemap `<lastmod>`.
+pub fn parse_modified_since(value: &str) -> Result<DateTime<FixedOffset>, String> {
+    parse_lastmod(value).ok_or_else(|| {
+        format!(
+            "'{}' is not a valid date. Use e.g. '2024-01-01' or '2024-01-01T12:00:00+00:00'.",
+            value
+        )
+    })
+}
+
+/// Returns the version line printed by `--version`. With `verbose`, the git commit,
+/// build date, rustc version and enabled cargo features are included.
+pub fn version_info(verbose: bool) -> String {
+    let version = format!("siteprobe {}", env!("CARGO_PKG_VERSION"));
+    if !verbose {
+        return version;
+    }
+    format!(
+        "{}\n\
+         commit:   {}\n\
+         built:    {}\n\
+         rustc:    {}\n\
+         features: {}",
+        version,
+        env!("SITEPROBE_GIT_COMMIT"),
+        env!("SITEPROBE_BUILD_DATE"),
+        env!("SITEPROBE_RUSTC_VERSION"),
+        env!("SITEPROBE_FEATURES"),
+    )
+}
+
+/// The exit codes and their meaning, shown in `--help` and by `--explain-exit-codes`.
+pub const EXIT_CODES_HELP: &str = "\
 EXIT CODES:\n\
-    0  All URLs returned 2xx (success)\n\
-    1  One or more URLs returned 4xx/5xx or failed\n\
-    2  One or more URLs exceeded the slow threshold (--slow-threshold)"
+    0    All URLs returned 2xx (success)\n\
+    1    One or more URLs returned 4xx/5xx or failed\n\
+    2    One or more URLs exceeded the slow threshold (--slow-threshold)\n\
+    3    The success rate is below --min-success-rate\n\
+    4    A --max-p95-ms, --max-avg-ms or --max-error-rate budget was exceeded\n\
+    130  The run was interrupted with Ctrl-C before probing\n\
+\n\
+A run interrupted with Ctrl-C while probing exits with the code of the completed\n\
+requests (partial report).";
+
+// No doc comment here: clap would show it as the `--help` description. Without the
+// `cli` feature, library users can start from `Cli::default()` instead.
+#[derive(Debug)]
+#[cfg_attr(feature = "cli", derive(Parser))]
+#[cfg_attr(
+    feature = "cli",
+    command(
+        term_width = 80,
+        version,
+        disable_version_flag = true,
+        subcommand_negates_reqs = true,
+        args_conflicts_with_subcommands = true,
+        after_help = EXIT_CODES_HELP
+    )
 )]
 pub struct Cli {
-    #[arg(
-        help = "The URL of the sitemap to be fetched and processed.",
+    #[cfg_attr(feature = "cli", arg(
+        help = "The URL of the sitemap to be fetched and processed, or the path of a local sitemap file. A plain-text list of URLs (one per line, `#` comments ignored) works too, given as a URL, a file path, or `-` to read it from stdin. Given several, their URLs are merged into one report.",
+        value_name = "SITEMAP_URL",
         value_hint = ValueHint::Url,
-        value_parser = value_parser!(Url)
-    )]
-    pub sitemap_url: Url,
+        value_parser = parse_sitemap_source,
+        required_unless_present_any = ["explain_exit_codes", "version", "urls_file"]
+    ))]
+    pub sitemap_urls: Vec<SitemapSource>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Probe the URLs listed in this file, one per line, instead of fetching a sitemap. Empty lines and lines starting with `#` are ignored. E.g. the file written by --failures-path.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path,
+        conflicts_with = "sitemap_urls"
+    ))]
+    pub urls_file: Option<PathBuf>,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
         long,
         help = "Basic authentication credentials in the format `username:password`",
         value_parser = validate_basic_auth,
-    )]
+    ))]
     pub basic_auth: Option<String>,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Token sent as `Authorization: Bearer <TOKEN>` with every request.",
+        value_name = "TOKEN",
+        value_parser = validate_bearer_token,
+        conflicts_with = "basic_auth",
+    ))]
+    pub bearer_token: Option<String>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "URL of a login form. siteprobe POSTs --login-data to it before fetching the sitemap and keeps the session cookies for all requests.",
+        value_parser = value_parser!(Url),
+        value_hint = ValueHint::Url,
+    ))]
+    pub login_url: Option<Url>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Form field sent to --login-url (format: 'name=value'). Can be specified multiple times.",
+        value_parser = parse_login_field,
+        requires = "login_url",
+    ))]
+    pub login_data: Vec<LoginField>,
+
+    #[cfg_attr(feature = "cli", arg(
         short = 'H',
         long = "header",
         help = "Custom header to include in each request (format: 'Name: Value'). Can be specified multiple times.",
         value_parser = validate_header,
-    )]
+    ))]
     pub headers: Vec<String>,
 
-    #[arg(
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Value of the `Accept` header sent with each request, e.g. 'application/json'. An `Accept` header given with -H takes precedence."
+        )
+    )]
+    pub accept: Option<String>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Probe JSON API endpoints: sends `Accept: application/json` and lists the 2xx responses that are not served as JSON.",
+            conflicts_with = "accept"
+        )
+    )]
+    pub json_api: bool,
+
+    #[cfg_attr(feature = "cli", arg(
         short = 'c',
         long,
         help = "Maximum number of concurrent requests allowed",
         default_value_t = defaults::SEMAPHORE as u8,
         value_parser = clap::value_parser!(u8).range(1..=100)
-    )]
+    ))]
     pub concurrency_limit: u8,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Maximum number of child sitemaps fetched concurrently from a sitemap index. Defaults to --concurrency-limit.",
+        value_parser = clap::value_parser!(u8).range(1..=100)
+    ))]
+    pub sitemap_concurrency: Option<u8>,
+
+    #[cfg_attr(feature = "cli", arg(
         short = 'l',
         long,
         help = "The rate limit for all requests in the format 'requests/time[unit]', where unit can be seconds (`s`), minutes (`m`), or hours (`h`). E.g. '-l 300/5m' for 300 requests per 5 minutes, or '-l 100/1h' for 100 requests per hour.",
         value_parser = parse_rate_limit
-    )]
+    ))]
     pub rate_limit: Option<u32>, // Returns requests per 1 minute
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
         short = 'o',
         long,
         help = "Directory where all downloaded documents will be saved",
         value_hint = ValueHint::DirPath,
         value_parser = validate_output_dir_str
-    )]
+    ))]
     pub output_dir: Option<PathBuf>,
 
-    #[arg(
-        short = 'a',
-        long,
-        help = "Append a random timestamp to each URL to bypass caching mechanisms",
-        default_value = "false"
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_enum,
+            default_value_t = StorageLayout::Flat,
+            requires = "output_dir",
+            help = "How the documents are arranged in --output-dir: 'flat' stores /a/b/c as a/b/c.html, 'mirror' as a/b/c/index.html, recreating the URL structure for offline archiving."
+        )
+    )]
+    pub storage_layout: StorageLayout,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            requires = "output_dir",
+            help = "Store the status line and response headers of each document in --output-dir, in a `<name>.headers.txt` file next to it."
+        )
+    )]
+    pub store_headers: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            requires = "output_dir",
+            help = "Only store the documents of 4xx and 5xx responses in --output-dir, skipping the successful ones."
+        )
+    )]
+    pub store_errors_only: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            short = 'a',
+            long,
+            help = "Append a random timestamp to each URL to bypass caching mechanisms",
+            default_value = "false"
+        )
     )]
     pub append_timestamp: bool,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "The name of the query parameter --append-timestamp adds to each URL",
+        default_value_t = defaults::CACHE_BUST_PARAM.to_string(),
+        value_parser = validate_query_param_name,
+        requires = "append_timestamp"
+    ))]
+    pub cache_bust_param: String,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "With --append-timestamp, send a `Cache-Control: no-cache` header instead of adding a query parameter to each URL",
+            requires = "append_timestamp",
+            conflicts_with = "cache_bust_param"
+        )
+    )]
+    pub cache_bust_header: bool,
+
+    #[cfg_attr(feature = "cli", arg(
         short = 'r',
         long,
         help = "File path for storing the generated `report.csv`",
         value_hint = ValueHint::FilePath,
         value_parser = expand_path
-    )]
+    ))]
     pub report_path: Option<PathBuf>,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
         short = 'j',
         long,
         help = "File path for storing the generated `report.json`",
         value_hint = ValueHint::FilePath,
         value_parser = expand_path
-    )]
+    ))]
     pub report_path_json: Option<PathBuf>,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
         long,
         help = "File path for storing the generated `report.html`",
         value_hint = ValueHint::FilePath,
         value_parser = expand_path
-    )]
+    ))]
     pub report_path_html: Option<PathBuf>,
 
-    #[arg(
-        short = 't',
+    #[cfg_attr(feature = "cli", arg(
         long,
-        help = "Default timeout (in seconds) for each request",
-        default_value_t = defaults::TIMEOUT,
-        value_parser = clap::value_parser!(u64).range(1..)
+        help = "File path for a JSON Lines report: one JSON object per response, followed by a summary line. Lines are appended to an existing file, e.g. for ingestion into log systems.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path
+    ))]
+    pub report_path_jsonl: Option<PathBuf>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "File path for a JUnit XML report with a test case per URL, failing for 4xx/5xx responses. CI systems like Jenkins or GitLab show it as test results.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path
+    ))]
+    pub report_path_junit: Option<PathBuf>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "File path for a Markdown report with the summary, error and slow responses as tables, e.g. to paste into pull requests and issues.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path
+    ))]
+    pub report_path_md: Option<PathBuf>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "File path for the list of URLs that failed (4xx/5xx or network errors), one per line. Probe them again with --urls-file.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path
+    ))]
+    pub failures_path: Option<PathBuf>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Directory to write the reports selected with --report-formats to, as `report.csv`, `report.json`, etc. A path given with --report-path, --report-path-json, --report-path-jsonl, --report-path-junit, --report-path-md or --report-path-html takes precedence.",
+        value_hint = ValueHint::DirPath,
+        value_parser = expand_path
+    ))]
+    pub report_dir: Option<PathBuf>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            requires = "report_dir",
+            help = "Comma-separated list of reports to write to --report-dir. Defaults to 'csv,json,html'."
+        )
     )]
+    pub report_formats: Vec<ReportFormat>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "File path for a small JSON status (exit code, counts, timestamp) written at the end of every run, also when the run fails or is interrupted. Lets CI jobs check the result without parsing the full report.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path
+    ))]
+    pub status_file: Option<PathBuf>,
+
+    #[cfg_attr(feature = "cli", arg(
+        short = 't',
+        long,
+        help = "Default timeout (in seconds) for each request. Use 0 to disable the timeout; a request to an unresponsive server then never finishes, so only do this when the whole run is time-limited.",
+        default_value_t = defaults::TIMEOUT
+    ))]
     pub request_timeout: u64,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Maximum number of idle connections kept open per host. Defaults to unlimited; lower it to avoid holding many idle connections at high concurrency.",
+        value_parser = clap::value_parser!(u32).range(0..=1000)
+    ))]
+    pub pool_max_idle_per_host: Option<u32>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Time (in seconds) an idle connection is kept open for reuse [default: 90]",
+        value_parser = clap::value_parser!(u64).range(1..=3600)
+    ))]
+    pub pool_idle_timeout: Option<u64>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Disable HTTP keep-alive and open a new connection for every request. Useful to stress-test connection setup.",
+            default_value = "false"
+        )
+    )]
+    pub no_keepalive: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            visible_alias = "danger-accept-invalid-certs",
+            help = "Accept invalid TLS certificates, e.g. the self-signed certificate of a staging site. Never use this for production checks.",
+            default_value = "false"
+        )
+    )]
+    pub insecure: bool,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Resolve a host to the given IP instead of using DNS, in the format 'host:port:ip' (like curl). E.g. '--resolve example.com:443:203.0.113.7' to probe a new server before switching DNS, with the original Host header and TLS server name. Unlike curl, the override applies to all ports of the host. Can be specified multiple times.",
+        value_parser = parse_resolve_override
+    ))]
+    pub resolve: Vec<ResolveOverride>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Local IP address to send all requests from, e.g. on hosts with several network interfaces."
+        )
+    )]
+    pub local_address: Option<IpAddr>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long = "timeout-override",
+        help = "Use a different timeout for URLs matching a regular expression, in the format 'pattern=seconds'. E.g. '--timeout-override /export/=60'. Can be specified multiple times; the first matching pattern wins.",
+        value_parser = parse_timeout_override,
+    ))]
+    pub timeout_overrides: Vec<TimeoutOverride>,
+
+    #[cfg_attr(feature = "cli", arg(
         long,
         help = "Custom User-Agent header to be used in requests",
         default_value_t = defaults::USER_AGENT.to_string(),
-    )]
+    ))]
     pub user_agent: String,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
         long,
         help = "Limit the number of slow documents displayed in the report.",
         default_value_t = defaults::SLOW_NUM,
         value_parser = clap::value_parser!(u32).range(1..)
-    )]
+    ))]
     pub slow_num: u32,
 
-    #[arg(
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            help = "Comma-separated list of text report sections to show, e.g. 'summary,errors'. Shows all sections by default."
+        )
+    )]
+    pub sections: Vec<ReportSection>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Width (in columns) of the text report. Statistics tables are shown side by side when they fit, otherwise stacked. Defaults to the terminal width, or 80 when not attached to a terminal.",
+        value_parser = clap::value_parser!(u16).range(40..)
+    ))]
+    pub report_width: Option<u16>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        value_name = "COLS",
+        help = "Truncate URLs longer than this many columns in the error and slow response lists of the text report. Use 0 to never truncate. Defaults to fit the terminal width; URLs are not truncated when not attached to a terminal.",
+        value_parser = parse_url_truncate
+    ))]
+    pub url_truncate: Option<usize>,
+
+    #[cfg_attr(feature = "cli", arg(
         short = 's',
         long,
         help = "Show slow responses. The value is the threshold (in seconds) for considering a document as 'slow'. E.g. '-s 3' for 3 seconds or '-s 0.05' for 50ms.",
         value_parser = parse_slow_threshold,
-    )]
+    ))]
     pub slow_threshold: Option<f64>,
 
-    #[arg(
-        short = 'f',
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Group the slow responses by status class (2xx, 3xx, 4xx, 5xx), to tell slow but working URLs from slow and failing ones.",
+            requires = "slow_threshold"
+        )
+    )]
+    pub slow_by_status: bool,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Fail the run with exit code 3 if less than this percentage of URLs return 2xx, e.g. '99' to gate deploys on 99% healthy URLs. Takes priority over exit code 1.",
+        value_name = "PCT",
+        value_parser = parse_percent
+    ))]
+    pub min_success_rate: Option<f64>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Performance budget: fail the run with exit code 4 if the 95th percentile response time exceeds this many milliseconds.",
+            value_name = "MS"
+        )
+    )]
+    pub max_p95_ms: Option<u64>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Performance budget: fail the run with exit code 4 if the average response time exceeds this many milliseconds.",
+            value_name = "MS"
+        )
+    )]
+    pub max_avg_ms: Option<u64>,
+
+    #[cfg_attr(feature = "cli", arg(
         long,
-        help = "Controls automatic redirects. When enabled, the client will follow HTTP redirects (up to 10 by default). Note that for security, Basic Authentication credentials are intentionally not forwarded during redirects to prevent unintended credential exposure."
+        help = "Performance budget: fail the run with exit code 4 if more than this percentage of URLs return 4xx/5xx or fail, e.g. '1' for at most 1% errors. Takes priority over exit code 1.",
+        value_name = "PCT",
+        value_parser = parse_percent
+    ))]
+    pub max_error_rate: Option<f64>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            short = 'f',
+            long,
+            help = "Controls automatic redirects. When enabled, the client will follow HTTP redirects (up to 10 by default, see --max-redirects). Note that for security, Basic Authentication credentials and Bearer tokens are intentionally not forwarded during redirects to prevent unintended credential exposure."
+        )
     )]
     pub follow_redirects: bool,
 
-    #[arg(
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Follow redirects like --follow-redirects, but only within the host of the requested URL. A redirect to another host, e.g. an SSO login, is not followed; the redirect and its target are recorded as the result.",
+            default_value = "false"
+        )
+    )]
+    pub same_host_redirects_only: bool,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "The maximum number of redirects followed with --follow-redirects or --same-host-redirects-only. A longer redirect chain is reported as an error. 0 doesn't follow redirects at all.",
+        value_name = "N",
+        default_value_t = defaults::MAX_REDIRECTS
+    ))]
+    pub max_redirects: usize,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "List URLs that take more than N redirects to resolve under 'URLs with Excessive Redirects' in the report. Redirect loops are always listed.",
+            value_name = "N"
+        )
+    )]
+    pub warn_redirects: Option<usize>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Treat redirects as errors, for sitemaps whose URLs must be canonical. A 3xx response, or with --follow-redirects a URL that redirected, is listed under 'Error Responses' and fails the run with exit code 1."
+        )
+    )]
+    pub redirects_are_errors: bool,
+
+    #[cfg_attr(feature = "cli", arg(
         long,
         help = "Number of retries for failed requests (network errors or 5xx responses)",
         default_value_t = defaults::RETRIES,
         value_parser = clap::value_parser!(u8).range(0..=10)
-    )]
+    ))]
     pub retries: u8,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
         long,
-        help = "Output the JSON report to stdout instead of the normal table output. Suppresses all other console output for clean piping.",
-        default_value = "false"
+        help = "Pause before the first retry in milliseconds. It doubles with every further retry, plus a random jitter of up to 50%.",
+        value_name = "MS",
+        default_value_t = defaults::RETRY_BACKOFF_MS,
+        value_parser = clap::value_parser!(u64).range(0..=60_000)
+    ))]
+    pub retry_backoff_ms: u64,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Ignore the Retry-After header of 429 and 503 responses. By default, retries wait as long as the header asks, and 429 responses with the header are retried too. A response asking to wait more than 60 seconds is not retried.",
+            default_value = "false"
+        )
     )]
-    pub json: bool,
+    pub no_respect_retry_after: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            visible_alias = "fail-fast",
+            help = "Stop immediately when the first URL fails the run with exit code 1, e.g. with a 4xx/5xx, a failed request or a failed content check, aborting all pending and in-flight requests.",
+            default_value = "false"
+        )
+    )]
+    pub stop_on_first_error: bool,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Stop probing once the response bodies add up to more than this number of bytes. The report covers the requests completed until then.",
+        value_name = "BYTES",
+        value_parser = clap::value_parser!(u64).range(1..)
+    ))]
+    pub max_total_bytes: Option<u64>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Stop reading a response body after this many bytes. The response is flagged as truncated and its size is recorded as the cap.",
+        value_name = "BYTES",
+        value_parser = clap::value_parser!(u64).range(1..)
+    ))]
+    pub max_body_size: Option<u64>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Stop probing after this many seconds of the run, including fetching the sitemap. The report covers the requests completed until then.",
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    ))]
+    pub max_duration: Option<u64>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            value_enum,
+            num_args = 0..=1,
+            default_missing_value = "warn",
+            help = "List 2xx responses with an empty body as \"Empty Responses\". With 'error', they also count as errors for the exit code."
+        )
+    )]
+    pub flag_empty_body: Option<EmptyBodyMode>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Probe the URLs in random order instead of sitemap order. The report is still sorted by URL.",
+            default_value = "false"
+        )
+    )]
+    pub shuffle: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Seed for --shuffle, to reproduce a probe order",
+            requires = "shuffle"
+        )
+    )]
+    pub seed: Option<u64>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "If the sitemap URL returns 404, try the conventional locations (`/sitemap_index.xml`, `/sitemap.xml`, `/sitemap1.xml`) and the `Sitemap:` entries in robots.txt.",
+            default_value = "false"
+        )
+    )]
+    pub discover: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Accept an HTML sitemap, a page linking to the site's pages, if the sitemap URL doesn't return an XML sitemap. The same-origin links on the page are probed.",
+            default_value = "false"
+        )
+    )]
+    pub html_sitemap: bool,
 
-    #[arg(
+    #[cfg_attr(feature = "cli", arg(
+        long = "allow-host",
+        help = "Only probe URLs on the given host. Can be specified multiple times. If omitted, all hosts are allowed.",
+        value_parser = validate_host,
+    ))]
+    pub allow_hosts: Vec<String>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long = "deny-host",
+        help = "Skip URLs on the given host. Can be specified multiple times. Takes precedence over --allow-host.",
+        value_parser = validate_host,
+    ))]
+    pub deny_hosts: Vec<String>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long = "include",
+        value_name = "REGEX",
+        help = "Only probe URLs matching this regular expression, e.g. '--include /blog/'. Can be specified multiple times; a URL is probed if it matches any of them.",
+        value_parser = parse_url_pattern,
+    ))]
+    pub include_patterns: Vec<Regex>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long = "exclude",
+        value_name = "REGEX",
+        help = "Skip URLs matching this regular expression, e.g. '--exclude /blog/drafts/'. Can be specified multiple times. Takes precedence over --include.",
+        value_parser = parse_url_pattern,
+    ))]
+    pub exclude_patterns: Vec<Regex>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Remove the query string from the sitemap URLs, so URLs that only differ in e.g. tracking parameters are probed once.",
+            default_value = "false",
+            conflicts_with = "ignore_params"
+        )
+    )]
+    pub ignore_query: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long = "ignore-param",
+            value_name = "NAME",
+            help = "Remove the given query parameter from the sitemap URLs, e.g. '--ignore-param utm_source', so URLs that only differ in it are probed once. Can be specified multiple times.",
+        )
+    )]
+    pub ignore_params: Vec<String>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Add a \"URL Structure\" section with the path depth of the sitemap URLs and the deepest and longest URLs, which often point to crawl traps or parameterized pages."
+        )
+    )]
+    pub url_structure: bool,
+
+    #[cfg_attr(feature = "cli", arg(
         long,
-        help = "Path to a TOML config file. Defaults to `.siteprobe.toml` in the current directory.",
+        help = "Only probe URLs that are new or failed in a previous JSON report (--report-path-json), skipping the URLs that were healthy last time. A soft 404 or a failed content check counts as a failure.",
+        value_name = "PREVIOUS_JSON",
         value_hint = ValueHint::FilePath,
         value_parser = expand_path
+    ))]
+    pub changed_since: Option<PathBuf>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Only probe URLs whose sitemap <lastmod> is at or after this date, e.g. '2024-01-01' or '2024-01-01T12:00:00+00:00'. URLs without a <lastmod> are always probed.",
+        value_name = "DATE",
+        value_parser = parse_modified_since
+    ))]
+    pub modified_since: Option<DateTime<FixedOffset>>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Fail (exit code 1) before probing if the sitemap doesn't list this number of URLs. Guards against sitemap generation bugs.",
+            value_name = "N"
+        )
+    )]
+    pub expect_url_count: Option<usize>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Only probe the first N URLs, after the URL filters were applied (also with --shuffle). The URLs of each sitemap are sorted alphabetically and follow in the order the sitemaps are given, listing each URL once; --urls-file and stdin lists keep their order. With --ignore-query or --ignore-param, all URLs are sorted alphabetically.",
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..)
+    ))]
+    pub max_urls: Option<u64>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Allowed deviation (in percent) from --expect-url-count, e.g. '10' to accept 90 to 110 URLs when expecting 100.",
+        value_name = "PCT",
+        default_value_t = 0.0,
+        requires = "expect_url_count",
+        value_parser = parse_slow_threshold
+    ))]
+    pub url_count_tolerance: f64,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Interval (in seconds) used to bucket the requests-per-second over time in the JSON and HTML reports.",
+        default_value_t = defaults::THROUGHPUT_INTERVAL,
+        value_parser = clap::value_parser!(u64).range(1..=3600)
+    ))]
+    pub throughput_interval: u64,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Probe each URL N times and report per-URL average, min and max response times. All samples are included in the overall statistics.",
+        default_value_t = defaults::REPEAT,
+        value_parser = clap::value_parser!(u32).range(1..=1000)
+    ))]
+    pub repeat: u32,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Send N warm-up requests per URL before the measured run. Their results are discarded and excluded from the statistics.",
+        default_value_t = defaults::WARMUP,
+        value_parser = clap::value_parser!(u32).range(0..=100)
+    ))]
+    pub warmup: u32,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Add a SHA-256 hash of each response body to the CSV and JSON reports, to detect content changes across runs. Costs CPU time on every page.",
+            default_value = "false"
+        )
+    )]
+    pub hash_bodies: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Send HEAD instead of GET requests, to check availability without downloading the pages. The response size is taken from the Content-Length header.",
+            default_value = "false",
+            conflicts_with_all = ["output_dir", "hash_bodies"]
+        )
+    )]
+    pub head: bool,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Flag 2xx responses whose body doesn't contain this text, e.g. a footer, to catch error pages served with a 200. They are listed under 'Content Mismatch' and fail the run with exit code 1. Can be specified multiple times; every marker must be present. With --max-body-size, only the downloaded part is searched.",
+        value_name = "TEXT",
+        value_parser = clap::builder::NonEmptyStringValueParser::new(),
+        conflicts_with = "head"
+    ))]
+    pub expect_contains: Vec<String>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Flag 2xx responses whose body doesn't match this regular expression, e.g. '<title>.+</title>', to catch template rendering failures. They are listed under 'Content Mismatch' and fail the run with exit code 1. Can be specified multiple times; every pattern must match.",
+        value_name = "REGEX",
+        value_parser = parse_url_pattern,
+        conflicts_with = "head"
+    ))]
+    pub expect_matches: Vec<Regex>,
+
+    #[cfg_attr(feature = "cli", arg(
+        long = "soft-404-marker",
+        help = "Flag 200 responses whose body contains this text, e.g. 'Page not found', as soft 404s: missing pages served with an error template instead of a 404. They are listed under 'Soft 404s' and fail the run with exit code 1. Can be specified multiple times; any marker flags the page.",
+        value_name = "TEXT",
+        value_parser = clap::builder::NonEmptyStringValueParser::new(),
+        conflicts_with = "head"
+    ))]
+    pub soft_404_markers: Vec<String>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Include the request headers sent and the response headers received for each URL in the JSON report.",
+            default_value = "false"
+        )
+    )]
+    pub dump_headers: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "List the Server, Cache-Control, Content-Type and X-Cache response headers of each URL in the JSON report, and the distribution of Server values in the text report.",
+            default_value = "false"
+        )
+    )]
+    pub capture_headers: bool,
+
+    #[cfg_attr(feature = "cli", arg(
+        long = "redact-header",
+        help = "Replace the value of the given header with `[REDACTED]` in the JSON report (used with --dump-headers). Can be specified multiple times.",
+        value_parser = validate_header_name,
+    ))]
+    pub redact_headers: Vec<String>,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Output the JSON report to stdout instead of the normal table output. Suppresses all other console output for clean piping.",
+            default_value = "false"
+        )
     )]
-    pub config: Option<PathBuf>,
+    pub json: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Print the URLs that would be probed, one per line, and exit without fetching them. With --json, print them as a JSON array."
+        )
+    )]
+    pub list_urls: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Collect and filter the sitemap URLs, then print how many would be probed, the URLs per sitemap file and an estimated run time, without fetching them. With --json, print the summary as JSON."
+        )
+    )]
+    pub dry_run: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Print nothing if all URLs are OK (exit code 0), e.g. for cron jobs that mail any output. Otherwise the normal report is printed. Errors and warnings are always shown."
+        )
+    )]
+    pub silent_on_success: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Disable colored output. Colors are also disabled when the `NO_COLOR` environment variable is set.",
+            default_value = "false"
+        )
+    )]
+    pub no_color: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            short = 'V',
+            long,
+            help = "Print version. Combine with -v to include build details for bug reports.",
+            default_value = "false"
+        )
+    )]
+    pub version: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            short = 'v',
+            long,
+            help = "Print additional diagnostics, such as the reason for the exit code, to stderr.",
+            default_value = "false"
+        )
+    )]
+    pub verbose: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Print the exit codes and their meaning, then exit.",
+            default_value = "false"
+        )
+    )]
+    pub explain_exit_codes: bool,
+
+    #[cfg_attr(
+        feature = "cli",
+        arg(
+            long,
+            help = "Output only the array of responses in the JSON report (stdout and --report-path-json), without the config and statistics.",
+            default_value = "false"
+        )
+    )]
+    pub json_responses_only: bool,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Path to a TOML config file. Defaults to `.siteprobe.toml` in the current directory. Can be specified multiple times; the files are merged in order, later files overriding earlier ones.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path
+    ))]
+    pub config: Vec<PathBuf>,
+
+    #[cfg_attr(feature = "cli", command(subcommand))]
+    pub command: Option<Command>,
+}
+
+// Subcommands that work on earlier reports instead of probing a sitemap. No doc comment
+// here either: clap would show it as the description of siteprobe itself.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cli", derive(clap::Subcommand))]
+pub enum Command {
+    /// Compare two JSON reports and show regressions: new failures, fixed URLs and
+    /// response time changes. Exits with code 1 if a URL fails or got slower.
+    Compare(CompareArgs),
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cli", derive(clap::Args))]
+pub struct CompareArgs {
+    #[cfg_attr(feature = "cli", arg(
+        help = "The JSON report to compare against, e.g. from the previous run, as written by --report-path-json or --json.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path
+    ))]
+    pub baseline: PathBuf,
+
+    #[cfg_attr(feature = "cli", arg(
+        help = "The JSON report of the current run.",
+        value_hint = ValueHint::FilePath,
+        value_parser = expand_path
+    ))]
+    pub current: PathBuf,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "Report URLs whose response time changed by more than this many milliseconds. A slowdown counts as a regression.",
+        value_name = "MS",
+        default_value_t = defaults::COMPARE_THRESHOLD_MS
+    ))]
+    pub threshold_ms: u64,
+
+    #[cfg_attr(feature = "cli", arg(
+        long,
+        help = "The name of the cache buster query parameter the reports were written with (--append-timestamp), removed to match the URLs.",
+        value_name = "NAME",
+        default_value_t = defaults::CACHE_BUST_PARAM.to_string(),
+        value_parser = validate_query_param_name
+    ))]
+    pub cache_bust_param: String,
+}
+
+impl Default for Cli {
+    /// Returns the same options as running siteprobe without any arguments, except that
+    /// no sitemap URL is set.
+    fn default() -> Self {
+        Self {
+            sitemap_urls: Vec::new(),
+            urls_file: None,
+            basic_auth: None,
+            bearer_token: None,
+            headers: Vec::new(),
+            accept: None,
+            json_api: false,
+            login_url: None,
+            login_data: Vec::new(),
+            concurrency_limit: defaults::SEMAPHORE,
+            sitemap_concurrency: None,
+            rate_limit: None,
+            output_dir: None,
+            storage_layout: StorageLayout::Flat,
+            store_headers: false,
+            store_errors_only: false,
+            append_timestamp: false,
+            cache_bust_param: defaults::CACHE_BUST_PARAM.to_string(),
+            cache_bust_header: false,
+            report_path: None,
+            report_path_json: None,
+            report_path_html: None,
+            report_path_jsonl: None,
+            report_path_junit: None,
+            report_path_md: None,
+            failures_path: None,
+            report_dir: None,
+            report_formats: Vec::new(),
+            status_file: None,
+            request_timeout: defaults::TIMEOUT,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            no_keepalive: false,
+            insecure: false,
+            resolve: Vec::new(),
+            local_address: None,
+            timeout_overrides: Vec::new(),
+            user_agent: defaults::USER_AGENT.to_string(),
+            slow_num: defaults::SLOW_NUM,
+            report_width: None,
+            url_truncate: None,
+            sections: Vec::new(),
+            slow_threshold: None,
+            min_success_rate: None,
+            max_p95_ms: None,
+            max_avg_ms: None,
+            max_error_rate: None,
+            slow_by_status: false,
+            follow_redirects: false,
+            same_host_redirects_only: false,
+            max_redirects: defaults::MAX_REDIRECTS,
+            warn_redirects: None,
+            redirects_are_errors: false,
+            retries: defaults::RETRIES,
+            retry_backoff_ms: defaults::RETRY_BACKOFF_MS,
+            no_respect_retry_after: false,
+            stop_on_first_error: false,
+            max_total_bytes: None,
+            max_body_size: None,
+            max_duration: None,
+            flag_empty_body: None,
+            shuffle: false,
+            seed: None,
+            discover: false,
+            html_sitemap: false,
+            allow_hosts: Vec::new(),
+            deny_hosts: Vec::new(),
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
+            ignore_query: false,
+            ignore_params: Vec::new(),
+            url_structure: false,
+            changed_since: None,
+            modified_since: None,
+            expect_url_count: None,
+            max_urls: None,
+            url_count_tolerance: 0.0,
+            throughput_interval: defaults::THROUGHPUT_INTERVAL,
+            repeat: defaults::REPEAT,
+            warmup: defaults::WARMUP,
+            hash_bodies: false,
+            head: false,
+            expect_contains: Vec::new(),
+            expect_matches: Vec::new(),
+            soft_404_markers: Vec::new(),
+            dump_headers: false,
+            capture_headers: false,
+            redact_headers: Vec::new(),
+            json: false,
+            list_urls: false,
+            dry_run: false,
+            silent_on_success: false,
+            no_color: false,
+            verbose: false,
+            explain_exit_codes: false,
+            version: false,
+            json_responses_only: false,
+            config: Vec::new(),
+            command: None,
+        }
+    }
 }
 
 /// Represents settings loaded from a `.siteprobe.toml` config file.
@@ -304,39 +1527,71 @@ pub struct ConfigFile {
 }
 
 impl ConfigFile {
-    /// Load a config file from the given path, or return a default (empty) config.
-    pub fn load(path: Option<&PathBuf>) -> Result<Self, String> {
-        let config_path = match path {
-            Some(p) => {
-                if !p.exists() {
-                    return Err(format!("Config file '{}' not found.", p.display()));
-                }
-                p.clone()
+    /// Load and merge the config files at the given paths, in order. Each file must
+    /// exist. Without paths, `.siteprobe.toml` in the current directory is loaded if
+    /// present, or a default (empty) config is returned.
+    pub fn load(paths: &[PathBuf]) -> Result<Self, String> {
+        if paths.is_empty() {
+            let default_path = PathBuf::from(".siteprobe.toml");
+            if !default_path.exists() {
+                return Ok(Self::default());
             }
-            None => {
-                let default_path = PathBuf::from(".siteprobe.toml");
-                if !default_path.exists() {
-                    return Ok(Self::default());
-                }
-                default_path
+            return Self::load_file(&default_path);
+        }
+
+        let mut config = Self::default();
+        for path in paths {
+            if !path.exists() {
+                return Err(format!("Config file '{}' not found.", path.display()));
             }
-        };
+            config.merge(Self::load_file(path)?);
+        }
+        Ok(config)
+    }
 
-        let contents = fs::read_to_string(&config_path).map_err(|e| {
+    fn load_file(config_path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(config_path).map_err(|e| {
             format!(
                 "Failed to read config file '{}': {}",
                 config_path.display(),
                 e
             )
         })?;
-        let config: ConfigFile = toml::from_str(&contents).map_err(|e| {
+        toml::from_str(&contents).map_err(|e| {
             format!(
                 "Failed to parse config file '{}': {}",
                 config_path.display(),
                 e
             )
-        })?;
-        Ok(config)
+        })
+    }
+
+    /// Overrides the values of this config with those set in `other`. Lists such as
+    /// `headers` are replaced, not extended.
+    fn merge(&mut self, other: ConfigFile) {
+        macro_rules! merge_fields {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() {
+                    self.$field = other.$field;
+                })*
+            };
+        }
+        merge_fields!(
+            user_agent,
+            concurrency_limit,
+            rate_limit,
+            request_timeout,
+            slow_threshold,
+            slow_num,
+            basic_auth,
+            follow_redirects,
+            append_timestamp,
+            retries,
+            report_path,
+            report_path_json,
+            report_path_html,
+            headers
+        );
     }
 }
 
@@ -370,9 +1625,40 @@ fn arg_provided(name: &str) -> bool {
 }
 
 impl Cli {
+    /// Whether progress and informational messages are suppressed, with `--json`,
+    /// `--list-urls`, `--dry-run` or `--silent-on-success`.
+    pub fn quiet(&self) -> bool {
+        self.json || self.list_urls || self.dry_run || self.silent_on_success
+    }
+
+    /// Sets the report paths for the `--report-formats` written to `--report-dir`. Paths
+    /// that are already set, e.g. with `--report-path`, are kept.
+    pub fn apply_report_dir(&mut self) {
+        let Some(dir) = self.report_dir.clone() else {
+            return;
+        };
+        let formats = if self.report_formats.is_empty() {
+            vec![ReportFormat::Csv, ReportFormat::Json, ReportFormat::Html]
+        } else {
+            self.report_formats.clone()
+        };
+        for format in formats {
+            let (path, file_name) = match format {
+                ReportFormat::Csv => (&mut self.report_path, "report.csv"),
+                ReportFormat::Json => (&mut self.report_path_json, "report.json"),
+                ReportFormat::Jsonl => (&mut self.report_path_jsonl, "report.jsonl"),
+                ReportFormat::Html => (&mut self.report_path_html, "report.html"),
+                ReportFormat::Junit => (&mut self.report_path_junit, "report.junit.xml"),
+                ReportFormat::Md => (&mut self.report_path_md, "report.md"),
+            };
+            path.get_or_insert_with(|| dir.join(file_name));
+        }
+    }
+
     /// Merge config file values into the CLI options.
-    /// CLI arguments take priority over config file values.
-    pub fn apply_config(&mut self, config: &ConfigFile) {
+    /// CLI arguments take priority over config file values. Invalid values are
+    /// skipped and recorded in `warnings`.
+    pub fn apply_config(&mut self, config: &ConfigFile, warnings: &Warnings) {
         if let Some(ref v) = config.user_agent {
             if !arg_provided("user_agent") {
                 self.user_agent = v.clone();
@@ -387,7 +1673,7 @@ impl Cli {
             if !arg_provided("rate_limit") {
                 match parse_rate_limit(v) {
                     Ok(rpm) => self.rate_limit = Some(rpm),
-                    Err(e) => eprintln!("Warning: invalid rate_limit in config file: {}", e),
+                    Err(e) => warnings.push(format!("Invalid rate_limit in config file: {}", e)),
                 }
             }
         }
@@ -446,7 +1732,7 @@ impl Cli {
                 for h in v {
                     match validate_header(h) {
                         Ok(valid) => self.headers.push(valid),
-                        Err(e) => eprintln!("Warning: invalid header in config file: {}", e),
+                        Err(e) => warnings.push(format!("Invalid header in config file: {}", e)),
                     }
                 }
             }
@@ -1,19 +1,83 @@
-use crate::metrics::{Entry, Metrics, CLEAN_FORMAT};
-use crate::options::Cli;
+#[cfg(feature = "cli")]
+use crate::metrics::CLEAN_FORMAT;
+use crate::metrics::{Entry, Metrics};
+use crate::network::{headers_to_json, is_redirect};
+#[cfg(feature = "cli")]
+use crate::options::defaults;
+#[cfg(feature = "cli")]
+use crate::options::ReportSection;
+use crate::options::{Cli, EmptyBodyMode};
+use crate::sitemap::{DuplicateUrls, UrlOverview, UrlStructure};
+use crate::term::style;
 use crate::utils;
-use console::style;
+#[cfg(feature = "cli")]
+use console::{measure_text_width, Term};
 use csv::Writer;
+#[cfg(feature = "cli")]
 use prettytable::{Cell, Row, Table};
+use reqwest::header::HeaderMap;
 use reqwest::StatusCode;
+use serde::Deserialize;
 use serde_json::json;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::time::Duration;
 
+/// Nearest-rank percentile of sorted durations: the smallest duration that at least
+/// `p` of all durations are at or below.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = (sorted.len() as f64 * p).ceil() as usize;
+    sorted
+        .get(rank.saturating_sub(1))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Median of sorted durations, the mean of the middle two for an even count.
+fn median(sorted: &[Duration]) -> Duration {
+    match sorted.len() {
+        0 => Duration::ZERO,
+        n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        n => sorted[n / 2],
+    }
+}
+
+/// Escapes a value for a cell of a Markdown table.
+fn markdown_escape(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+/// Renders metrics entries as a two-column Markdown table.
+fn markdown_metrics_table<'a>(entries: impl Iterator<Item = &'a Entry>) -> String {
+    let mut table = "| Metric | Value |\n| --- | --- |\n".to_string();
+    for entry in entries {
+        table.push_str(&format!(
+            "| {} | {} |\n",
+            markdown_escape(entry.label),
+            markdown_escape(&entry.value)
+        ));
+    }
+    table
+}
+
+/// Renders responses as a Markdown table of status code, URL and response time.
+fn markdown_responses_table(responses: &[Response]) -> String {
+    let mut table = "| Status | URL | Response Time |\n| --- | --- | ---: |\n".to_string();
+    for r in responses {
+        table.push_str(&format!(
+            "| {} | `{}` | {}ms |\n",
+            r.status_code,
+            markdown_escape(&r.url),
+            r.response_time.as_millis()
+        ));
+    }
+    table
+}
+
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -21,166 +85,1189 @@ fn html_escape(s: &str) -> String {
         .replace('"', "&quot;")
 }
 
-#[derive(Debug, Clone)]
+/// Returns the width of the text report: `--report-width` if given, otherwise the
+/// terminal width, falling back to the default when stdout is not a terminal.
+#[cfg(feature = "cli")]
+fn report_width(options: &Cli) -> usize {
+    options
+        .report_width
+        .or_else(|| Term::stdout().size_checked().map(|(_, cols)| cols))
+        .unwrap_or(defaults::REPORT_WIDTH) as usize
+}
+
+/// Returns the width URLs are truncated to in the error and slow response lists:
+/// `--url-truncate` if given (0 disables truncation), otherwise the report width minus
+/// room for the status and response time. Without a terminal and `--report-width`,
+/// URLs are not truncated so they can be copied from logs.
+#[cfg(feature = "cli")]
+fn url_width(options: &Cli) -> Option<usize> {
+    match options.url_truncate {
+        Some(0) => None,
+        Some(cols) => Some(cols),
+        None => options
+            .report_width
+            .or_else(|| Term::stdout().size_checked().map(|(_, cols)| cols))
+            .map(|cols| {
+                (cols as usize)
+                    .saturating_sub(defaults::URL_TRUNCATE_MARGIN)
+                    .max(defaults::URL_TRUNCATE_MIN)
+            }),
+    }
+}
+
+/// Truncates `url` to `width` columns, if set.
+#[cfg(feature = "cli")]
+fn truncate_url(url: &str, width: Option<usize>) -> String {
+    width.map_or_else(|| url.to_string(), |w| utils::truncate_message(url, w))
+}
+
+/// Describes why a response failed its content checks, e.g.
+/// `missing 'Footer', 'Imprint'; no match for /<title>.+</title>/`.
+fn describe_mismatch(r: &Response) -> String {
+    let mut parts = Vec::new();
+    if !r.missing_markers.is_empty() {
+        let markers: Vec<_> = r
+            .missing_markers
+            .iter()
+            .map(|m| format!("'{}'", m))
+            .collect();
+        parts.push(format!("missing {}", markers.join(", ")));
+    }
+    if !r.unmatched_patterns.is_empty() {
+        let patterns: Vec<_> = r
+            .unmatched_patterns
+            .iter()
+            .map(|p| format!("/{}/", p))
+            .collect();
+        parts.push(format!("no match for {}", patterns.join(", ")));
+    }
+    parts.join("; ")
+}
+
+/// Places two rendered tables side by side if the result fits within `width`
+/// columns, otherwise stacks them vertically.
+#[cfg(feature = "cli")]
+pub fn layout_tables(left: &str, right: &str, width: usize) -> String {
+    let mut table = Table::new();
+    table.set_format(*CLEAN_FORMAT);
+    table.add_row(Row::new(vec![Cell::new(left), Cell::new(right)]));
+    let side_by_side = table.to_string();
+
+    let fits = side_by_side
+        .lines()
+        .all(|line| measure_text_width(line) <= width);
+    if fits {
+        return side_by_side;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*CLEAN_FORMAT);
+    table.add_row(Row::new(vec![Cell::new(left)]));
+    table.add_row(Row::new(vec![Cell::new(right)]));
+    table.to_string()
+}
+
+/// Number of buckets used for the response time histogram in the HTML chart and the
+/// text report sparkline.
+const HISTOGRAM_BUCKETS: usize = 20;
+
+/// Buckets response times (in milliseconds) into `bucket_count` equally wide buckets
+/// between the fastest and slowest response.
+///
+/// Returns the lower bound of the first bucket, the bucket width and the bucket counts.
+fn response_time_histogram(times_ms: &[f64], bucket_count: usize) -> (f64, f64, Vec<usize>) {
+    let min_t = times_ms.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max_t = times_ms.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = if (max_t - min_t).abs() < 0.001 {
+        1.0
+    } else {
+        max_t - min_t
+    };
+    let bucket_width = range / bucket_count as f64;
+    let mut buckets = vec![0usize; bucket_count];
+    for &t in times_ms {
+        let idx = ((t - min_t) / bucket_width).floor() as usize;
+        let idx = idx.min(bucket_count - 1);
+        buckets[idx] += 1;
+    }
+    (min_t, bucket_width, buckets)
+}
+
+/// Renders the requests per second over time as an SVG bar chart.
+fn throughput_svg(rates: &[f64], interval_secs: u64) -> String {
+    if rates.is_empty() {
+        return String::from("<p>No data available.</p>");
+    }
+
+    let max_rate = rates
+        .iter()
+        .cloned()
+        .fold(0.0f64, f64::max)
+        .max(f64::EPSILON);
+    let chart_w = 1000.0f64;
+    let chart_h = 160.0f64;
+    let bar_w = chart_w / rates.len() as f64;
+    let label_every = (rates.len() / 10).max(1);
+
+    let mut svg = format!(
+        r#"<svg viewBox="0 0 {vw} {vh}" xmlns="http://www.w3.org/2000/svg" style="width:100%">"#,
+        vw = chart_w + 60.0,
+        vh = chart_h + 50.0
+    );
+    svg.push_str(&format!(
+        r##"<text x="10" y="{}" font-size="11" fill="#64748b" text-anchor="middle" transform="rotate(-90,10,{})">Req/s</text>"##,
+        chart_h / 2.0 + 10.0,
+        chart_h / 2.0 + 10.0
+    ));
+    for (i, &rate) in rates.iter().enumerate() {
+        let bar_h = (rate / max_rate) * chart_h;
+        let x = 40.0 + i as f64 * bar_w;
+        let y = chart_h - bar_h + 10.0;
+        let t = i as u64 * interval_secs;
+        svg.push_str(&format!(
+            r##"<rect x="{x:.1}" y="{y:.1}" width="{bw:.1}" height="{bh:.1}" fill="#0ea5e9" opacity="0.85" rx="1"><title>{t}s: {rate:.2} req/s</title></rect>"##,
+            x = x,
+            y = y,
+            bw = (bar_w - 1.0).max(1.0),
+            bh = bar_h,
+            t = t,
+            rate = rate
+        ));
+        if i % label_every == 0 {
+            svg.push_str(&format!(
+                r##"<text x="{x:.1}" y="{y}" font-size="10" fill="#64748b" text-anchor="middle">{t}s</text>"##,
+                x = x + bar_w / 2.0,
+                y = chart_h + 25.0,
+                t = t
+            ));
+        }
+    }
+    svg.push_str(&format!(
+        r##"<text x="{}" y="{}" font-size="11" fill="#64748b" text-anchor="middle">Time since start</text>"##,
+        40.0 + chart_w / 2.0,
+        chart_h + 45.0
+    ));
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Returns the status family label (e.g. `2xx`) and the CSS class used for a status
+/// code in the HTML report. The chart colors match the `status-*` classes.
+fn status_family(code: u16) -> (String, &'static str, &'static str) {
+    let family = format!("{}xx", code / 100);
+    match code {
+        ..300 => (family, "status-ok", "#16a34a"),
+        300..400 => (family, "status-redirect", "#ca8a04"),
+        _ => (family, "status-error", "#dc2626"),
+    }
+}
+
+/// Renders the status code counts as an SVG bar chart. Bars are grouped by status
+/// family (2xx, 3xx, 4xx, 5xx). Each bar carries its count and percentage as `data-*`
+/// attributes for the hover tooltip of the inlined script.
+fn status_code_svg(status_entries: &[(u16, usize)]) -> String {
+    if status_entries.is_empty() {
+        return String::from("<p>No data available.</p>");
+    }
+
+    let total: usize = status_entries.iter().map(|&(_, c)| c).sum();
+    let max_count = status_entries.iter().map(|&(_, c)| c).max().unwrap_or(1);
+    let families = status_entries
+        .windows(2)
+        .filter(|w| w[0].0 / 100 != w[1].0 / 100)
+        .count()
+        + 1;
+    let chart_w = 400.0f64;
+    let chart_h = 200.0f64;
+    // Half a bar of space between the families.
+    let group_gap = 0.5;
+    let slot_w = chart_w / (status_entries.len() as f64 + (families - 1) as f64 * group_gap);
+    let bar_w = (slot_w - 4.0).max(4.0);
+
+    let mut svg = format!(
+        r#"<svg viewBox="0 0 {vw} {vh}" xmlns="http://www.w3.org/2000/svg" style="width:100%;max-width:500px">"#,
+        vw = chart_w + 60.0,
+        vh = chart_h + 80.0
+    );
+    let mut x = 40.0;
+    let mut group_start = x;
+    for (i, &(code, count)) in status_entries.iter().enumerate() {
+        let (family, class, color) = status_family(code);
+        let bar_h = (count as f64 / max_count as f64) * chart_h;
+        let y = chart_h - bar_h + 10.0;
+        let pct = count as f64 / total as f64 * 100.0;
+        let reason = StatusCode::from_u16(code)
+            .ok()
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or_default();
+        svg.push_str(&format!(
+            r#"<rect class="bar {class}" x="{x:.1}" y="{y:.1}" width="{bw:.1}" height="{bh:.1}" fill="{color}" rx="2" data-code="{code}" data-reason="{reason}" data-count="{count}" data-pct="{pct:.1}"><title>{code}: {count} ({pct:.1}%)</title></rect>"#,
+            bw = bar_w,
+            bh = bar_h,
+        ));
+        // Count label above bar
+        svg.push_str(&format!(
+            r##"<text x="{x:.1}" y="{y:.1}" font-size="11" fill="#334155" text-anchor="middle" font-weight="600">{count}</text>"##,
+            x = x + bar_w / 2.0,
+            y = y - 4.0,
+        ));
+        // Code label below
+        svg.push_str(&format!(
+            r##"<text x="{x:.1}" y="{y}" font-size="11" fill="#64748b" text-anchor="middle">{code}</text>"##,
+            x = x + bar_w / 2.0,
+            y = chart_h + 28.0,
+        ));
+        x += slot_w;
+
+        // Family label below the last bar of each group
+        let group_ends = status_entries
+            .get(i + 1)
+            .is_none_or(|&(next, _)| next / 100 != code / 100);
+        if group_ends {
+            let group_end = x - (slot_w - bar_w);
+            svg.push_str(&format!(
+                r##"<line x1="{x1:.1}" x2="{x2:.1}" y1="{y}" y2="{y}" stroke="{color}" stroke-width="2"/><text x="{x:.1}" y="{ty}" font-size="11" fill="{color}" text-anchor="middle" font-weight="600">{family}</text>"##,
+                x1 = group_start,
+                x2 = group_end,
+                y = chart_h + 36.0,
+                x = (group_start + group_end) / 2.0,
+                ty = chart_h + 50.0,
+            ));
+            x += slot_w * group_gap;
+            group_start = x;
+        }
+    }
+    svg.push_str(&format!(
+        r##"<text x="{}" y="{}" font-size="11" fill="#64748b" text-anchor="middle">Status Code</text>"##,
+        40.0 + chart_w / 2.0,
+        chart_h + 70.0
+    ));
+    svg.push_str("</svg>");
+    svg
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Response {
+    /// The final URL, after redirects were followed. Listed as `url` in the JSON report
+    /// and as `URL` in the CSV report.
     pub url: String,
+    /// The URL as requested. It differs from `source_url` only by the cache buster added
+    /// by `--append-timestamp`, and from `url` if a redirect was followed.
+    pub requested_url: String,
     pub response_time: Duration,
+    /// Time to first byte: until the response headers arrived, before the body was
+    /// downloaded.
+    pub ttfb: Duration,
     pub response_size: usize,
     pub status_code: StatusCode,
+    /// Response headers, only captured when `--dump-headers` is enabled.
+    pub response_headers: Option<HeaderMap>,
+    /// The URL as listed in the sitemap, before cache busting or redirects.
+    /// Used to group repeated probes of the same URL. Listed as `requestedUrl` in the
+    /// JSON report and as `Requested URL` in the CSV report, see [`Response::listed_url`].
+    pub source_url: String,
+    /// When the request was sent, relative to the start of the run.
+    pub started_at: Duration,
+    /// The per-URL timeout (in seconds) used instead of `--request-timeout`,
+    /// if a `--timeout-override` pattern matched.
+    pub timeout_override: Option<u64>,
+    /// Whether the URL triggered at least one redirect: either a 3xx status other than
+    /// `304 Not Modified` (see [`crate::network::is_redirect`]), or, with
+    /// `--follow-redirects`, a final URL that differs from the requested one.
+    pub redirected: bool,
+    /// Where a 3xx response redirects to, from its `Location` header.
+    pub redirect_target: Option<String>,
+    /// The number of redirects followed to get to `url`, with `--follow-redirects`.
+    pub redirect_count: usize,
+    /// Whether the redirect chain looped back to a URL it passed before. The loop is
+    /// not followed, so the 3xx response is the result.
+    pub redirect_loop: bool,
+    /// Whether the response was served from a cache, see [`crate::network::is_cache_hit`].
+    pub cache_hit: bool,
+    /// The `Content-Type` header of the response, if any.
+    pub content_type: Option<String>,
+    /// SHA-256 hash of the response body (hex), only computed with `--hash-bodies`.
+    pub body_hash: Option<String>,
+    /// The [`CAPTURED_HEADERS`](crate::network::CAPTURED_HEADERS) of the response,
+    /// listed in the JSON report with `--capture-headers`.
+    pub headers: BTreeMap<String, String>,
+    /// How long to wait before retrying, from the `Retry-After` header of a 429 or
+    /// 503 response.
+    pub retry_after: Option<Duration>,
+    /// Whether the body was cut off at `--max-body-size`. The size is then the cap.
+    pub truncated: bool,
+    /// Why the body couldn't be stored in `--output-dir`, if it couldn't.
+    pub storage_error: Option<String>,
+    /// The `--expect-contains` markers missing from the body of a 2xx response.
+    pub missing_markers: Vec<String>,
+    /// The `--expect-matches` patterns that didn't match the body of a 2xx response.
+    pub unmatched_patterns: Vec<String>,
+    /// Whether the body of a 200 response contains a `--soft-404-marker`.
+    pub soft_404: bool,
+    /// Why the request failed, e.g. a timeout or more redirects than `--max-redirects`.
+    /// The status code is then the one siteprobe assigns to the failure (408 for a
+    /// timeout, 502 for a connection error, 400 for an invalid request), the last
+    /// redirect status for a cut-off redirect chain, or 500 if the request failed
+    /// inside siteprobe.
+    pub error: Option<String>,
 }
 
-#[derive(Debug)]
+impl Response {
+    /// Whether the response is a 2xx with an empty body, which often signals a broken
+    /// page or a truncated response. `204 No Content` and `205 Reset Content` are
+    /// expected to be empty and not included.
+    pub fn is_empty_body(&self) -> bool {
+        self.status_code.is_success()
+            && self.response_size == 0
+            && self.status_code != StatusCode::NO_CONTENT
+            && self.status_code != StatusCode::RESET_CONTENT
+    }
+
+    /// Whether the response is an error: a 4xx/5xx status, or a failed request (see
+    /// [`Response::error`]).
+    pub fn is_error(&self) -> bool {
+        self.status_code.is_client_error()
+            || self.status_code.is_server_error()
+            || self.error.is_some()
+    }
+
+    /// The URL as listed in the sitemap (see [`Response::source_url`]), or `url` for a
+    /// response without one. It stays the same from run to run.
+    pub fn listed_url(&self) -> &str {
+        if self.source_url.is_empty() {
+            &self.url
+        } else {
+            &self.source_url
+        }
+    }
+
+    /// Whether the response failed regardless of the report options: an error (see
+    /// [`Response::is_error`]), a failed content check, or a soft 404.
+    pub fn is_failed(&self) -> bool {
+        self.is_error() || self.is_content_mismatch() || self.soft_404
+    }
+
+    /// Whether the response counts as an error for the exit code: a failed response (see
+    /// [`Response::is_failed`]), a redirect with `--redirects-are-errors`, or an empty
+    /// body with `--flag-empty-body error`.
+    pub fn is_failure(
+        &self,
+        redirects_are_errors: bool,
+        empty_body: Option<EmptyBodyMode>,
+    ) -> bool {
+        self.is_failed()
+            || (redirects_are_errors && self.redirected)
+            || (empty_body == Some(EmptyBodyMode::Error) && self.is_empty_body())
+    }
+
+    /// Whether the body failed a content check: an `--expect-contains` marker is
+    /// missing, or an `--expect-matches` pattern didn't match.
+    pub fn is_content_mismatch(&self) -> bool {
+        !self.missing_markers.is_empty() || !self.unmatched_patterns.is_empty()
+    }
+
+    /// Whether the response is served as JSON, i.e. with an `application/json` or
+    /// `+json` media type such as `application/problem+json`.
+    pub fn is_json(&self) -> bool {
+        self.content_type.as_deref().is_some_and(|ct| {
+            let media_type = ct.split(';').next().unwrap_or_default().trim();
+            media_type.eq_ignore_ascii_case("application/json")
+                || media_type.to_ascii_lowercase().ends_with("+json")
+        })
+    }
+}
+
+/// Response time statistics for a single URL across all `--repeat` samples.
+#[derive(Debug, Clone)]
+pub struct UrlAggregate {
+    pub url: String,
+    pub samples: usize,
+    pub avg_response_time: Duration,
+    pub min_response_time: Duration,
+    pub max_response_time: Duration,
+}
+
+#[derive(Debug, Default)]
 pub struct Report {
     pub sitemap_url: String,
     pub concurrency_limit: u8,
     pub rate_limit: Option<u32>,
     pub total_time: Duration,
     pub responses: VecDeque<Response>,
+    /// Headers sent with every request, only recorded when `--dump-headers` is enabled.
+    pub request_headers: Option<HeaderMap>,
+    /// Number of warm-up requests sent before the measured run (`--warmup`).
+    /// These are not part of `responses` and their time is excluded from `total_time`.
+    pub warmup_requests: usize,
+    /// Duplicate `<loc>` entries found in the sitemap(s).
+    pub duplicates: DuplicateUrls,
+    /// Unique hosts and URLs per scheme in the sitemap.
+    pub url_overview: Option<UrlOverview>,
+    /// Path depth and length of the sitemap URLs, only computed with `--url-structure`.
+    pub url_structure: Option<UrlStructure>,
+    /// Warnings raised during the run that didn't stop it, e.g. a missing child sitemap.
+    pub warnings: Vec<String>,
+    /// The failed response that stopped the run early (`--stop-on-first-error`).
+    pub first_error: Option<Response>,
+    /// How 2xx responses with an empty body are treated (`--flag-empty-body`).
+    pub empty_body: Option<EmptyBodyMode>,
+    /// Whether redirects count as errors (`--redirects-are-errors`).
+    pub redirects_are_errors: bool,
+    /// The minimum percentage of 2xx responses for the run to pass (`--min-success-rate`).
+    pub min_success_rate: Option<f64>,
+    /// The performance budget for the 95th percentile response time (`--max-p95-ms`).
+    pub max_p95_ms: Option<u64>,
+    /// The performance budget for the average response time (`--max-avg-ms`).
+    pub max_avg_ms: Option<u64>,
+    /// The performance budget for the error rate, in percent (`--max-error-rate`).
+    pub max_error_rate: Option<f64>,
+    /// Number of sitemap URLs that collapsed into another one after removing query
+    /// parameters (`--ignore-query`, `--ignore-param`).
+    pub collapsed_urls: Option<usize>,
+    /// Number of URLs skipped because they were healthy in the previous report
+    /// (`--changed-since`).
+    pub skipped_unchanged: Option<usize>,
+    /// Number of URLs skipped because their sitemap `<lastmod>` is older than
+    /// `--modified-since`.
+    pub skipped_unmodified: Option<usize>,
+    /// Whether 2xx responses are expected to be JSON (`--json-api`).
+    pub expect_json: bool,
+    /// Whether the response bodies were hashed (`--hash-bodies`), adding a hash
+    /// column to the CSV and JSON reports.
+    pub hash_bodies: bool,
+    /// Whether a timestamp was appended to every URL to bypass caches
+    /// (`--append-timestamp`).
+    pub bypass_caching: bool,
+    /// The byte budget of the run (`--max-total-bytes`).
+    pub max_total_bytes: Option<u64>,
+    /// Whether probing stopped early because the byte budget was used up.
+    pub byte_capped: bool,
+    /// The time budget of the run in seconds (`--max-duration`).
+    pub max_duration: Option<u64>,
+    /// Whether probing stopped early because the time budget was used up.
+    pub time_capped: bool,
+    /// Whether the run was interrupted with Ctrl-C. The report covers the requests
+    /// completed until then and the exit code is that of the completed responses.
+    pub interrupted: bool,
 }
 
-#[derive(Debug)]
+/// Writes the `--status-file`, adding the time the run finished (`finishedAt`, in
+/// seconds since the Unix epoch) to `status`.
+pub fn write_status_file(path: &Path, mut status: serde_json::Value) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    status["finishedAt"] = json!(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs()));
+    std::fs::write(path, format!("{}\n", status))
+}
+
+/// Loads whether each URL failed in a previous JSON report, as written by
+/// `--report-path-json` or `--json`, see [`Response::is_failed`]. The full report and
+/// the `--json-responses-only` array are both accepted.
+///
+/// URLs are keyed as requested, before redirects, without the `cache_bust_param` cache
+/// buster added by `--append-timestamp`. If a URL was probed several times (`--repeat`),
+/// a single failure counts.
+pub fn load_previous_failures(
+    path: &Path,
+    cache_bust_param: &str,
+) -> Result<HashMap<String, bool>, String> {
+    let mut failures: HashMap<String, bool> = HashMap::new();
+    for r in load_json_responses(path, "previous report")? {
+        let url = utils::strip_cache_buster(&r.requested_url, cache_bust_param);
+        *failures.entry(url.to_string()).or_default() |= r.is_failed();
+    }
+    Ok(failures)
+}
+
+/// Reads the `responses` array of a JSON report, or a `--json-responses-only` array.
+/// `what` names the report in error messages, e.g. "previous report".
+fn read_json_responses(path: &Path, what: &str) -> Result<Vec<serde_json::Value>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read {} {}: {}", what, path.display(), e))?;
+    let mut data: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid {} {}: {}", what, path.display(), e))?;
+
+    let responses = match data.get_mut("responses") {
+        Some(responses) => responses.take(),
+        None => data,
+    };
+    match responses {
+        serde_json::Value::Array(responses) => Ok(responses),
+        _ => Err(format!(
+            "Invalid {} {}: no responses found",
+            what,
+            path.display()
+        )),
+    }
+}
+
+/// A response as written to the JSON report by [`Report::to_json_string`]. Fields that
+/// are only written with some options, e.g. `bodyHash`, may be missing.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonResponse {
+    url: String,
+    /// The URL as listed in the sitemap. Missing in reports written before the requested
+    /// URL was recorded, and with the `--append-timestamp` cache buster in older ones.
+    requested_url: Option<String>,
+    status_code: u16,
+    #[serde(default)]
+    response_time: u64,
+    #[serde(default)]
+    ttfb: u64,
+    #[serde(default)]
+    response_size: usize,
+    timeout_override_secs: Option<u64>,
+    body_hash: Option<String>,
+    redirect_target: Option<String>,
+    #[serde(default)]
+    redirect_count: usize,
+    #[serde(default)]
+    redirect_loop: bool,
+    #[serde(default)]
+    missing_markers: Vec<String>,
+    #[serde(default)]
+    unmatched_patterns: Vec<String>,
+    #[serde(default)]
+    soft_404: bool,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(default)]
+    truncated: bool,
+    error: Option<String>,
+}
+
+/// Loads the responses of a JSON report, as written by `--report-path-json` or
+/// `--json`. The full report and the `--json-responses-only` array are both accepted.
+///
+/// Only what the JSON report records is restored: response times are in whole
+/// milliseconds, and the request and response headers of `--dump-headers` are dropped.
+pub fn load_json_report(path: &Path) -> Result<Vec<Response>, String> {
+    load_json_responses(path, "report")
+}
+
+/// Loads the responses of a JSON report, see [`load_json_report`]. `what` names the
+/// report in error messages, e.g. "previous report".
+fn load_json_responses(path: &Path, what: &str) -> Result<Vec<Response>, String> {
+    read_json_responses(path, what)?
+        .into_iter()
+        .map(|entry| {
+            let r: JsonResponse = serde_json::from_value(entry)
+                .map_err(|e| format!("Invalid {} {}: {}", what, path.display(), e))?;
+            let status_code = StatusCode::from_u16(r.status_code)
+                .map_err(|e| format!("Invalid {} {}: {}", what, path.display(), e))?;
+            let requested_url = r.requested_url.unwrap_or_else(|| r.url.clone());
+            Ok(Response {
+                redirected: is_redirect(status_code) || requested_url != r.url,
+                source_url: requested_url.clone(),
+                requested_url,
+                url: r.url,
+                status_code,
+                response_time: Duration::from_millis(r.response_time),
+                ttfb: Duration::from_millis(r.ttfb),
+                response_size: r.response_size,
+                timeout_override: r.timeout_override_secs,
+                body_hash: r.body_hash,
+                redirect_target: r.redirect_target,
+                redirect_count: r.redirect_count,
+                redirect_loop: r.redirect_loop,
+                missing_markers: r.missing_markers,
+                unmatched_patterns: r.unmatched_patterns,
+                soft_404: r.soft_404,
+                headers: r.headers,
+                truncated: r.truncated,
+                error: r.error,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Statistics computed over all responses of a report, returned by [`Report::statistics`].
+///
+/// Rates are percentages from 0 to 100. Response times and sizes are zero when the
+/// report has no responses.
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Statistics {
+    /// Number of responses, counting every probe of `--repeat`.
+    pub total_requests: usize,
+    /// Requests per second over the measured run.
+    pub requests_per_second: f64,
+    pub avg_response_time: Duration,
+    pub median_response_time: Duration,
+    pub min_response_time: Duration,
+    pub max_response_time: Duration,
+    /// Nearest-rank percentiles: at least 90%, 95% and 99% of the responses were as
+    /// fast or faster.
+    pub p90_response_time: Duration,
+    pub p95_response_time: Duration,
+    pub p99_response_time: Duration,
+    /// Population standard deviation of the response times.
+    pub std_dev_response_time: Duration,
+    /// Time to first byte, see [`Response::ttfb`].
+    pub avg_ttfb: Duration,
+    pub median_ttfb: Duration,
+    pub p95_ttfb: Duration,
+    /// Percentage of 2xx responses.
+    pub success_rate: f64,
+    /// Percentage of 4xx and 5xx responses, including failed requests.
+    pub error_rate: f64,
+    /// Percentage of URLs that redirected, including followed redirects.
+    pub redirect_rate: f64,
+    /// Percentage of responses served from a cache (304, `X-Cache: HIT`, `Age` > 0).
+    pub cache_hit_rate: f64,
+    /// Percentage of responses slower than the slow threshold, `None` if no threshold is set.
+    pub slow_rate: Option<f64>,
+    /// Body sizes in bytes, see [`Response::response_size`].
+    pub avg_response_size: usize,
+    pub min_response_size: usize,
+    pub max_response_size: usize,
+}
+
+/// The statistics as labeled entries, for the text, Markdown, JSON and HTML reports. Returned by
+/// [`Report::statistics_tables`].
+#[derive(Debug)]
+pub struct StatisticsTables {
+    /// The run's settings and how much of it was probed: concurrency, rate limit,
+    /// elapsed time, cache bypassing, budgets and skipped URLs.
+    pub summary: Metrics,
+    /// Average, median, percentile and extreme response times, and the TTFB.
     pub response_time: Metrics,
+    /// Success, error, redirect, cache hit and slow rates.
     pub status_code: Metrics,
+    /// Total requests, throughput and response sizes.
     pub performance: Metrics,
 }
 
 impl Report {
+    #[cfg(feature = "cli")]
     pub fn show_text_report(&self, options: &Cli) {
-        let stats = self.generate_statistics(options.slow_threshold);
-        let base_metrics = Metrics(vec![
-            Entry {
-                label: "Concurrency Limit",
-                value: self.concurrency_limit.to_string(),
-                json_label: "concurrencyLimit",
-                json_value: json!(self.concurrency_limit),
-            },
-            Entry {
-                label: "Rate Limit",
-                value: if self.rate_limit.is_some() {
-                    format!("{}/min", self.rate_limit.unwrap())
-                } else {
-                    "No".to_string()
-                },
-                json_label: "rateLimit",
-                json_value: json!(self.rate_limit),
-            },
-            Entry {
-                label: "Elapsed Time",
-                value: format!("{:.2?}", self.total_time),
-                json_label: "elapsedTimeMs",
-                json_value: json!(self.total_time.as_millis()),
-            },
-            Entry {
-                label: "Bypass Caching",
-                value: if options.append_timestamp {
-                    "Yes".to_string()
-                } else {
-                    "No".to_string()
-                },
-                json_label: "bypassCaching",
-                json_value: json!(options.append_timestamp),
-            },
-        ]);
-
+        let stats = self.statistics_tables(options.slow_threshold);
         println!(
             "\n\n{} {}\n",
             style("Statistics for").bold(),
             style(&self.sitemap_url).bold().underlined()
         );
 
-        let mut table = Table::new();
-        table.set_format(*CLEAN_FORMAT);
-        table.add_row(Row::new(vec![
-            Cell::new(base_metrics.build_table().as_str()),
-            Cell::new(stats.status_code.build_table().as_str()),
-        ]));
-        println!("{}", table);
+        let width = report_width(options);
+        let url_width = url_width(options);
+        let show = |section| options.sections.is_empty() || options.sections.contains(&section);
 
-        println!(
-            "{}\n",
-            style("Response Time and Performance Statistics:").bold()
-        );
+        if show(ReportSection::Summary) {
+            println!(
+                "{}",
+                layout_tables(
+                    &stats.summary.build_table(),
+                    &stats.status_code.build_table(),
+                    width
+                )
+            );
 
-        let mut table = Table::new();
-        table.set_format(*CLEAN_FORMAT);
-        table.add_row(Row::new(vec![
-            Cell::new(stats.response_time.build_table().as_str()),
-            Cell::new(stats.performance.build_table().as_str()),
-        ]));
-        println!("{}", table);
+            // Distribution of `Server` header values (--capture-headers)
+            let servers = self.server_distribution();
+            if options.capture_headers && !servers.is_empty() {
+                println!("{}\n", style("Server Headers:").bold());
+                for (server, count) in servers {
+                    println!("{} {}", style(format!("{}:", count)).bold().dim(), server);
+                }
+                println!();
+            }
+        }
 
-        // Error Response List
-        let error_responses = self.error_responses();
-        if !error_responses.is_empty() {
-            println!("{}\n", style("Error Responses:").bold());
-            for r in error_responses {
+        if show(ReportSection::Performance) {
+            println!(
+                "{}\n",
+                style("Response Time and Performance Statistics:").bold()
+            );
+
+            println!(
+                "{}",
+                layout_tables(
+                    &stats.response_time.build_table(),
+                    &stats.performance.build_table(),
+                    width
+                )
+            );
+
+            let times_ms: Vec<f64> = self
+                .responses
+                .iter()
+                .map(|r| r.response_time.as_secs_f64() * 1000.0)
+                .collect();
+            if !times_ms.is_empty() {
+                let (_, _, buckets) = response_time_histogram(&times_ms, HISTOGRAM_BUCKETS);
                 println!(
-                    "{} {} {}",
-                    if r.status_code.is_server_error() {
-                        style(format!("{}:", r.status_code)).bold().white().on_red()
-                    } else {
-                        style(format!("{}:", r.status_code)).bold().dim()
-                    },
-                    r.url,
-                    style(format!("{}ms", r.response_time.as_millis())).dim()
+                    "{} {}\n",
+                    style("Distribution:").bold(),
+                    style(utils::sparkline(&buckets)).cyan()
                 );
             }
-            println!(); // Blank line before slow responses
+
+            // Per-URL statistics when probing each URL multiple times.
+            if options.repeat > 1 {
+                println!(
+                    "{} {}\n",
+                    style("Per-URL Response Times:").bold(),
+                    style(format!("{} samples each", options.repeat))
+                        .dim()
+                        .italic()
+                );
+                for a in self.url_aggregates() {
+                    println!(
+                        "{} {}",
+                        a.url,
+                        style(format!(
+                            "avg {} / min {} / max {}",
+                            utils::ms(a.avg_response_time),
+                            utils::ms(a.min_response_time),
+                            utils::ms(a.max_response_time)
+                        ))
+                        .dim()
+                    );
+                }
+                println!();
+            }
         }
 
-        // Slow Response List
-        if let Some(threshold) = options.slow_threshold {
-            let slow_responses = self.slowest_responses(threshold, options.slow_num);
-            if !slow_responses.is_empty() {
+        if show(ReportSection::Sitemap) {
+            // Duplicate URLs in the sitemap(s)
+            if !self.duplicates.is_empty() {
+                println!(
+                    "{} {}\n",
+                    style("Duplicate URLs:").bold(),
+                    style(format!(
+                        "{} within a single sitemap, {} across sitemaps",
+                        self.duplicates.within_sitemap.len(),
+                        self.duplicates.across_sitemaps.len()
+                    ))
+                    .dim()
+                    .italic()
+                );
+                for url in &self.duplicates.within_sitemap {
+                    println!("{} {}", style("within:").bold().yellow(), url);
+                }
+                for url in &self.duplicates.across_sitemaps {
+                    println!("{} {}", style("across:").bold().dim(), url);
+                }
+                println!();
+            }
+
+            // Deepest and longest URLs (--url-structure)
+            if let Some(structure) = &self.url_structure {
                 println!(
                     "{} {}\n",
-                    style("Slow Responses:").bold(),
-                    style(format!(">={}s", threshold)).dim().italic()
+                    style("URL Structure:").bold(),
+                    style(format!(
+                        "max depth {}, avg depth {:.1}",
+                        structure.max_depth, structure.avg_depth
+                    ))
+                    .dim()
+                    .italic()
                 );
-                for r in slow_responses {
+                for (url, depth) in &structure.deepest {
+                    println!(
+                        "{} {}",
+                        style(format!("depth {}:", depth)).bold().dim(),
+                        url
+                    );
+                }
+                if !structure.longest.is_empty() {
+                    println!();
+                }
+                for (url, length) in &structure.longest {
+                    println!(
+                        "{} {}",
+                        style(format!("{} chars:", length)).bold().dim(),
+                        url
+                    );
+                }
+                println!();
+            }
+        }
+
+        if show(ReportSection::Errors) {
+            // Error Response List
+            let error_responses = self.error_responses();
+            if !error_responses.is_empty() {
+                println!("{}\n", style("Error Responses:").bold());
+                for r in error_responses {
                     println!(
                         "{} {} {}",
-                        style(format!("{}:", r.status_code)).bold().dim(),
-                        r.url,
+                        if r.status_code.is_server_error() {
+                            style(format!("{}:", r.status_code)).bold().white().on_red()
+                        } else {
+                            style(format!("{}:", r.status_code)).bold().dim()
+                        },
+                        truncate_url(&r.url, url_width),
+                        style(match &r.error {
+                            Some(error) => format!("{}ms ({})", r.response_time.as_millis(), error),
+                            None => format!("{}ms", r.response_time.as_millis()),
+                        })
+                        .dim()
+                    );
+                }
+                println!(); // Blank line before slow responses
+            }
+
+            // Empty Response List
+            let empty_responses = self.empty_responses();
+            if !empty_responses.is_empty() {
+                println!("{}\n", style("Empty Responses:").bold());
+                for r in empty_responses {
+                    println!(
+                        "{} {} {}",
+                        style(format!("{}:", r.status_code)).bold().yellow(),
+                        truncate_url(&r.url, url_width),
                         style(format!("{}ms", r.response_time.as_millis())).dim()
                     );
                 }
+                println!();
+            }
+
+            // Pages failing a content check (--expect-contains, --expect-matches)
+            let content_mismatches = self.content_mismatches();
+            if !content_mismatches.is_empty() {
+                println!("{}\n", style("Content Mismatch:").bold());
+                for r in content_mismatches {
+                    println!(
+                        "{} {} {}",
+                        style(format!("{}:", r.status_code)).bold().yellow(),
+                        truncate_url(&r.url, url_width),
+                        style(describe_mismatch(&r)).dim()
+                    );
+                }
+                println!();
+            }
+
+            // Missing pages served with a 200 (--soft-404-marker)
+            let soft_404s = self.soft_404s();
+            if !soft_404s.is_empty() {
+                println!("{}\n", style("Soft 404s:").bold());
+                for r in soft_404s {
+                    println!(
+                        "{} {} {}",
+                        style(format!("{}:", r.status_code)).bold().yellow(),
+                        truncate_url(&r.url, url_width),
+                        style(format!("{}ms", r.response_time.as_millis())).dim()
+                    );
+                }
+                println!();
+            }
+
+            // Redirect loops and long redirect chains (--warn-redirects)
+            let excessive_redirects = self.excessive_redirects(options.warn_redirects);
+            if !excessive_redirects.is_empty() {
+                let header = style("URLs with Excessive Redirects:").bold();
+                match options.warn_redirects {
+                    Some(threshold) => println!(
+                        "{} {}\n",
+                        header,
+                        style(format!(">{} redirects", threshold)).dim().italic()
+                    ),
+                    None => println!("{}\n", header),
+                }
+                for r in excessive_redirects {
+                    let hops = if r.redirect_loop {
+                        style(format!("loop after {}:", r.redirect_count))
+                            .bold()
+                            .red()
+                    } else {
+                        style(format!("{} redirects:", r.redirect_count))
+                            .bold()
+                            .yellow()
+                    };
+                    println!(
+                        "{} {} {}",
+                        hops,
+                        truncate_url(&r.requested_url, url_width),
+                        style(format!(
+                            "→ {}",
+                            r.redirect_target.as_deref().unwrap_or(&r.url)
+                        ))
+                        .dim()
+                    );
+                }
+                println!();
+            }
+
+            // Responses that are not JSON with --json-api
+            let non_json_responses = self.non_json_responses();
+            if !non_json_responses.is_empty() {
+                println!("{}\n", style("Non-JSON Responses:").bold());
+                for r in non_json_responses {
+                    println!(
+                        "{} {} {}",
+                        style(format!("{}:", r.status_code)).bold().yellow(),
+                        truncate_url(&r.url, url_width),
+                        style(r.content_type.as_deref().unwrap_or("no Content-Type")).dim()
+                    );
+                }
+                println!();
+            }
+        }
+
+        if show(ReportSection::Slow) {
+            // Slow Response List
+            if let Some(threshold) = options.slow_threshold {
+                let groups = if options.slow_by_status {
+                    self.slowest_responses_by_status(threshold, options.slow_num)
+                } else {
+                    vec![(
+                        String::new(),
+                        self.slowest_responses(threshold, options.slow_num),
+                    )]
+                };
+                if groups.iter().any(|(_, responses)| !responses.is_empty()) {
+                    println!(
+                        "{} {}\n",
+                        style("Slow Responses:").bold(),
+                        style(format!(">={}s", threshold)).dim().italic()
+                    );
+                    for (i, (class, slow_responses)) in groups.iter().enumerate() {
+                        if options.slow_by_status {
+                            if i > 0 {
+                                println!();
+                            }
+                            println!("{}", style(format!("Slowest {}:", class)).bold());
+                        }
+                        for r in slow_responses {
+                            println!(
+                                "{} {} {}",
+                                style(format!("{}:", r.status_code)).bold().dim(),
+                                truncate_url(&r.url, url_width),
+                                style(format!("{}ms", r.response_time.as_millis())).dim()
+                            );
+                        }
+                    }
+                }
             }
         }
     }
 
     fn build_json_data(&self, options: &Cli) -> serde_json::Value {
-        let statistics = self.generate_statistics(options.slow_threshold);
+        let statistics = self.statistics_tables(options.slow_threshold);
 
-        json!(
+        let mut data = json!(
             {
                "config": {
                     "sitemapUrl": self.sitemap_url,
                     "concurrencyLimit": self.concurrency_limit,
+                    "rateLimit": self.rate_limit,
                     "elapsedTime": self.total_time.as_millis(),
                     "bypassCaching": options.append_timestamp,
+                    "warmupRequests": self.warmup_requests,
                 },
                 "statistics": {
                     "performance": statistics.performance,
                     "responseTime": statistics.response_time,
                     "statusCode": statistics.status_code,
+                    "throughput": {
+                        "intervalSecs": options.throughput_interval,
+                        "requestsPerSecond": self.throughput(Duration::from_secs(options.throughput_interval)),
+                    },
+                },
+                "duplicates": {
+                    "withinSitemap": self.duplicates.within_sitemap,
+                    "acrossSitemaps": self.duplicates.across_sitemaps,
                 },
+                "warnings": self.warnings,
                 "responses" : self.responses.iter().map(|r| {
-                    json!({
+                    let mut entry = json!({
                         "url": r.url,
+                        "requestedUrl": r.listed_url(),
                         "responseTime": r.response_time.as_millis(),
+                        "ttfb": r.ttfb.as_millis(),
                         "responseSize": r.response_size,
                         "statusCode": r.status_code.as_u16(),
-                    })
+                    });
+                    if let Some(seconds) = r.timeout_override {
+                        entry["timeoutOverrideSecs"] = json!(seconds);
+                    }
+                    if self.hash_bodies {
+                        entry["bodyHash"] = json!(r.body_hash);
+                    }
+                    if let Some(target) = &r.redirect_target {
+                        entry["redirectTarget"] = json!(target);
+                    }
+                    if r.redirect_count > 0 {
+                        entry["redirectCount"] = json!(r.redirect_count);
+                    }
+                    if r.redirect_loop {
+                        entry["redirectLoop"] = json!(true);
+                    }
+                    if !r.missing_markers.is_empty() {
+                        entry["missingMarkers"] = json!(r.missing_markers);
+                    }
+                    if !r.unmatched_patterns.is_empty() {
+                        entry["unmatchedPatterns"] = json!(r.unmatched_patterns);
+                    }
+                    if r.soft_404 {
+                        entry["soft404"] = json!(true);
+                    }
+                    if let Some(error) = &r.error {
+                        entry["error"] = json!(error);
+                    }
+                    if options.capture_headers {
+                        entry["headers"] = json!(r.headers);
+                    }
+                    if options.max_body_size.is_some() {
+                        entry["truncated"] = json!(r.truncated);
+                    }
+                    if options.dump_headers {
+                        let redact = &options.redact_headers;
+                        entry["requestHeaders"] = self.request_headers.as_ref()
+                            .map_or(json!({}), |h| headers_to_json(h, redact));
+                        entry["responseHeaders"] = r.response_headers.as_ref()
+                            .map_or(json!({}), |h| headers_to_json(h, redact));
+                    }
+                    entry
                 }).collect::<Vec<serde_json::Value>>()
             }
-        )
+        );
+
+        if let Some(overview) = &self.url_overview {
+            data["config"]["discovery"] = json!({
+                "urls": overview.urls,
+                "hosts": overview.hosts,
+                "http": overview.http,
+                "https": overview.https,
+            });
+        }
+
+        if let Some(structure) = &self.url_structure {
+            data["urlStructure"] = json!({
+                "maxDepth": structure.max_depth,
+                "avgDepth": structure.avg_depth,
+                "deepest": structure.deepest.iter()
+                    .map(|(url, depth)| json!({"url": url, "depth": depth}))
+                    .collect::<Vec<_>>(),
+                "longest": structure.longest.iter()
+                    .map(|(url, length)| json!({"url": url, "length": length}))
+                    .collect::<Vec<_>>(),
+            });
+        }
+
+        if let Some(max) = self.max_total_bytes {
+            data["config"]["maxTotalBytes"] = json!(max);
+            data["config"]["totalBytes"] = json!(self.total_bytes());
+            data["config"]["byteCapped"] = json!(self.byte_capped);
+        }
+
+        if let Some(max) = options.max_body_size {
+            data["config"]["maxBodySize"] = json!(max);
+        }
+
+        if let Some(max) = self.max_duration {
+            data["config"]["maxDuration"] = json!(max);
+            data["config"]["timeCapped"] = json!(self.time_capped);
+        }
+
+        if let Some(collapsed) = self.collapsed_urls {
+            data["config"]["collapsedUrls"] = json!(collapsed);
+        }
+
+        if let Some(skipped) = self.skipped_unchanged {
+            data["config"]["skippedUnchanged"] = json!(skipped);
+        }
+
+        if let Some(skipped) = self.skipped_unmodified {
+            data["config"]["skippedUnmodified"] = json!(skipped);
+        }
+
+        if let Some(threshold) = options.slow_threshold {
+            let slow_entries = |responses: &[Response]| {
+                responses
+                    .iter()
+                    .map(|r| {
+                        json!({
+                            "url": r.url,
+                            "responseTime": r.response_time.as_millis(),
+                            "statusCode": r.status_code.as_u16(),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            };
+            data["slowResponses"] = json!(slow_entries(
+                &self.slowest_responses(threshold, options.slow_num)
+            ));
+            if options.slow_by_status {
+                data["slowResponsesByStatus"] = self
+                    .slowest_responses_by_status(threshold, options.slow_num)
+                    .into_iter()
+                    .map(|(class, responses)| (class, json!(slow_entries(&responses))))
+                    .collect::<serde_json::Map<_, _>>()
+                    .into();
+            }
+        }
+
+        if self.empty_body.is_some() {
+            data["emptyResponses"] = self
+                .empty_responses()
+                .iter()
+                .map(|r| json!(r.url))
+                .collect();
+        }
+
+        if self.expect_json {
+            data["nonJsonResponses"] = self
+                .non_json_responses()
+                .iter()
+                .map(|r| json!({"url": r.url, "contentType": r.content_type}))
+                .collect();
+        }
+
+        if options.repeat > 1 {
+            data["config"]["repeat"] = json!(options.repeat);
+            data["urlStatistics"] = self
+                .url_aggregates()
+                .iter()
+                .map(|a| {
+                    json!({
+                        "url": a.url,
+                        "samples": a.samples,
+                        "avgMs": a.avg_response_time.as_millis(),
+                        "minMs": a.min_response_time.as_millis(),
+                        "maxMs": a.max_response_time.as_millis(),
+                    })
+                })
+                .collect();
+        }
+
+        data
+    }
+
+    /// Returns the JSON document to output: the full report object, or only the
+    /// `responses` array when `--json-responses-only` is set.
+    fn json_output(&self, options: &Cli) -> serde_json::Value {
+        let mut json_data = self.build_json_data(options);
+        if options.json_responses_only {
+            json_data["responses"].take()
+        } else {
+            json_data
+        }
+    }
+
+    /// Returns the status written to `--status-file`: the exit code, the number of
+    /// responses, OK responses, errors and slow responses, and whether the run stopped
+    /// early (`--stop-on-first-error`, `--max-total-bytes`).
+    pub fn status_json(&self, slow_threshold: Option<f64>) -> serde_json::Value {
+        json!({
+            "exitCode": self.exit_status(slow_threshold),
+            "total": self.responses.len(),
+            "ok": self.responses.iter().filter(|r| r.status_code.is_success()).count(),
+            "errors": self.failure_count(),
+            "slow": self.slow_count(slow_threshold),
+            "partial": self.first_error.is_some() || self.byte_capped || self.time_capped || self.interrupted,
+            "interrupted": self.interrupted,
+        })
     }
 
     /// Returns the JSON report as a pretty-printed string.
     pub fn to_json_string(&self, options: &Cli) -> Result<String, Box<dyn Error>> {
-        let json_data = self.build_json_data(options);
+        let json_data = self.json_output(options);
         Ok(serde_json::to_string_pretty(&json_data)?)
     }
 
@@ -194,13 +1281,13 @@ impl Report {
             std::fs::create_dir_all(parent)?;
         }
 
-        let json_data = self.build_json_data(options);
+        let json_data = self.json_output(options);
 
         // Write the JSON to a file
         let mut file = File::create(report_path)?;
         file.write_all(serde_json::to_string_pretty(&json_data)?.as_bytes())?;
 
-        if !options.json {
+        if !options.quiet() {
             println!(
                 "\n📄 The JSON report was written to {}",
                 style(report_path.display()).underlined().cyan()
@@ -210,6 +1297,135 @@ impl Report {
         Ok(())
     }
 
+    /// Append a JSON Lines report: one JSON object per response, in the format of the
+    /// JSON report's `responses`, followed by a `{"summary": ...}` line with the
+    /// `--status-file` fields, the sitemap URL and the elapsed time.
+    pub fn write_jsonl_report(
+        &self,
+        options: &Cli,
+        report_path: &PathBuf,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = report_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut summary = self.status_json(options.slow_threshold);
+        summary["sitemapUrl"] = json!(self.sitemap_url);
+        summary["elapsedTime"] = json!(self.total_time.as_millis());
+
+        let mut lines = String::new();
+        let mut data = self.build_json_data(options);
+        for entry in data["responses"].as_array_mut().into_iter().flatten() {
+            lines.push_str(&entry.take().to_string());
+            lines.push('\n');
+        }
+        lines.push_str(&json!({ "summary": summary }).to_string());
+        lines.push('\n');
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(report_path)?;
+        file.write_all(lines.as_bytes())?;
+
+        if !options.quiet() {
+            println!(
+                "\n📄 The JSON Lines report was written to {}",
+                style(report_path.display()).underlined().cyan()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Write a JUnit XML report: one `<testsuite>` for the sitemap with a `<testcase>`
+    /// per response, named by the URL as listed in the sitemap and timed by its response
+    /// time. Responses that count as errors for the exit code get a `<failure>`.
+    pub fn write_junit_report(
+        &self,
+        report_path: &PathBuf,
+        quiet: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = report_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let failures = self.responses.iter().filter(|r| self.is_failure(r)).count();
+        let mut xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" time=\"{:.3}\">\n",
+            html_escape(&self.sitemap_url),
+            self.responses.len(),
+            failures,
+            self.total_time.as_secs_f64()
+        );
+        for r in &self.responses {
+            xml.push_str(&format!(
+                "  <testcase name=\"{}\" classname=\"siteprobe\" time=\"{:.3}\"",
+                html_escape(r.listed_url()),
+                r.response_time.as_secs_f64()
+            ));
+            if self.is_failure(r) {
+                let message = if r.is_content_mismatch() {
+                    format!("{} {}", r.status_code, describe_mismatch(r))
+                } else if r.soft_404 {
+                    format!("{} soft 404", r.status_code)
+                } else if r.is_empty_body() {
+                    format!("{} with an empty body", r.status_code)
+                } else {
+                    r.status_code.to_string()
+                };
+                xml.push_str(&format!(
+                    ">\n    <failure message=\"{}\" type=\"{}\"/>\n  </testcase>\n",
+                    html_escape(&message),
+                    r.status_code.as_u16()
+                ));
+            } else {
+                xml.push_str("/>\n");
+            }
+        }
+        xml.push_str("</testsuite>\n");
+        std::fs::write(report_path, xml)?;
+
+        if !quiet {
+            println!(
+                "\n🧪 The JUnit report was written to {}",
+                style(report_path.display()).underlined().cyan()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Write the URLs of the responses that count as errors for the exit code (4xx/5xx,
+    /// including network errors), one per line, to probe them again with `--urls-file`.
+    /// The URLs are the ones listed in the sitemap, before cache busting or redirects.
+    pub fn write_failures(&self, path: &PathBuf, quiet: bool) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut seen = HashSet::new();
+        let mut lines = String::new();
+        for r in self.responses.iter().filter(|r| self.is_failure(r)) {
+            let url = r.listed_url();
+            if seen.insert(url) {
+                lines.push_str(url);
+                lines.push('\n');
+            }
+        }
+        std::fs::write(path, lines)?;
+
+        if !quiet {
+            println!(
+                "\n🔁 {} failed URLs were written to {}",
+                seen.len(),
+                style(path.display()).underlined().cyan()
+            );
+        }
+
+        Ok(())
+    }
+
     /// Write a CSV report
     pub fn write_csv_report(
         &self,
@@ -222,23 +1438,87 @@ impl Report {
         }
 
         let mut writer = Writer::from_path(report_path)?;
-        writer.write_record(vec![
+        let mut header = vec![
             "URL",
             "Response Time (ms)",
             "Response Size",
             "Status Code",
-        ])?;
+            "Requested URL",
+        ];
+        if self.hash_bodies {
+            header.push("Body Hash");
+        }
+        writer.write_record(header)?;
         for r in &self.responses {
-            writer.write_record(vec![
-                &r.url,
-                &r.response_time.as_millis().to_string(),
-                &r.response_size.to_string(),
-                &r.status_code.to_string(),
-            ])?;
+            let mut record = vec![
+                r.url.clone(),
+                r.response_time.as_millis().to_string(),
+                r.response_size.to_string(),
+                r.status_code.to_string(),
+                r.listed_url().to_string(),
+            ];
+            if self.hash_bodies {
+                record.push(r.body_hash.clone().unwrap_or_default());
+            }
+            writer.write_record(record)?;
+        }
+        if !quiet {
+            println!(
+                "\n📊 The CSV report was written to {}",
+                style(report_path.display()).underlined().cyan()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Write a Markdown report mirroring the text report, for pasting into pull
+    /// requests and issues: the summary and response time statistics, and the error
+    /// and slow responses, as GitHub-flavored tables.
+    pub fn write_markdown_report(
+        &self,
+        options: &Cli,
+        report_path: &PathBuf,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = report_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let stats = self.statistics_tables(options.slow_threshold);
+
+        let mut md = format!("# Statistics for {}\n\n", self.sitemap_url);
+        md.push_str(&format!(
+            "**{}**\n\n",
+            self.summary_line(options.slow_threshold)
+        ));
+        md.push_str("## Summary\n\n");
+        md.push_str(&markdown_metrics_table(
+            stats.summary.0.iter().chain(&stats.status_code.0),
+        ));
+        md.push_str("\n## Response Time and Performance Statistics\n\n");
+        md.push_str(&markdown_metrics_table(
+            stats.response_time.0.iter().chain(&stats.performance.0),
+        ));
+
+        let error_responses = self.error_responses();
+        if !error_responses.is_empty() {
+            md.push_str("\n## Error Responses\n\n");
+            md.push_str(&markdown_responses_table(&error_responses));
+        }
+
+        if let Some(threshold) = options.slow_threshold {
+            let slow_responses = self.slowest_responses(threshold, options.slow_num);
+            if !slow_responses.is_empty() {
+                md.push_str(&format!("\n## Slow Responses (>={}s)\n\n", threshold));
+                md.push_str(&markdown_responses_table(&slow_responses));
+            }
         }
-        if !quiet {
+
+        std::fs::write(report_path, md)?;
+
+        if !options.quiet() {
             println!(
-                "\n📊 The CSV report was written to {}",
+                "\n📝 The Markdown report was written to {}",
                 style(report_path.display()).underlined().cyan()
             );
         }
@@ -256,7 +1536,8 @@ impl Report {
             std::fs::create_dir_all(parent)?;
         }
 
-        let stats = self.generate_statistics(options.slow_threshold);
+        let summary = self.statistics(options.slow_threshold);
+        let stats = self.statistics_tables(options.slow_threshold);
         let total_requests = self.responses.len();
         let total_time_secs = self.total_time.as_secs_f64();
 
@@ -275,21 +1556,8 @@ impl Report {
             .map(|r| r.response_time.as_secs_f64() * 1000.0)
             .collect();
         let (histogram_svg, histogram_buckets_exist) = if !times_ms.is_empty() {
-            let min_t = times_ms.iter().cloned().fold(f64::INFINITY, f64::min);
-            let max_t = times_ms.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-            let bucket_count = 20usize;
-            let range = if (max_t - min_t).abs() < 0.001 {
-                1.0
-            } else {
-                max_t - min_t
-            };
-            let bucket_width = range / bucket_count as f64;
-            let mut buckets = vec![0usize; bucket_count];
-            for &t in &times_ms {
-                let idx = ((t - min_t) / bucket_width).floor() as usize;
-                let idx = idx.min(bucket_count - 1);
-                buckets[idx] += 1;
-            }
+            let bucket_count = HISTOGRAM_BUCKETS;
+            let (min_t, bucket_width, buckets) = response_time_histogram(&times_ms, bucket_count);
             let max_count = *buckets.iter().max().unwrap_or(&1);
             let chart_w = 600.0f64;
             let chart_h = 200.0f64;
@@ -348,68 +1616,13 @@ impl Report {
         let _ = histogram_buckets_exist;
 
         // Status code bar chart SVG
-        let status_svg = if !status_entries.is_empty() {
-            let max_count = status_entries.iter().map(|&(_, c)| c).max().unwrap_or(1);
-            let chart_w = 400.0f64;
-            let chart_h = 200.0f64;
-            let bar_w = chart_w / status_entries.len() as f64;
-            let mut svg = format!(
-                r#"<svg viewBox="0 0 {vw} {vh}" xmlns="http://www.w3.org/2000/svg" style="width:100%;max-width:500px">"#,
-                vw = chart_w + 60.0,
-                vh = chart_h + 60.0
-            );
-            for (i, &(code, count)) in status_entries.iter().enumerate() {
-                let bar_h = if max_count > 0 {
-                    (count as f64 / max_count as f64) * chart_h
-                } else {
-                    0.0
-                };
-                let x = 40.0 + i as f64 * bar_w;
-                let y = chart_h - bar_h + 10.0;
-                let color = if code < 300 {
-                    "#22c55e"
-                } else if code < 400 {
-                    "#eab308"
-                } else if code < 500 {
-                    "#f97316"
-                } else {
-                    "#ef4444"
-                };
-                svg.push_str(&format!(
-                    r#"<rect x="{x:.1}" y="{y:.1}" width="{bw:.1}" height="{bh:.1}" fill="{color}" rx="2"><title>{code}: {count}</title></rect>"#,
-                    x = x,
-                    y = y,
-                    bw = (bar_w - 4.0).max(4.0),
-                    bh = bar_h,
-                    color = color,
-                    code = code,
-                    count = count
-                ));
-                // Count label above bar
-                svg.push_str(&format!(
-                    r##"<text x="{x:.1}" y="{y:.1}" font-size="11" fill="#334155" text-anchor="middle" font-weight="600">{count}</text>"##,
-                    x = x + (bar_w - 4.0).max(4.0) / 2.0,
-                    y = y - 4.0,
-                    count = count
-                ));
-                // Code label below
-                svg.push_str(&format!(
-                    r##"<text x="{x:.1}" y="{y}" font-size="11" fill="#64748b" text-anchor="middle">{code}</text>"##,
-                    x = x + (bar_w - 4.0).max(4.0) / 2.0,
-                    y = chart_h + 28.0,
-                    code = code
-                ));
-            }
-            svg.push_str(&format!(
-                r##"<text x="{}" y="{}" font-size="11" fill="#64748b" text-anchor="middle">Status Code</text>"##,
-                40.0 + chart_w / 2.0,
-                chart_h + 50.0
-            ));
-            svg.push_str("</svg>");
-            svg
-        } else {
-            String::from("<p>No data available.</p>")
-        };
+        let status_svg = status_code_svg(&status_entries);
+
+        // Throughput over time bar chart SVG
+        let throughput_svg = throughput_svg(
+            &self.throughput(Duration::from_secs(options.throughput_interval)),
+            options.throughput_interval,
+        );
 
         // Build table rows
         let mut table_rows = String::new();
@@ -473,6 +1686,9 @@ tr:hover td{{background:#f8fafc}}
 .status-ok{{color:#16a34a;font-weight:600}}
 .status-redirect{{color:#ca8a04;font-weight:600}}
 .status-error{{color:#dc2626;font-weight:600}}
+.bar{{cursor:pointer}}
+.bar:hover{{opacity:.8}}
+#chart-tooltip{{position:fixed;pointer-events:none;background:#0f172a;color:#fff;font-size:.8rem;padding:6px 10px;border-radius:6px;white-space:nowrap;display:none;z-index:10}}
 .stats-grid{{display:grid;grid-template-columns:repeat(auto-fit,minmax(220px,1fr));gap:8px 24px}}
 .stat-row{{display:flex;justify-content:space-between;padding:6px 0;border-bottom:1px solid #f1f5f9}}
 .stat-label{{color:#64748b;font-size:.85rem}}
@@ -492,6 +1708,9 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
   <div class="card"><div class="label">Concurrency</div><div class="value">{concurrency}</div></div>
   <div class="card"><div class="label">Success Rate</div><div class="value">{success_rate}</div></div>
   <div class="card"><div class="label">Error Rate</div><div class="value">{error_rate}</div></div>
+  <div class="card"><div class="label">Cache Hit Rate</div><div class="value">{cache_hit_rate}</div></div>
+  <div class="card"><div class="label">Avg Response Time</div><div class="value">{avg_response_time}</div></div>
+  <div class="card"><div class="label">p95 Response Time</div><div class="value">{p95_response_time}</div></div>
 </div>
 
 <div class="section">
@@ -519,6 +1738,11 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
   </div>
 </div>
 
+<div class="section">
+<h2>Throughput Over Time</h2>
+{throughput_svg}
+</div>
+
 <div class="section">
 <h2>All Responses ({total})</h2>
 <div style="overflow-x:auto">
@@ -540,7 +1764,23 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
 
 <footer>Generated by Siteprobe {version}</footer>
 </div>
+<div id="chart-tooltip"></div>
 <script>
+(function(){{
+  const tip=document.getElementById('chart-tooltip');
+  document.querySelectorAll('rect.bar[data-code]').forEach(bar=>{{
+    bar.querySelector('title').remove();
+    bar.addEventListener('mousemove',function(e){{
+      const d=this.dataset;
+      const n=+d.count;
+      tip.textContent=d.code+' '+d.reason+': '+n+(n===1?' request':' requests')+' ('+d.pct+'%)';
+      tip.style.display='block';
+      tip.style.left=(e.clientX+12)+'px';
+      tip.style.top=(e.clientY+12)+'px';
+    }});
+    bar.addEventListener('mouseleave',function(){{tip.style.display='none'}});
+  }});
+}})();
 (function(){{
   const table=document.getElementById('responses');
   const headers=table.querySelectorAll('th');
@@ -573,8 +1813,11 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
             total = total_requests,
             rps = rps,
             concurrency = self.concurrency_limit,
-            success_rate = stats.status_code.0.iter().find(|e| e.json_label == "successRatePercentage").map(|e| e.value.clone()).unwrap_or_default(),
-            error_rate = stats.status_code.0.iter().find(|e| e.json_label == "errorRatePercentage").map(|e| e.value.clone()).unwrap_or_default(),
+            success_rate = utils::percent(summary.success_rate),
+            error_rate = utils::percent(summary.error_rate),
+            cache_hit_rate = utils::percent(summary.cache_hit_rate),
+            avg_response_time = utils::ms(summary.avg_response_time),
+            p95_response_time = utils::ms(summary.p95_response_time),
             response_time_stats = stats.response_time.0.iter().map(|e| format!(
                 r#"<div class="stat-row"><span class="stat-label">{}</span><span class="stat-value">{}</span></div>"#,
                 html_escape(e.label), html_escape(&e.value)
@@ -585,6 +1828,7 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
             )).collect::<Vec<_>>().join("\n"),
             histogram_svg = histogram_svg,
             status_svg = status_svg,
+            throughput_svg = throughput_svg,
             table_rows = table_rows,
             version = env!("CARGO_PKG_VERSION"),
         );
@@ -592,7 +1836,7 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
         let mut file = File::create(report_path)?;
         file.write_all(html.as_bytes())?;
 
-        if !options.json {
+        if !options.quiet() {
             println!(
                 "\n🌐 The HTML report was written to {}",
                 style(report_path.display()).underlined().cyan()
@@ -602,62 +1846,390 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
         Ok(())
     }
 
-    /// Determines the appropriate process exit code based on response results.
+    /// Determines the appropriate process exit code based on response results, see
+    /// [`Report::exit_status`].
+    pub fn exit_code(&self, slow_threshold: Option<f64>) -> ExitCode {
+        ExitCode::from(self.exit_status(slow_threshold))
+    }
+
+    /// Returns the exit status of the run, used by [`Report::exit_code`], the
+    /// `--status-file`, the summary line and the exit reason.
     ///
     /// - `0` — All URLs returned 2xx (success).
     /// - `1` — One or more URLs returned 4xx/5xx (errors). Takes priority over slow.
     /// - `2` — One or more URLs exceeded the slow threshold (when `--slow-threshold` is set).
-    pub fn exit_code(&self, slow_threshold: Option<f64>) -> ExitCode {
-        let has_errors = self
+    /// - `3` — The success rate is below `--min-success-rate`. Takes priority over errors.
+    /// - `4` — A performance budget was exceeded (`--max-p95-ms`, `--max-avg-ms`,
+    ///   `--max-error-rate`). Takes priority over errors.
+    ///
+    /// A run interrupted with Ctrl-C gets the exit status of its completed responses, so
+    /// a partial run with errors still fails. The interruption shows in the summary line
+    /// and the `--status-file` (`interrupted`, `partial`).
+    pub fn exit_status(&self, slow_threshold: Option<f64>) -> u8 {
+        if self.success_rate_shortfall().is_some() {
+            3
+        } else if !self.exceeded_budgets().is_empty() {
+            4
+        } else if self.responses.iter().any(|r| self.is_failure(r)) {
+            1
+        } else if self.slow_count(slow_threshold) > 0 {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Returns the number of responses that count as an error for the exit code, see
+    /// [`Report::is_failure`].
+    fn failure_count(&self) -> usize {
+        self.responses.iter().filter(|r| self.is_failure(r)).count()
+    }
+
+    /// Returns the number of responses slower than `slow_threshold` (in seconds).
+    fn slow_count(&self, slow_threshold: Option<f64>) -> usize {
+        slow_threshold.map_or(0, |threshold| {
+            self.responses
+                .iter()
+                .filter(|r| r.response_time.as_secs_f64() > threshold)
+                .count()
+        })
+    }
+
+    /// Returns the success rate ([`Statistics::success_rate`]) and `--min-success-rate`
+    /// if the rate is below the minimum. A run without responses has no rate to check.
+    pub fn success_rate_shortfall(&self) -> Option<(f64, f64)> {
+        let min = self.min_success_rate?;
+        if self.responses.is_empty() {
+            return None;
+        }
+        let rate = self.statistics(None).success_rate;
+        (rate < min).then_some((rate, min))
+    }
+
+    /// Describes each performance budget (`--max-p95-ms`, `--max-avg-ms`,
+    /// `--max-error-rate`) the run exceeded, e.g. "p95 response time of 812ms exceeds
+    /// --max-p95-ms 500". A run without responses has nothing to measure.
+    pub fn exceeded_budgets(&self) -> Vec<String> {
+        let mut exceeded = Vec::new();
+        if self.responses.is_empty()
+            || (self.max_p95_ms.is_none()
+                && self.max_avg_ms.is_none()
+                && self.max_error_rate.is_none())
+        {
+            return exceeded;
+        }
+        let stats = self.statistics(None);
+        if let Some(max) = self.max_p95_ms {
+            let p95 = stats.p95_response_time.as_millis();
+            if p95 > u128::from(max) {
+                exceeded.push(format!(
+                    "p95 response time of {}ms exceeds --max-p95-ms {}",
+                    p95, max
+                ));
+            }
+        }
+        if let Some(max) = self.max_avg_ms {
+            let avg = stats.avg_response_time.as_millis();
+            if avg > u128::from(max) {
+                exceeded.push(format!(
+                    "average response time of {}ms exceeds --max-avg-ms {}",
+                    avg, max
+                ));
+            }
+        }
+        if let Some(max) = self.max_error_rate {
+            if stats.error_rate > max {
+                exceeded.push(format!(
+                    "error rate of {:.2}% exceeds --max-error-rate {}",
+                    stats.error_rate, max
+                ));
+            }
+        }
+        exceeded
+    }
+
+    /// Whether a response counts as an error for the exit code, see
+    /// [`Response::is_failure`].
+    fn is_failure(&self, r: &Response) -> bool {
+        r.is_failure(self.redirects_are_errors, self.empty_body)
+    }
+
+    /// Whether a response is listed under "Error Responses": an error (see
+    /// [`Response::is_error`]), or a redirect with `--redirects-are-errors`.
+    fn is_error(&self, r: &Response) -> bool {
+        r.is_error() || (self.redirects_are_errors && r.redirected)
+    }
+
+    /// Returns the summed body size of all responses, in bytes.
+    pub fn total_bytes(&self) -> u64 {
+        self.responses.iter().map(|r| r.response_size as u64).sum()
+    }
+
+    /// Returns the 2xx responses that are not served as JSON, sorted by URL. Empty
+    /// unless `--json-api` is enabled.
+    pub fn non_json_responses(&self) -> Vec<Response> {
+        if !self.expect_json {
+            return Vec::new();
+        }
+        let mut responses: Vec<_> = self
+            .responses
+            .iter()
+            .filter(|r| r.status_code.is_success() && !r.is_json())
+            .cloned()
+            .collect();
+        responses.sort_unstable_by(|a, b| a.url.cmp(&b.url));
+        responses
+    }
+
+    /// Returns the responses whose redirect chain looped, or took more than
+    /// `threshold` redirects (`--warn-redirects`), sorted by URL. Loops are returned
+    /// without a threshold too.
+    pub fn excessive_redirects(&self, threshold: Option<usize>) -> Vec<Response> {
+        let mut responses: Vec<_> = self
+            .responses
+            .iter()
+            .filter(|r| r.redirect_loop || threshold.is_some_and(|t| r.redirect_count > t))
+            .cloned()
+            .collect();
+        responses.sort_unstable_by(|a, b| a.requested_url.cmp(&b.requested_url));
+        responses.dedup_by(|a, b| a.requested_url == b.requested_url);
+        responses
+    }
+
+    /// Returns the 2xx responses whose body failed a content check (`--expect-contains`,
+    /// `--expect-matches`), sorted by URL.
+    pub fn content_mismatches(&self) -> Vec<Response> {
+        let mut responses: Vec<_> = self
+            .responses
+            .iter()
+            .filter(|r| r.is_content_mismatch())
+            .cloned()
+            .collect();
+        responses.sort_unstable_by(|a, b| a.url.cmp(&b.url));
+        responses
+    }
+
+    /// Returns the 200 responses whose body contains a `--soft-404-marker`, sorted by
+    /// URL.
+    pub fn soft_404s(&self) -> Vec<Response> {
+        let mut responses: Vec<_> = self
             .responses
             .iter()
-            .any(|r| r.status_code.is_client_error() || r.status_code.is_server_error());
+            .filter(|r| r.soft_404)
+            .cloned()
+            .collect();
+        responses.sort_unstable_by(|a, b| a.url.cmp(&b.url));
+        responses
+    }
 
-        if has_errors {
-            return ExitCode::from(1);
+    /// Returns the 2xx responses with an empty body, sorted by URL. Empty unless
+    /// `--flag-empty-body` is enabled.
+    pub fn empty_responses(&self) -> Vec<Response> {
+        if self.empty_body.is_none() {
+            return Vec::new();
         }
+        let mut responses: Vec<_> = self
+            .responses
+            .iter()
+            .filter(|r| r.is_empty_body())
+            .cloned()
+            .collect();
+        responses.sort_unstable_by(|a, b| a.url.cmp(&b.url));
+        responses
+    }
 
-        if let Some(threshold) = slow_threshold {
-            let has_slow = self
+    /// Returns a one-line summary of the run and its exit code, e.g. "✅ 1190/1200 OK — exit 0"
+    /// or "❌ 8 errors, 2 slow — exit 1". An interrupted run is summarized as e.g.
+    /// "🛑 Interrupted after 40 responses: 1 error — exit 1".
+    pub fn summary_line(&self, slow_threshold: Option<f64>) -> String {
+        let total = self.responses.len();
+        let code = self.exit_status(slow_threshold);
+        let (icon, outcome) = if code == 0 {
+            let ok = self
                 .responses
                 .iter()
-                .any(|r| r.response_time.as_secs_f64() > threshold);
-            if has_slow {
-                return ExitCode::from(2);
+                .filter(|r| r.status_code.is_success())
+                .count();
+            ("✅", format!("{}/{} OK", ok, total))
+        } else {
+            ("❌", self.failed_outcome(slow_threshold))
+        };
+        if self.interrupted {
+            format!(
+                "🛑 Interrupted after {} responses: {} — exit {}",
+                total, outcome, code
+            )
+        } else {
+            format!("{} {} — exit {}", icon, outcome, code)
+        }
+    }
+
+    /// Lists what failed the run for [`Report::summary_line`], e.g. "8 errors, 2 slow".
+    fn failed_outcome(&self, slow_threshold: Option<f64>) -> String {
+        let errors = self.failure_count();
+        let slow = self.slow_count(slow_threshold);
+        let exceeded_budgets = self.exceeded_budgets().len();
+        let mut parts = Vec::new();
+        if let Some((rate, min)) = self.success_rate_shortfall() {
+            parts.push(format!("{:.2}% OK, below {}%", rate, min));
+        }
+        if exceeded_budgets > 0 {
+            parts.push(format!(
+                "{} budget{} exceeded",
+                exceeded_budgets,
+                if exceeded_budgets == 1 { "" } else { "s" }
+            ));
+        }
+        if errors > 0 {
+            parts.push(format!(
+                "{} error{}",
+                errors,
+                if errors == 1 { "" } else { "s" }
+            ));
+        }
+        if slow > 0 {
+            parts.push(format!("{} slow", slow));
+        }
+        parts.join(", ")
+    }
+
+    /// Explains the exit code returned by [`Report::exit_code`], e.g.
+    /// "Exiting with code 1 because 3 URLs returned 4xx/5xx or failed."
+    pub fn exit_reason(&self, slow_threshold: Option<f64>) -> String {
+        let reason = self.exit_status_reason(slow_threshold);
+        if self.interrupted {
+            format!(
+                "{} The run was interrupted with Ctrl-C, only the {} completed responses count.",
+                reason,
+                self.responses.len()
+            )
+        } else {
+            reason
+        }
+    }
+
+    /// Explains the exit status of the completed responses, see [`Report::exit_reason`].
+    fn exit_status_reason(&self, slow_threshold: Option<f64>) -> String {
+        match self.exit_status(slow_threshold) {
+            3 => {
+                let (rate, min) = self.success_rate_shortfall().unwrap_or_default();
+                format!(
+                    "Exiting with code 3 because the success rate of {:.2}% is below the minimum of {}% (--min-success-rate).",
+                    rate, min
+                )
             }
+            4 => format!(
+                "Exiting with code 4 because the performance budget was exceeded: {}.",
+                self.exceeded_budgets().join("; ")
+            ),
+            1 => self.failure_reason(),
+            2 => format!(
+                "Exiting with code 2 because {} URL(s) exceeded the slow threshold of {}s.",
+                self.slow_count(slow_threshold),
+                slow_threshold.unwrap_or_default()
+            ),
+            _ => "Exiting with code 0 because no URL returned an error or was slow.".to_string(),
+        }
+    }
+
+    /// Explains exit code `1`, naming the first kind of failure found (see
+    /// [`Report::is_failure`]).
+    fn failure_reason(&self) -> String {
+        let error_count = self.responses.iter().filter(|r| r.is_error()).count();
+        if error_count > 0 {
+            return format!(
+                "Exiting with code 1 because {} URL(s) returned 4xx/5xx or failed.",
+                error_count
+            );
+        }
+
+        let redirect_count = self.responses.iter().filter(|r| r.redirected).count();
+        if self.redirects_are_errors && redirect_count > 0 {
+            return format!(
+                "Exiting with code 1 because {} URL(s) redirected (--redirects-are-errors).",
+                redirect_count
+            );
         }
 
-        ExitCode::SUCCESS
+        let soft_404_count = self.soft_404s().len();
+        if soft_404_count > 0 {
+            return format!(
+                "Exiting with code 1 because {} URL(s) were soft 404s (--soft-404-marker).",
+                soft_404_count
+            );
+        }
+
+        let mismatch_count = self.content_mismatches().len();
+        if mismatch_count > 0 {
+            return format!(
+                "Exiting with code 1 because {} URL(s) failed a content check (--expect-contains, --expect-matches).",
+                mismatch_count
+            );
+        }
+
+        format!(
+            "Exiting with code 1 because {} URL(s) returned an empty body.",
+            self.empty_responses().len()
+        )
     }
 
     // === Statistics ==============================================================================
 
-    fn generate_statistics(&self, slow_threshold: Option<f64>) -> Statistics {
-        let report = &self;
-        let total_requests = report.responses.len();
-        let total_time_secs = report.total_time.as_secs_f64();
+    /// Computes the statistics over all responses. Responses slower than `slow_threshold`
+    /// (in seconds) count toward [`Statistics::slow_rate`].
+    ///
+    /// A library user can compute the statistics of responses it collected itself:
+    ///
+    /// ```rust
+    /// use reqwest::StatusCode;
+    /// use siteprobe::report::{Report, Response};
+    /// use std::time::Duration;
+    ///
+    /// let response = |status: StatusCode, ms: u64| Response {
+    ///     url: "https://example.com/".to_string(),
+    ///     status_code: status,
+    ///     response_time: Duration::from_millis(ms),
+    ///     ..Default::default()
+    /// };
+    /// let report = Report {
+    ///     total_time: Duration::from_secs(2),
+    ///     responses: vec![
+    ///         response(StatusCode::OK, 100),
+    ///         response(StatusCode::OK, 300),
+    ///         response(StatusCode::NOT_FOUND, 200),
+    ///         response(StatusCode::OK, 1400),
+    ///     ]
+    ///     .into(),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let stats = report.statistics(Some(1.0));
+    /// assert_eq!(stats.total_requests, 4);
+    /// assert_eq!(stats.requests_per_second, 2.0);
+    /// assert_eq!(stats.avg_response_time, Duration::from_millis(500));
+    /// assert_eq!(stats.max_response_time, Duration::from_millis(1400));
+    /// assert_eq!(stats.success_rate, 75.0);
+    /// assert_eq!(stats.error_rate, 25.0);
+    /// assert_eq!(stats.slow_rate, Some(25.0));
+    /// ```
+    pub fn statistics(&self, slow_threshold: Option<f64>) -> Statistics {
+        let total_requests = self.responses.len();
+        let total_time_secs = self.total_time.as_secs_f64();
 
-        let response_times: Vec<Duration> =
-            report.responses.iter().map(|r| r.response_time).collect();
-        let response_sizes: Vec<usize> = report.responses.iter().map(|r| r.response_size).collect();
+        // Sorted, so the median and percentiles can be read by position.
+        let mut response_times: Vec<Duration> =
+            self.responses.iter().map(|r| r.response_time).collect();
+        response_times.sort_unstable();
+        let mut ttfbs: Vec<Duration> = self.responses.iter().map(|r| r.ttfb).collect();
+        ttfbs.sort_unstable();
+        let response_sizes: Vec<usize> = self.responses.iter().map(|r| r.response_size).collect();
 
         let avg_response_time = if total_requests > 0 {
             response_times.iter().map(|d| d.as_secs_f64()).sum::<f64>() / total_requests as f64
         } else {
             0.0
         };
-        let median_response_time = response_times.get(response_times.len() / 2).copied();
-        let min_response_time = response_times.iter().copied().min();
-        let max_response_time = response_times.iter().copied().max();
-        let p90_response_time = response_times
-            .get((response_times.len() as f64 * 0.90) as usize)
-            .copied();
-        let p95_response_time = response_times
-            .get((response_times.len() as f64 * 0.95) as usize)
-            .copied();
-        let p99_response_time = response_times
-            .get((response_times.len() as f64 * 0.99) as usize)
-            .copied();
+        let median_response_time = median(&response_times);
 
         let variance = if total_requests > 0 {
             response_times
@@ -668,25 +2240,26 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
         } else {
             0.0
         };
-        let std_dev = variance.sqrt();
 
-        let mut status_counts: HashMap<StatusCode, usize> = HashMap::new();
         let mut success_count = 0;
         let mut error_count = 0;
         let mut redirect_count = 0;
+        let mut cache_hit_count = 0;
         let mut slow_count = 0;
 
-        for response in &report.responses {
-            *status_counts.entry(response.status_code).or_insert(0) += 1;
+        for response in &self.responses {
             if response.status_code.is_success() {
                 success_count += 1;
-            } else if response.status_code.is_client_error()
-                || response.status_code.is_server_error()
-            {
+            } else if response.is_error() {
                 error_count += 1;
-            } else if response.status_code.is_redirection() {
+            }
+            // Counted separately from the status, so followed redirects are included.
+            if response.redirected || is_redirect(response.status_code) {
                 redirect_count += 1;
             }
+            if response.cache_hit {
+                cache_hit_count += 1;
+            }
 
             if let Some(threshold) = slow_threshold {
                 if response.response_time.as_secs_f64() > threshold {
@@ -695,155 +2268,357 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
             }
         }
 
-        let success_rate = if total_requests > 0 {
-            (success_count as f64 / total_requests as f64) * 100.0
-        } else {
-            0.0
-        };
-        let error_rate = if total_requests > 0 {
-            (error_count as f64 / total_requests as f64) * 100.0
-        } else {
-            0.0
-        };
-        let redirect_rate = if total_requests > 0 {
-            (redirect_count as f64 / total_requests as f64) * 100.0
-        } else {
-            0.0
-        };
-        let slow_request_percentage = if total_requests > 0 {
-            (slow_count as f64 / total_requests as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        let avg_response_size = if total_requests > 0 {
-            response_sizes.iter().sum::<usize>() / total_requests
-        } else {
-            0
+        let rate = |count: usize| {
+            if total_requests > 0 {
+                (count as f64 / total_requests as f64) * 100.0
+            } else {
+                0.0
+            }
         };
-        let min_response_size = response_sizes.iter().copied().min();
-        let max_response_size = response_sizes.iter().copied().max();
 
         Statistics {
+            total_requests,
+            requests_per_second: if total_time_secs > 0.0 {
+                total_requests as f64 / total_time_secs
+            } else {
+                0.0
+            },
+            avg_response_time: Duration::from_secs_f64(avg_response_time),
+            median_response_time,
+            min_response_time: response_times.first().copied().unwrap_or_default(),
+            max_response_time: response_times.last().copied().unwrap_or_default(),
+            p90_response_time: percentile(&response_times, 0.90),
+            p95_response_time: percentile(&response_times, 0.95),
+            p99_response_time: percentile(&response_times, 0.99),
+            std_dev_response_time: Duration::from_secs_f64(variance.sqrt()),
+            avg_ttfb: ttfbs
+                .iter()
+                .sum::<Duration>()
+                .checked_div(total_requests as u32)
+                .unwrap_or_default(),
+            median_ttfb: median(&ttfbs),
+            p95_ttfb: percentile(&ttfbs, 0.95),
+            success_rate: rate(success_count),
+            error_rate: rate(error_count),
+            redirect_rate: rate(redirect_count),
+            cache_hit_rate: rate(cache_hit_count),
+            slow_rate: slow_threshold.map(|_| rate(slow_count)),
+            avg_response_size: response_sizes
+                .iter()
+                .sum::<usize>()
+                .checked_div(total_requests)
+                .unwrap_or(0),
+            min_response_size: response_sizes.iter().copied().min().unwrap_or_default(),
+            max_response_size: response_sizes.iter().copied().max().unwrap_or_default(),
+        }
+    }
+
+    /// Builds the labeled statistics entries shown in the text, Markdown, JSON and HTML reports,
+    /// from [`Report::statistics`].
+    pub fn statistics_tables(&self, slow_threshold: Option<f64>) -> StatisticsTables {
+        let stats = self.statistics(slow_threshold);
+        let mut summary = Metrics(vec![
+            Entry {
+                label: "Concurrency Limit",
+                value: self.concurrency_limit.to_string(),
+                json_label: "concurrencyLimit",
+                json_value: json!(self.concurrency_limit),
+            },
+            Entry {
+                label: "Rate Limit",
+                value: match self.rate_limit {
+                    Some(rate_limit) => format!("{}/min", rate_limit),
+                    None => "No".to_string(),
+                },
+                json_label: "rateLimit",
+                json_value: json!(self.rate_limit),
+            },
+            Entry {
+                label: "Elapsed Time",
+                value: format!("{:.2?}", self.total_time),
+                json_label: "elapsedTimeMs",
+                json_value: json!(self.total_time.as_millis()),
+            },
+            Entry {
+                label: "Bypass Caching",
+                value: if self.bypass_caching {
+                    "Yes".to_string()
+                } else {
+                    "No".to_string()
+                },
+                json_label: "bypassCaching",
+                json_value: json!(self.bypass_caching),
+            },
+        ]);
+        if let Some(max) = self.max_total_bytes {
+            summary.0.push(Entry {
+                label: "Downloaded",
+                value: format!(
+                    "{} of {}{}",
+                    utils::kb(self.total_bytes() as usize),
+                    utils::kb(max as usize),
+                    if self.byte_capped { " (capped)" } else { "" }
+                ),
+                json_label: "totalBytes",
+                json_value: json!(self.total_bytes()),
+            });
+        }
+        if let Some(max) = self.max_duration {
+            summary.0.push(Entry {
+                label: "Time Budget",
+                value: format!(
+                    "{}s{}",
+                    max,
+                    if self.time_capped { " (reached)" } else { "" }
+                ),
+                json_label: "maxDuration",
+                json_value: json!(max),
+            });
+        }
+        if let Some(collapsed) = self.collapsed_urls {
+            summary.0.push(Entry {
+                label: "Collapsed URLs",
+                value: collapsed.to_string(),
+                json_label: "collapsedUrls",
+                json_value: json!(collapsed),
+            });
+        }
+        if let Some(skipped) = self.skipped_unchanged {
+            summary.0.push(Entry {
+                label: "Skipped Unchanged",
+                value: skipped.to_string(),
+                json_label: "skippedUnchanged",
+                json_value: json!(skipped),
+            });
+        }
+        if let Some(skipped) = self.skipped_unmodified {
+            summary.0.push(Entry {
+                label: "Skipped Unmodified",
+                value: skipped.to_string(),
+                json_label: "skippedUnmodified",
+                json_value: json!(skipped),
+            });
+        }
+        if self.warmup_requests > 0 {
+            summary.0.push(Entry {
+                label: "Warm-up Requests",
+                value: self.warmup_requests.to_string(),
+                json_label: "warmupRequests",
+                json_value: json!(self.warmup_requests),
+            });
+        }
+
+        StatisticsTables {
+            summary,
             response_time: Metrics(vec![
                 Entry {
                     label: "⏰ Average Response Time",
-                    value: utils::ms(Duration::from_secs_f64(avg_response_time)),
+                    value: utils::ms(stats.avg_response_time),
                     json_label: "avgMs",
-                    json_value: json!(Duration::from_secs_f64(avg_response_time).as_millis()),
+                    json_value: json!(stats.avg_response_time.as_millis()),
                 },
                 Entry {
                     label: "🔷 Median Response Time",
-                    value: utils::ms(median_response_time.unwrap_or_default()),
+                    value: utils::ms(stats.median_response_time),
                     json_label: "medianMs",
-                    json_value: json!(median_response_time.unwrap_or_default().as_millis()),
+                    json_value: json!(stats.median_response_time.as_millis()),
                 },
                 Entry {
                     label: "🐇 Min Response Time",
-                    value: utils::ms(min_response_time.unwrap_or_default()),
+                    value: utils::ms(stats.min_response_time),
                     json_label: "minMs",
-                    json_value: json!(min_response_time.unwrap_or_default().as_millis()),
+                    json_value: json!(stats.min_response_time.as_millis()),
                 },
                 Entry {
                     label: "🐌 Max Response Time",
-                    value: utils::ms(max_response_time.unwrap_or_default()),
+                    value: utils::ms(stats.max_response_time),
                     json_label: "maxMs",
-                    json_value: json!(max_response_time.unwrap_or_default().as_millis()),
+                    json_value: json!(stats.max_response_time.as_millis()),
                 },
                 Entry {
                     label: "📏 P90 Response Time",
-                    value: utils::ms(p90_response_time.unwrap_or_default()),
+                    value: utils::ms(stats.p90_response_time),
                     json_label: "p90Ms",
-                    json_value: json!(p90_response_time.unwrap_or_default().as_millis()),
+                    json_value: json!(stats.p90_response_time.as_millis()),
                 },
                 Entry {
                     label: "🎯 P95 Response Time",
-                    value: utils::ms(p95_response_time.unwrap_or_default()),
+                    value: utils::ms(stats.p95_response_time),
                     json_label: "p95Ms",
-                    json_value: json!(p95_response_time.unwrap_or_default().as_millis()),
+                    json_value: json!(stats.p95_response_time.as_millis()),
                 },
                 Entry {
                     label: "🚀 P99 Response Time",
-                    value: utils::ms(p99_response_time.unwrap_or_default()),
+                    value: utils::ms(stats.p99_response_time),
                     json_label: "p99Ms",
-                    json_value: json!(p99_response_time.unwrap_or_default().as_millis()),
+                    json_value: json!(stats.p99_response_time.as_millis()),
                 },
                 Entry {
                     label: "📊 Standard Deviation",
-                    value: utils::ms(Duration::from_secs_f64(std_dev)),
+                    value: utils::ms(stats.std_dev_response_time),
                     json_label: "stdDevMs",
-                    json_value: json!(Duration::from_secs_f64(std_dev).as_millis()),
+                    json_value: json!(stats.std_dev_response_time.as_millis()),
+                },
+                Entry {
+                    label: "📨 Average TTFB",
+                    value: utils::ms(stats.avg_ttfb),
+                    json_label: "avgTtfbMs",
+                    json_value: json!(stats.avg_ttfb.as_millis()),
+                },
+                Entry {
+                    label: "📬 Median TTFB",
+                    value: utils::ms(stats.median_ttfb),
+                    json_label: "medianTtfbMs",
+                    json_value: json!(stats.median_ttfb.as_millis()),
+                },
+                Entry {
+                    label: "📮 P95 TTFB",
+                    value: utils::ms(stats.p95_ttfb),
+                    json_label: "p95TtfbMs",
+                    json_value: json!(stats.p95_ttfb.as_millis()),
                 },
             ]),
             status_code: Metrics(vec![
                 Entry {
                     label: "✅ Success Rate",
-                    value: utils::percent(success_rate),
+                    value: utils::percent(stats.success_rate),
                     json_label: "successRatePercentage",
-                    json_value: json!(success_rate),
+                    json_value: json!(stats.success_rate),
                 },
                 Entry {
                     label: "🚨 Error Rate",
-                    value: utils::percent(error_rate),
+                    value: utils::percent(stats.error_rate),
                     json_label: "errorRatePercentage",
-                    json_value: json!(error_rate),
+                    json_value: json!(stats.error_rate),
                 },
                 Entry {
                     label: "🔄 Redirect Rate",
-                    value: utils::percent(redirect_rate),
+                    value: utils::percent(stats.redirect_rate),
                     json_label: "redirectRatePercentage",
-                    json_value: json!(redirect_rate),
+                    json_value: json!(stats.redirect_rate),
+                },
+                Entry {
+                    label: "💾 Cache Hit Rate",
+                    value: utils::percent(stats.cache_hit_rate),
+                    json_label: "cacheHitRatePercentage",
+                    json_value: json!(stats.cache_hit_rate),
                 },
             ]),
             performance: Metrics(vec![
                 Entry {
                     label: "⚡️ Total Requests Processed",
-                    value: total_requests.to_string(),
+                    value: stats.total_requests.to_string(),
                     json_label: "totalRequests",
-                    json_value: json!(total_requests),
+                    json_value: json!(stats.total_requests),
                 },
                 Entry {
                     label: "⏳ Requests Per Second (RPS)",
-                    value: if total_time_secs > 0.0 {
-                        format!("{:.02} / sec", total_requests as f64 / total_time_secs)
-                    } else {
+                    value: if self.total_time.is_zero() {
                         "0 / sec".to_string()
+                    } else {
+                        format!("{:.02} / sec", stats.requests_per_second)
                     },
                     json_label: "requestsPerSecond",
-                    json_value: json!(total_requests as f64 / total_time_secs),
+                    json_value: json!(stats.requests_per_second),
                 },
                 Entry {
                     label: "📊 Slow Request Percentage",
-                    value: if slow_threshold.is_some() {
-                        utils::percent(slow_request_percentage)
-                    } else {
-                        "Not Set".to_string()
+                    value: match stats.slow_rate {
+                        Some(rate) => utils::percent(rate),
+                        None => "Not Set".to_string(),
                     },
                     json_label: "slowRequestPercentage",
-                    json_value: json!(slow_request_percentage),
+                    json_value: json!(stats.slow_rate.unwrap_or(0.0)),
                 },
                 Entry {
                     label: "📦 Average Response Size",
-                    value: utils::kb(avg_response_size),
+                    value: utils::kb(stats.avg_response_size),
                     json_label: "avgResponseSizeBytes",
-                    json_value: json!(avg_response_size),
+                    json_value: json!(stats.avg_response_size),
                 },
                 Entry {
                     label: "🔹 Min Response Size",
-                    value: utils::kb(min_response_size.unwrap_or_default()),
+                    value: utils::kb(stats.min_response_size),
                     json_label: "minResponseSizeBytes",
-                    json_value: json!(min_response_size.unwrap_or_default()),
+                    json_value: json!(stats.min_response_size),
                 },
                 Entry {
                     label: "🔺 Max Response Size",
-                    value: utils::kb(max_response_size.unwrap_or_default()),
+                    value: utils::kb(stats.max_response_size),
                     json_label: "maxResponseSizeBytes",
-                    json_value: json!(max_response_size.unwrap_or_default()),
+                    json_value: json!(stats.max_response_size),
                 },
             ]),
         }
     }
 
+    /// Computes the requests per second over the run, bucketed by `interval`.
+    ///
+    /// Each response is counted in the bucket of its `started_at` time, measured from
+    /// the earliest request. Returns one rate per bucket, in chronological order.
+    pub fn throughput(&self, interval: Duration) -> Vec<f64> {
+        let Some(first) = self.responses.iter().map(|r| r.started_at).min() else {
+            return Vec::new();
+        };
+        let interval_secs = interval.as_secs_f64().max(f64::EPSILON);
+
+        let mut buckets: Vec<usize> = Vec::new();
+        for r in &self.responses {
+            let idx = ((r.started_at - first).as_secs_f64() / interval_secs).floor() as usize;
+            if idx >= buckets.len() {
+                buckets.resize(idx + 1, 0);
+            }
+            buckets[idx] += 1;
+        }
+
+        buckets
+            .into_iter()
+            .map(|count| count as f64 / interval_secs)
+            .collect()
+    }
+
+    /// Groups the responses by URL and computes per-URL response time statistics.
+    ///
+    /// Responses are grouped by their [`Response::listed_url`]. The result is sorted by
+    /// URL.
+    pub fn url_aggregates(&self) -> Vec<UrlAggregate> {
+        let mut groups: BTreeMap<&str, Vec<Duration>> = BTreeMap::new();
+        for r in &self.responses {
+            groups
+                .entry(r.listed_url())
+                .or_default()
+                .push(r.response_time);
+        }
+
+        groups
+            .into_iter()
+            .map(|(url, times)| UrlAggregate {
+                url: url.to_string(),
+                samples: times.len(),
+                avg_response_time: times.iter().sum::<Duration>() / times.len() as u32,
+                min_response_time: times.iter().copied().min().unwrap_or_default(),
+                max_response_time: times.iter().copied().max().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Counts the responses per `Server` header value, most common first. Responses
+    /// without a `Server` header are not counted.
+    pub fn server_distribution(&self) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for r in &self.responses {
+            if let Some(server) = r.headers.get("server") {
+                *counts.entry(server.as_str()).or_default() += 1;
+            }
+        }
+        let mut servers: Vec<_> = counts
+            .into_iter()
+            .map(|(server, count)| (server.to_string(), count))
+            .collect();
+        servers.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        servers
+    }
+
     /// Filters and retrieves the slowest HTTP responses from the report.
     ///
     /// This function identifies HTTP responses with a response time exceeding the specified
@@ -863,23 +2638,46 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
     /// A `Vec<Response>` containing at most `limit` responses sorted by `response_time`
     /// in descending order. Each response in the vector has a `response_time` greater
     /// than the given threshold.
-    fn slowest_responses(&self, threshold: f64, limit: u32) -> Vec<Response> {
+    pub fn slowest_responses(&self, threshold: f64, limit: u32) -> Vec<Response> {
         let mut responses: Vec<_> = self
             .responses
             .iter()
             .filter(|r| r.response_time.as_secs_f64() >= threshold)
             .cloned()
             .collect();
-        responses.sort_unstable_by(|a, b| b.response_time.cmp(&a.response_time));
+        responses.sort_unstable_by_key(|r| std::cmp::Reverse(r.response_time));
         responses.into_iter().take(limit as usize).collect()
     }
 
+    /// Like [`Report::slowest_responses`], but grouped by status class, so slow but
+    /// working URLs can be told apart from slow and failing ones.
+    ///
+    /// Returns one `(class, responses)` pair per status class with slow responses,
+    /// e.g. `("5xx", ...)`, ordered by class. Each group holds at most `limit` responses.
+    pub fn slowest_responses_by_status(
+        &self,
+        threshold: f64,
+        limit: u32,
+    ) -> Vec<(String, Vec<Response>)> {
+        let mut groups: BTreeMap<u16, Vec<Response>> = BTreeMap::new();
+        for r in self.slowest_responses(threshold, u32::MAX) {
+            let group = groups.entry(r.status_code.as_u16() / 100).or_default();
+            if group.len() < limit as usize {
+                group.push(r);
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(class, responses)| (format!("{}xx", class), responses))
+            .collect()
+    }
+
     /// Filters and returns a sorted list of error responses from the report.
     ///
     /// # Description
     /// This function processes the `responses` field of the `Report` struct to extract
     /// all responses whose HTTP status codes indicate either client errors (4xx)
-    /// or server errors (5xx). The resulting list is then sorted primarily by
+    /// or server errors (5xx), and redirects with `--redirects-are-errors`. The resulting list is then sorted primarily by
     /// status code in descending order, and secondarily by URL in ascending order.
     ///
     /// # Returns
@@ -892,11 +2690,11 @@ footer{{text-align:center;color:#94a3b8;font-size:.75rem;padding:24px 0}}
     /// # See Also
     /// `Response` - Contains details about individual HTTP requests, such as the
     /// URL, status code, response time, etc.
-    fn error_responses(&self) -> Vec<Response> {
+    pub fn error_responses(&self) -> Vec<Response> {
         let mut responses: Vec<_> = self
             .responses
             .iter()
-            .filter(|r| r.status_code.is_client_error() || r.status_code.is_server_error())
+            .filter(|r| self.is_error(r))
             .cloned()
             .collect();
 
@@ -1,24 +1,38 @@
-use crate::network::get_url_response;
-use crate::options::Cli;
-use crate::report::Report;
+use crate::network::{build_request_headers, get_url_response, ContentChecks, MAX_RETRY_AFTER};
+use crate::options::{Cli, SitemapSource};
+use crate::report::{Report, Response};
+use crate::storage::OutputDir;
+use crate::term::{self, style};
 use crate::utils;
-use console::style;
+use crate::warnings::Warnings;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use encoding_rs::{Encoding, UTF_8};
 use flate2::read::GzDecoder;
 use futures::future::join_all;
+use futures::stream::{self, FuturesUnordered, StreamExt};
 use governor::clock::DefaultClock;
 use governor::state::{InMemoryState, NotKeyed};
 use governor::{Quota, RateLimiter};
+use once_cell::sync::Lazy;
 use quick_xml::events::Event;
 use quick_xml::Reader;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use regex::Regex;
 use reqwest::Client;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
+use std::future::Future;
 use std::io::Read;
 use std::num::NonZeroU32;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Semaphore;
 use tokio::time::Instant;
+use url::Url;
 
 // region: Structs & Enums
 #[derive(Debug, PartialEq)]
@@ -28,11 +42,105 @@ pub enum SitemapType {
     Unknown,
 }
 
+/// Duplicate `<loc>` entries found while collecting the sitemap URLs.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DuplicateUrls {
+    /// URLs listed more than once within the same sitemap file (usually a generator bug).
+    pub within_sitemap: Vec<String>,
+    /// URLs listed in more than one sitemap file (often benign overlap).
+    pub across_sitemaps: Vec<String>,
+}
+
+impl DuplicateUrls {
+    pub fn is_empty(&self) -> bool {
+        self.within_sitemap.is_empty() && self.across_sitemaps.is_empty()
+    }
+}
+
+/// Path depth and length of the sitemap URLs (`--url-structure`). Very deep or long
+/// URLs often indicate parameterized pages or crawl traps.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UrlStructure {
+    /// The highest number of path segments of any URL.
+    pub max_depth: usize,
+    /// The average number of path segments.
+    pub avg_depth: f64,
+    /// The deepest URLs with their path depth, deepest first.
+    pub deepest: Vec<(String, usize)>,
+    /// The longest URLs with their length in characters, longest first.
+    pub longest: Vec<(String, usize)>,
+}
+
+/// Number of unique hosts and URLs per scheme in the sitemap, shown before probing to
+/// catch unexpected external hosts or plain `http://` URLs early.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UrlOverview {
+    pub urls: usize,
+    pub hosts: usize,
+    pub http: usize,
+    pub https: usize,
+}
+
+/// The deduplicated URLs collected from a sitemap, along with discovery details.
+#[derive(Debug, Default)]
+pub struct SitemapUrls {
+    pub urls: Vec<String>,
+    pub duplicates: DuplicateUrls,
+    /// The `<lastmod>` value of the URLs that have one. If a URL is listed more than
+    /// once, the first value is kept.
+    pub lastmod: HashMap<String, String>,
+    /// The sitemap files the URLs were collected from, with the number of URLs each
+    /// lists (before deduplication).
+    pub sitemaps: Vec<(String, usize)>,
+}
+
+/// What a run would do, shown by `--dry-run` instead of probing.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DryRun {
+    /// Number of URLs that would be probed, after all filters.
+    pub urls: usize,
+    /// Number of requests, including `--repeat` and `--warmup`.
+    pub requests: usize,
+    /// The sitemap files with the number of URLs each lists.
+    pub sitemaps: Vec<(String, usize)>,
+    /// The least time the requests take with `--rate-limit`, zero without one.
+    pub min_time: Duration,
+    /// The time the requests take if every attempt times out, given the concurrency,
+    /// `--request-timeout` and `--retries`.
+    pub max_time: Duration,
+}
+
+/// A fetched sitemap file and the `Content-Type` it was served with.
+struct SitemapFile {
+    content: String,
+    content_type: Option<String>,
+    /// The encoding the sitemap was decoded from, if it isn't UTF-8.
+    encoding: Option<&'static str>,
+}
+
 pub struct RateLimitSetup {
     pub limit: Option<u32>,
     pub limiter: Option<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
 }
 
+impl fmt::Display for DryRun {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Dry run: {} URLs would be probed with {} requests.",
+            self.urls, self.requests
+        )?;
+        for (sitemap, urls) in &self.sitemaps {
+            writeln!(f, "  {}: {} URLs", sitemap, urls)?;
+        }
+        write!(f, "Estimated time: ")?;
+        if !self.min_time.is_zero() {
+            write!(f, "at least {:.0?} with --rate-limit, ", self.min_time)?;
+        }
+        write!(f, "up to {:.0?} if every request times out.", self.max_time)
+    }
+}
+
 // Implement Display for SitemapType
 impl fmt::Display for SitemapType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -43,14 +151,63 @@ impl fmt::Display for SitemapType {
 
 // region: Functions
 
-/// Decompresses gzip-compressed bytes into a UTF-8 string.
-pub fn decompress_gzip(bytes: &[u8]) -> Result<String, Box<dyn Error>> {
+/// Decompresses gzip-compressed bytes. The result is decoded with [`decode_sitemap`].
+pub fn decompress_gzip(bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
     let mut decoder = GzDecoder::new(bytes);
-    let mut decompressed = String::new();
-    decoder.read_to_string(&mut decompressed)?;
+    let mut decompressed = Vec::new();
+    decoder.read_to_end(&mut decompressed)?;
     Ok(decompressed)
 }
 
+/// Decodes a sitemap body into a string. Sitemaps should be UTF-8, but some are served
+/// in a legacy encoding such as ISO-8859-1 or Windows-1252. The encoding is taken from
+/// the `charset` of the `Content-Type` header, then from the XML declaration, and
+/// defaults to UTF-8.
+///
+/// Returns the decoded content and the name of the encoding if it isn't UTF-8.
+pub fn decode_sitemap(
+    bytes: &[u8],
+    content_type: Option<&str>,
+) -> Result<(String, Option<&'static str>), Box<dyn Error>> {
+    let label = content_type
+        .and_then(charset_from_content_type)
+        .or_else(|| xml_declared_encoding(bytes));
+    let encoding = label
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+
+    // A byte order mark overrides the declared encoding.
+    let (content, encoding, had_errors) = encoding.decode(bytes);
+    if had_errors {
+        return Err(format!("The sitemap is not valid {}.", encoding.name()).into());
+    }
+    let non_utf8 = (encoding != UTF_8).then(|| encoding.name());
+    Ok((content.into_owned(), non_utf8))
+}
+
+/// Returns the `charset` parameter of a `Content-Type` header value.
+fn charset_from_content_type(content_type: &str) -> Option<String> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Returns the `encoding` of the XML declaration (`<?xml ... encoding="..."?>`).
+fn xml_declared_encoding(bytes: &[u8]) -> Option<String> {
+    let head = &bytes[..bytes.len().min(256)];
+    let head = String::from_utf8_lossy(head);
+    let declaration = &head[head.find("<?xml")?..];
+    let declaration = &declaration[..declaration.find("?>")?];
+    let value = declaration.split("encoding").nth(1)?.trim_start();
+    let value = value.strip_prefix('=')?.trim_start();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    Some(value[..value.find(quote)?].to_string())
+}
+
 /// Checks if the content is gzip-compressed, either by URL suffix
 /// or by inspecting the gzip magic bytes (0x1f, 0x8b).
 pub fn is_gzip_content(url: &str, bytes: &[u8]) -> bool {
@@ -62,41 +219,389 @@ pub fn is_gzip_content(url: &str, bytes: &[u8]) -> bool {
 }
 
 /// Fetches a sitemap URL, automatically decompressing gzip content if detected.
-async fn get_sitemap_content(url: &str, client: &Client) -> Result<String, Box<dyn Error>> {
-    let response = client.get(url).send().await?.error_for_status()?;
-    let bytes = response.bytes().await?;
+async fn get_sitemap_content(url: &str, client: &Client) -> Result<SitemapFile, Box<dyn Error>> {
+    let (content_type, bytes) = match Url::parse(url).ok().filter(|u| u.scheme() == "file") {
+        Some(file_url) => (None, read_local_sitemap(&file_url)?),
+        None => {
+            let response = client.get(url).send().await?.error_for_status()?;
+            let content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from);
+            (content_type, response.bytes().await?.to_vec())
+        }
+    };
 
-    if is_gzip_content(url, &bytes) {
-        decompress_gzip(&bytes)
+    let bytes = if is_gzip_content(url, &bytes) {
+        decompress_gzip(&bytes)?
     } else {
-        Ok(String::from_utf8(bytes.to_vec())?)
+        bytes
+    };
+    let (content, encoding) = decode_sitemap(&bytes, content_type.as_deref())?;
+    Ok(SitemapFile {
+        content,
+        content_type,
+        encoding,
+    })
+}
+
+/// Reads a sitemap from disk, given as a `file://` URL, e.g. to check a generated
+/// sitemap before it's deployed.
+fn read_local_sitemap(file_url: &Url) -> Result<Vec<u8>, Box<dyn Error>> {
+    let path = file_url
+        .to_file_path()
+        .map_err(|_| format!("Invalid file URL: {}", file_url))?;
+    std::fs::read(&path)
+        .map_err(|e| format!("Unable to read the sitemap file {}: {}", path.display(), e).into())
+}
+
+/// Checks the `Content-Type` a sitemap was served with.
+///
+/// A sitemap served as `text/html` often means a misconfigured route returning e.g.
+/// the homepage. If the body is a valid sitemap anyway, a warning is returned. If the
+/// body is HTML as well, an error explaining the likely cause is returned.
+pub fn check_sitemap_content_type(
+    sitemap_url: &str,
+    content_type: Option<&str>,
+    content: &str,
+) -> Result<Option<String>, String> {
+    let Some(content_type) = content_type else {
+        return Ok(None);
+    };
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+    if mime != "text/html" {
+        return Ok(None);
+    }
+
+    if identify_sitemap_type(content) != SitemapType::Unknown {
+        return Ok(Some(format!(
+            "The sitemap {} is served as '{}'. Use 'application/xml' or 'text/xml' instead.",
+            sitemap_url, content_type
+        )));
     }
+
+    if looks_like_html(content) {
+        return Err(format!(
+            "The sitemap URL returned an HTML page (Content-Type '{}') instead of a sitemap. \
+             The route may be misconfigured and serve another page, e.g. the homepage: {}",
+            content_type, sitemap_url
+        ));
+    }
+
+    Ok(None)
+}
+
+/// Returns true if the content is an HTML page rather than XML.
+fn looks_like_html(content: &str) -> bool {
+    let head = content.trim_start().to_lowercase();
+    head.starts_with("<!doctype html") || head.starts_with("<html") || head.contains("<body")
+}
+
+/// Extracts the links of an HTML sitemap, a page listing the site's pages as `<a>`
+/// elements (`--html-sitemap`). Relative links are resolved against `page_url`. Only
+/// links with the same origin as the page are kept, without fragments and in the
+/// order they first appear.
+pub fn extract_html_links(html: &str, page_url: &str) -> Vec<String> {
+    static ANCHOR_HREF: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"(?is)<a\s[^>]*?\bhref\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap()
+    });
+    let Ok(page) = Url::parse(page_url) else {
+        return Vec::new();
+    };
+
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for captures in ANCHOR_HREF.captures_iter(html) {
+        let href = captures
+            .get(1)
+            .or_else(|| captures.get(2))
+            .or_else(|| captures.get(3))
+            .map_or("", |m| m.as_str())
+            .trim()
+            .replace("&amp;", "&");
+        let Ok(mut link) = page.join(&href) else {
+            continue;
+        };
+        if link.origin() != page.origin() {
+            continue;
+        }
+        link.set_fragment(None);
+        let link = link.to_string();
+        if seen.insert(link.clone()) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+/// Returns true if any error in the `source()` chain satisfies the predicate.
+fn error_chain_any(
+    e: &(dyn Error + 'static),
+    predicate: impl Fn(&(dyn Error + 'static)) -> bool,
+) -> bool {
+    let mut current = Some(e);
+    while let Some(err) = current {
+        if predicate(err) {
+            return true;
+        }
+        current = err.source();
+    }
+    false
+}
+
+/// Whether the error is a failed connection because of TLS, e.g. an invalid certificate.
+/// rustls reports it wrapped in (possibly nested) `io::Error`s, whose `source()` skips
+/// the wrapped error.
+fn is_tls_error(e: &(dyn Error + 'static)) -> bool {
+    let connect = e
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(reqwest::Error::is_connect);
+    connect
+        && error_chain_any(e, |err| {
+            let mut current = Some(err);
+            while let Some(err) = current {
+                if err.is::<rustls::Error>() {
+                    return true;
+                }
+                current = err
+                    .downcast_ref::<std::io::Error>()
+                    .and_then(std::io::Error::get_ref)
+                    .map(|inner| inner as &(dyn Error + 'static));
+            }
+            false
+        })
+}
+
+/// Maps an error from fetching the sitemap to an actionable message.
+///
+/// Distinguishes DNS failures (likely a typo in the host), refused connections
+/// (server down or unreachable), TLS errors (certificate problems) and timeouts.
+/// Any other error is reported as-is.
+pub fn describe_sitemap_error(sitemap_url: &str, e: &(dyn Error + 'static)) -> String {
+    let host = Url::parse(sitemap_url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from))
+        .unwrap_or_else(|| sitemap_url.to_string());
+
+    let chain_contains = |needles: &[&str]| {
+        error_chain_any(e, |err| {
+            let msg = err.to_string().to_lowercase();
+            needles.iter().any(|n| msg.contains(n))
+        })
+    };
+
+    if chain_contains(&["dns error", "failed to lookup address"]) {
+        return format!(
+            "Host not found: could not resolve '{}'. Check the sitemap URL for typos.",
+            host
+        );
+    }
+
+    if error_chain_any(e, |err| {
+        err.downcast_ref::<std::io::Error>()
+            .is_some_and(|io| io.kind() == std::io::ErrorKind::ConnectionRefused)
+    }) {
+        return format!(
+            "Connection refused by '{}'. Is the server running and reachable?",
+            host
+        );
+    }
+
+    if is_tls_error(e) {
+        let mut root_cause = e;
+        while let Some(source) = root_cause.source() {
+            root_cause = source;
+        }
+        return format!(
+            "TLS error while connecting to '{}'. Check the server's certificate: {}",
+            host, root_cause
+        );
+    }
+
+    if let Some(req_err) = e.downcast_ref::<reqwest::Error>() {
+        if req_err.is_timeout() {
+            return format!("Timed out while fetching the sitemap from '{}'.", host);
+        }
+    }
+
+    format!("Unable to fetch sitemap: {}", e)
+}
+
+/// Conventional sitemap locations tried by `--discover`, relative to the site root.
+const DISCOVERY_PATHS: &[&str] = &["/sitemap_index.xml", "/sitemap.xml", "/sitemap1.xml"];
+
+/// Returns the alternate sitemap URLs to try when `sitemap_url` returns a 404.
+///
+/// These are the conventional locations on the same origin, excluding the
+/// given URL itself.
+pub fn discovery_candidates(sitemap_url: &str) -> Vec<String> {
+    let Ok(base) = Url::parse(sitemap_url) else {
+        return Vec::new();
+    };
+    DISCOVERY_PATHS
+        .iter()
+        .filter_map(|path| base.join(path).ok())
+        .map(|u| u.to_string())
+        .filter(|u| u != sitemap_url)
+        .collect()
+}
+
+/// Extracts the `Sitemap:` URLs from a robots.txt body.
+pub fn parse_robots_sitemaps(robots: &str) -> Vec<String> {
+    robots
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if key.trim().eq_ignore_ascii_case("sitemap") {
+                Some(value.trim().to_string())
+            } else {
+                None
+            }
+        })
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
+/// Returns true if the error is an HTTP 404 response.
+fn is_not_found(e: &(dyn Error + 'static)) -> bool {
+    e.downcast_ref::<reqwest::Error>()
+        .and_then(|e| e.status())
+        .is_some_and(|status| status == reqwest::StatusCode::NOT_FOUND)
+}
+
+/// Tries the conventional sitemap locations and the robots.txt `Sitemap:` entries
+/// in turn, returning the first URL that could be fetched along with its content.
+async fn discover_sitemap(sitemap_url: &str, client: &Client) -> Option<(String, SitemapFile)> {
+    for candidate in discovery_candidates(sitemap_url) {
+        if let Ok(file) = get_sitemap_content(&candidate, client).await {
+            return Some((candidate, file));
+        }
+    }
+
+    let robots_url = Url::parse(sitemap_url).ok()?.join("/robots.txt").ok()?;
+    let robots = client
+        .get(robots_url)
+        .send()
+        .await
+        .ok()?
+        .error_for_status()
+        .ok()?
+        .text()
+        .await
+        .ok()?;
+    for candidate in parse_robots_sitemaps(&robots) {
+        if candidate == sitemap_url {
+            continue;
+        }
+        if let Ok(file) = get_sitemap_content(&candidate, client).await {
+            return Some((candidate, file));
+        }
+    }
+
+    None
 }
 
 pub async fn get_sitemap_urls(
     sitemap_url: &str,
     client: &Client,
     quiet: bool,
-) -> Result<Vec<String>, Box<dyn Error>> {
-    let content = match get_sitemap_content(sitemap_url, client).await {
-        Ok(content) => content,
+    discover: bool,
+    html_sitemap: bool,
+    concurrency: usize,
+    warnings: &Warnings,
+) -> Result<SitemapUrls, Box<dyn Error>> {
+    let (sitemap_url, file) = match get_sitemap_content(sitemap_url, client).await {
+        Ok(file) => (sitemap_url.to_string(), file),
+        Err(e) if discover && is_not_found(e.as_ref()) => {
+            match discover_sitemap(sitemap_url, client).await {
+                Some((found_url, file)) => {
+                    if !quiet {
+                        println!(
+                            "{} Sitemap not found at {}, using {}",
+                            style("[INFO]").cyan(),
+                            sitemap_url,
+                            found_url
+                        );
+                    }
+                    (found_url, file)
+                }
+                None => {
+                    return Err(format!(
+                        "Unable to fetch sitemap: {} returned 404 and no alternate sitemap was found.",
+                        sitemap_url
+                    )
+                    .into());
+                }
+            }
+        }
         Err(e) => {
-            return Err(format!("Unable to fetch sitemap: {}", e).into());
+            return Err(describe_sitemap_error(sitemap_url, e.as_ref()).into());
         }
     };
+    let sitemap_url = sitemap_url.as_str();
+
+    let content_type_check = |url: &str, file: &SitemapFile| {
+        if let Some(encoding) = file.encoding {
+            warnings.push(format!(
+                "The sitemap {} is encoded as {}. Sitemaps should be UTF-8.",
+                url, encoding
+            ));
+        }
+        check_sitemap_content_type(url, file.content_type.as_deref(), &file.content)
+    };
+    let sitemap_type = identify_sitemap_type(&file.content);
+    let is_html_sitemap =
+        html_sitemap && sitemap_type == SitemapType::Unknown && looks_like_html(&file.content);
+    // Not every site has a sitemap, a plain-text list of URLs works too.
+    let url_list = (sitemap_type == SitemapType::Unknown && !is_html_sitemap)
+        .then(|| parse_url_list(&file.content).ok())
+        .flatten()
+        .filter(|urls| !urls.is_empty());
+    if !is_html_sitemap {
+        match content_type_check(sitemap_url, &file) {
+            Ok(warning) => warnings.extend(warning),
+            Err(e) if looks_like_html(&file.content) => {
+                return Err(format!("{} Use --html-sitemap if it is an HTML sitemap.", e).into());
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let content = file.content;
 
-    let sitemap_type = identify_sitemap_type(&content);
     if !quiet {
-        println!("{} 🔎 Fetch {}...", style("[1/3]").dim(), sitemap_type);
+        if is_html_sitemap {
+            println!("{} 🔎 Fetch HTML sitemap...", style("[1/3]").dim());
+        } else if url_list.is_some() {
+            println!("{} 🔎 Fetch URL list...", style("[1/3]").dim());
+        } else {
+            println!("{} 🔎 Fetch {}...", style("[1/3]").dim(), sitemap_type);
+        }
     }
 
-    if sitemap_type == SitemapType::Unknown {
-        return Err(format!("The sitemap does not contain any URLs: {}", sitemap_url).into());
+    if sitemap_type == SitemapType::Unknown && !is_html_sitemap && url_list.is_none() {
+        let hint = if looks_like_html(&content) {
+            " Use --html-sitemap if it is an HTML sitemap."
+        } else {
+            ""
+        };
+        return Err(format!(
+            "The sitemap does not contain any URLs: {}{}",
+            sitemap_url, hint
+        )
+        .into());
     }
 
     // A sitemap.xml file might be an index file, linking to other sitemaps.
-    // In that case, retrieve the urls from all those sitemaps.
-    let mut urls = Vec::new();
+    // In that case, retrieve the urls from all those sitemaps. URLs are kept
+    // per sitemap file so duplicates can be attributed.
+    let mut urls_by_sitemap: Vec<(String, Vec<String>)> = Vec::new();
+    let mut lastmod = HashMap::new();
 
     if !quiet {
         println!(
@@ -105,32 +610,480 @@ pub async fn get_sitemap_urls(
         );
     }
     if sitemap_type == SitemapType::SitemapIndex {
-        let sitemap_urls = extract_sitemap_urls(&content);
-        for sitemap_url in sitemap_urls {
-            match get_sitemap_content(&sitemap_url, client).await {
-                Ok(content) => {
-                    urls.extend(extract_sitemap_urls(&content));
+        // Child sitemaps are fetched concurrently, but processed in the order
+        // they appear in the index.
+        let child_sitemaps = extract_sitemap_urls(&content);
+        let progress = term::sitemap_progress_bar(child_sitemaps.len() as u64, quiet);
+        let results: Vec<_> = stream::iter(child_sitemaps)
+            .map(|sitemap_url| {
+                let progress = &progress;
+                async move {
+                    let result = get_sitemap_content(&sitemap_url, client).await;
+                    progress.inc(1);
+                    (sitemap_url, result)
+                }
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+        progress.finish_and_clear();
+        for (sitemap_url, result) in results {
+            match result {
+                Ok(file) => {
+                    match content_type_check(&sitemap_url, &file) {
+                        Ok(warning) => warnings.extend(warning),
+                        Err(e) => {
+                            warnings.push(e);
+                            continue;
+                        }
+                    }
+                    let urls = extract_urls_and_lastmod(&file.content, &mut lastmod);
+                    urls_by_sitemap.push((sitemap_url, urls));
                 }
                 Err(_) => {
-                    eprintln!(
-                        "{} The referenced sitemap is missing: {}",
-                        style("[ERROR]").red(),
-                        &sitemap_url
-                    );
+                    warnings.push(format!(
+                        "The referenced sitemap is missing: {}",
+                        sitemap_url
+                    ));
                 }
             };
         }
     } else if sitemap_type == SitemapType::UrlSet {
-        urls.extend(extract_sitemap_urls(&content));
+        let urls = extract_urls_and_lastmod(&content, &mut lastmod);
+        urls_by_sitemap.push((sitemap_url.to_string(), urls));
+    } else if is_html_sitemap {
+        urls_by_sitemap.push((
+            sitemap_url.to_string(),
+            extract_html_links(&content, sitemap_url),
+        ));
+    } else if let Some(urls) = url_list {
+        urls_by_sitemap.push((sitemap_url.to_string(), urls));
     }
 
+    let duplicates = classify_duplicate_urls(&urls_by_sitemap);
+    let sitemaps = urls_by_sitemap
+        .iter()
+        .map(|(sitemap, urls)| (sitemap.clone(), urls.len()))
+        .collect();
+
     // Deduplicate URLs - a URL might appear in multiple sitemap files
+    let mut urls: Vec<String> = urls_by_sitemap
+        .into_iter()
+        .flat_map(|(_, urls)| urls)
+        .collect();
     urls.sort();
     urls.dedup();
 
+    warnings.extend(check_url_locations(sitemap_url, &urls));
+
+    Ok(SitemapUrls {
+        urls,
+        duplicates,
+        lastmod,
+        sitemaps,
+    })
+}
+
+/// Merges the URLs collected from several sitemaps into one set, e.g. when more than
+/// one sitemap URL is given.
+///
+/// URLs keep the order of their first occurrence and are listed once. A URL found
+/// through more than one source counts as a duplicate across sitemaps; for
+/// `<lastmod>`, the first source listing the URL wins.
+pub fn merge_sitemap_urls(sources: Vec<SitemapUrls>) -> SitemapUrls {
+    let mut merged = SitemapUrls::default();
+    let mut sources_per_url: HashMap<String, usize> = HashMap::new();
+    let mut within = BTreeSet::new();
+    let mut across = BTreeSet::new();
+
+    for source in sources {
+        for url in source.urls {
+            let count = sources_per_url.entry(url.clone()).or_default();
+            *count += 1;
+            if *count == 1 {
+                merged.urls.push(url);
+            } else {
+                across.insert(url);
+            }
+        }
+        within.extend(source.duplicates.within_sitemap);
+        across.extend(source.duplicates.across_sitemaps);
+        for (url, lastmod) in source.lastmod {
+            merged.lastmod.entry(url).or_insert(lastmod);
+        }
+        merged.sitemaps.extend(source.sitemaps);
+    }
+
+    merged.duplicates = DuplicateUrls {
+        within_sitemap: within.into_iter().collect(),
+        across_sitemaps: across.into_iter().collect(),
+    };
+    merged
+}
+
+/// Parses a plain-text URL list: one `http(s)://` URL per line. Empty lines and lines
+/// starting with `#` are ignored, and repeated URLs are only listed once, in the order
+/// they first appear.
+///
+/// # Errors
+///
+/// Names the line of the first entry that isn't an `http(s)://` URL.
+pub fn parse_url_list(content: &str) -> Result<Vec<String>, String> {
+    let mut seen = HashSet::new();
+    let mut urls = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let url = Url::parse(line)
+            .map_err(|e| e.to_string())
+            .and_then(|url| match url.scheme() {
+                "http" | "https" => Ok(url),
+                scheme => Err(format!("unsupported scheme '{}'", scheme)),
+            })
+            .map_err(|e| format!("Invalid URL on line {}: {} ({})", number + 1, line, e))?;
+        if seen.insert(url.to_string()) {
+            urls.push(url.to_string());
+        }
+    }
     Ok(urls)
 }
 
+/// Collects the URLs of a plain-text URL list read from `source`, see
+/// [`parse_url_list`].
+fn url_list(source: &str, content: &str) -> Result<SitemapUrls, Box<dyn Error>> {
+    let urls = parse_url_list(content).map_err(|e| format!("{} of {}", e, source))?;
+    Ok(SitemapUrls {
+        sitemaps: vec![(source.to_string(), urls.len())],
+        urls,
+        ..Default::default()
+    })
+}
+
+/// Reads the URLs to probe from a plain-text file instead of a sitemap, given with
+/// `--urls-file` or as the positional argument, e.g. the failures of a previous run
+/// written with `--failures-path`. See [`parse_url_list`] for the format.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read, or names the line of an invalid URL.
+pub fn read_urls_file(path: &Path) -> Result<SitemapUrls, Box<dyn Error>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read the URLs file {}: {}", path.display(), e))?;
+    url_list(&path.display().to_string(), &content)
+}
+
+/// Reads the URLs to probe from stdin, given as `-` for the positional argument. See
+/// [`parse_url_list`] for the format.
+///
+/// # Errors
+///
+/// Returns an error if stdin can't be read, or names the line of an invalid URL.
+pub fn read_urls_stdin() -> Result<SitemapUrls, Box<dyn Error>> {
+    let mut content = String::new();
+    std::io::stdin()
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Unable to read URLs from stdin: {}", e))?;
+    url_list("stdin", &content)
+}
+
+/// Checks the sitemap URLs for entries that likely point to the wrong place: URLs on
+/// another host than the sitemap, and plain `http://` URLs in a sitemap served over
+/// `https://`. Returns one warning per kind of problem, naming the first such URL.
+pub fn check_url_locations(sitemap_url: &str, urls: &[String]) -> Vec<String> {
+    let Ok(sitemap) = Url::parse(sitemap_url) else {
+        return Vec::new();
+    };
+    // A local sitemap file has no host to compare the URLs with.
+    let Some(sitemap_host) = sitemap.host_str().map(str::to_lowercase) else {
+        return Vec::new();
+    };
+
+    let mut off_host = Vec::new();
+    let mut insecure = Vec::new();
+    for url in urls {
+        let Ok(parsed) = Url::parse(url) else {
+            continue;
+        };
+        if parsed.host_str().map(str::to_lowercase).as_ref() != Some(&sitemap_host) {
+            off_host.push(url);
+        }
+        if sitemap.scheme() == "https" && parsed.scheme() == "http" {
+            insecure.push(url);
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if let Some(first) = off_host.first() {
+        warnings.push(format!(
+            "{} URL(s) are on another host than the sitemap, e.g. {}",
+            off_host.len(),
+            first
+        ));
+    }
+    if let Some(first) = insecure.first() {
+        warnings.push(format!(
+            "{} URL(s) use http:// although the sitemap is served over https://, e.g. {}",
+            insecure.len(),
+            first
+        ));
+    }
+    warnings
+}
+
+/// Summarizes the path depth and length of `urls`, listing at most `limit` of the
+/// deepest and longest URLs. The depth is the number of non-empty path segments, so
+/// `https://example.com/` has depth 0 and `https://example.com/a/b/` has depth 2.
+/// URLs that can't be parsed are ignored.
+pub fn url_structure(urls: &[String], limit: usize) -> UrlStructure {
+    let mut depths: Vec<(String, usize)> = urls
+        .iter()
+        .filter_map(|u| {
+            let parsed = Url::parse(u).ok()?;
+            let depth = parsed
+                .path_segments()
+                .map_or(0, |segments| segments.filter(|s| !s.is_empty()).count());
+            Some((u.clone(), depth))
+        })
+        .collect();
+    if depths.is_empty() {
+        return UrlStructure::default();
+    }
+
+    let max_depth = depths.iter().map(|(_, depth)| *depth).max().unwrap_or(0);
+    let avg_depth =
+        depths.iter().map(|(_, depth)| *depth).sum::<usize>() as f64 / depths.len() as f64;
+
+    let mut longest: Vec<(String, usize)> = depths
+        .iter()
+        .map(|(url, _)| (url.clone(), url.chars().count()))
+        .collect();
+    longest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    longest.truncate(limit);
+
+    depths.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    depths.truncate(limit);
+
+    UrlStructure {
+        max_depth,
+        avg_depth,
+        deepest: depths,
+        longest,
+    }
+}
+
+/// Counts the unique hosts (case-insensitively) and the `http://` and `https://` URLs.
+pub fn url_overview(urls: &[String]) -> UrlOverview {
+    let mut hosts = HashSet::new();
+    let mut overview = UrlOverview {
+        urls: urls.len(),
+        ..Default::default()
+    };
+    for parsed in urls.iter().filter_map(|u| Url::parse(u).ok()) {
+        if let Some(host) = parsed.host_str() {
+            hosts.insert(host.to_lowercase());
+        }
+        match parsed.scheme() {
+            "http" => overview.http += 1,
+            "https" => overview.https += 1,
+            _ => {}
+        }
+    }
+    overview.hosts = hosts.len();
+    overview
+}
+
+/// Classifies duplicate URLs by where they occur.
+///
+/// Takes the URLs of each sitemap file as `(sitemap_url, urls)` pairs. A URL that
+/// appears more than once in the same file is a duplicate *within* that sitemap;
+/// a URL that appears in several files is a duplicate *across* sitemaps. A URL can
+/// be both. The returned lists are sorted and contain each URL once.
+pub fn classify_duplicate_urls(urls_by_sitemap: &[(String, Vec<String>)]) -> DuplicateUrls {
+    let mut within = BTreeSet::new();
+    let mut sitemaps_per_url: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+
+    for (sitemap, urls) in urls_by_sitemap {
+        let mut seen = HashSet::new();
+        for url in urls {
+            if !seen.insert(url.as_str()) {
+                within.insert(url.clone());
+            }
+            sitemaps_per_url
+                .entry(url.as_str())
+                .or_default()
+                .insert(sitemap.as_str());
+        }
+    }
+
+    let across: BTreeSet<String> = sitemaps_per_url
+        .into_iter()
+        .filter(|(_, sitemaps)| sitemaps.len() > 1)
+        .map(|(url, _)| url.to_string())
+        .collect();
+
+    DuplicateUrls {
+        within_sitemap: within.into_iter().collect(),
+        across_sitemaps: across.into_iter().collect(),
+    }
+}
+
+/// Checks the number of sitemap URLs against `--expect-url-count`. The count may
+/// deviate from `expected` by `tolerance_pct` percent in either direction.
+///
+/// Returns an error naming the actual and expected counts if it's outside that band.
+pub fn check_url_count(actual: usize, expected: usize, tolerance_pct: f64) -> Result<(), String> {
+    let deviation = expected as f64 * tolerance_pct / 100.0;
+    let (min, max) = (expected as f64 - deviation, expected as f64 + deviation);
+    if (min..=max).contains(&(actual as f64)) {
+        return Ok(());
+    }
+
+    let expectation = if tolerance_pct > 0.0 {
+        format!(
+            "{} URLs ±{}% ({} to {})",
+            expected,
+            tolerance_pct,
+            min.max(0.0).ceil(),
+            max.floor()
+        )
+    } else {
+        format!("{} URLs", expected)
+    };
+    Err(format!(
+        "The sitemap lists {} URLs, expected {}.",
+        actual, expectation
+    ))
+}
+
+/// Removes query parameters from the URLs (`--ignore-query`, `--ignore-param`), so
+/// URLs that only differ in e.g. tracking parameters are probed once. With `all`, the
+/// whole query string is removed, otherwise only the parameters in `names`. The order
+/// of the remaining parameters is kept.
+///
+/// Returns the sorted, deduplicated URLs and the number of URLs that collapsed into
+/// another one.
+pub fn strip_query_params(urls: Vec<String>, names: &[String], all: bool) -> (Vec<String>, usize) {
+    let total = urls.len();
+    let mut stripped: Vec<String> = urls
+        .into_iter()
+        .map(|url| {
+            let Ok(mut parsed) = Url::parse(&url) else {
+                return url;
+            };
+            if parsed.query().is_none() {
+                return url;
+            }
+            let kept: Vec<(String, String)> = if all {
+                Vec::new()
+            } else {
+                parsed
+                    .query_pairs()
+                    .filter(|(name, _)| !names.iter().any(|n| n == name))
+                    .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                    .collect()
+            };
+            if kept.is_empty() {
+                parsed.set_query(None);
+            } else {
+                parsed.query_pairs_mut().clear().extend_pairs(kept);
+            }
+            parsed.to_string()
+        })
+        .collect();
+    stripped.sort();
+    stripped.dedup();
+    let collapsed = total - stripped.len();
+    (stripped, collapsed)
+}
+
+/// Keeps the URLs worth probing again after a previous run (`--changed-since`): URLs
+/// that are not in the previous report, and URLs that failed in it.
+///
+/// `previous` maps the URLs of the previous report to whether they failed, see
+/// [`crate::report::load_previous_failures`].
+///
+/// Returns the retained URLs and the number of URLs skipped as unchanged and healthy.
+pub fn filter_changed_urls(
+    urls: Vec<String>,
+    previous: &HashMap<String, bool>,
+) -> (Vec<String>, usize) {
+    let total = urls.len();
+    let retained: Vec<String> = urls
+        .into_iter()
+        .filter(|u| previous.get(u).is_none_or(|&failed| failed))
+        .collect();
+    let skipped = total - retained.len();
+    (retained, skipped)
+}
+
+/// Filters URLs by their host using an allowlist and a denylist.
+///
+/// A URL is kept if its host is not in `deny_hosts` and, when `allow_hosts` is
+/// non-empty, is in `allow_hosts`. Host names are compared case-insensitively.
+/// URLs without a parseable host are only kept when no allowlist is given.
+///
+/// Returns the retained URLs and the number of URLs that were dropped.
+pub fn filter_urls_by_host(
+    urls: Vec<String>,
+    allow_hosts: &[String],
+    deny_hosts: &[String],
+) -> (Vec<String>, usize) {
+    if allow_hosts.is_empty() && deny_hosts.is_empty() {
+        return (urls, 0);
+    }
+
+    let total = urls.len();
+    let retained: Vec<String> = urls
+        .into_iter()
+        .filter(|u| {
+            let host = Url::parse(u)
+                .ok()
+                .and_then(|parsed| parsed.host_str().map(String::from));
+            match host {
+                Some(host) => {
+                    !deny_hosts.iter().any(|h| h.eq_ignore_ascii_case(&host))
+                        && (allow_hosts.is_empty()
+                            || allow_hosts.iter().any(|h| h.eq_ignore_ascii_case(&host)))
+                }
+                None => allow_hosts.is_empty(),
+            }
+        })
+        .collect();
+
+    let dropped = total - retained.len();
+    (retained, dropped)
+}
+
+/// Filters URLs by regular expressions (`--include`, `--exclude`).
+///
+/// A URL is kept if it matches none of the `exclude` patterns and, when `include` is
+/// non-empty, at least one of the `include` patterns. Exclude wins when both match.
+///
+/// Returns the retained URLs and the number of URLs that were dropped.
+pub fn filter_urls_by_pattern(
+    urls: Vec<String>,
+    include: &[Regex],
+    exclude: &[Regex],
+) -> (Vec<String>, usize) {
+    if include.is_empty() && exclude.is_empty() {
+        return (urls, 0);
+    }
+
+    let total = urls.len();
+    let retained: Vec<String> = urls
+        .into_iter()
+        .filter(|u| {
+            !exclude.iter().any(|p| p.is_match(u))
+                && (include.is_empty() || include.iter().any(|p| p.is_match(u)))
+        })
+        .collect();
+
+    let dropped = total - retained.len();
+    (retained, dropped)
+}
+
 pub fn identify_sitemap_type(xml: &str) -> SitemapType {
     let mut reader = Reader::from_str(xml);
     let mut buf = Vec::new();
@@ -156,20 +1109,50 @@ pub fn identify_sitemap_type(xml: &str) -> SitemapType {
 
 /// Extracts all <loc> URLs from a sitemap.xml string
 pub fn extract_sitemap_urls(xml: &str) -> Vec<String> {
+    extract_sitemap_entries(xml)
+        .into_iter()
+        .map(|(url, _)| url)
+        .collect()
+}
+
+/// Extracts the `<loc>` of every entry in a sitemap along with its `<lastmod>`, if
+/// the entry has one.
+pub fn extract_sitemap_entries(xml: &str) -> Vec<(String, Option<String>)> {
     let mut reader = Reader::from_str(xml);
     let mut buf = Vec::new();
-    let mut urls = Vec::new();
+    let mut entries: Vec<(String, Option<String>)> = Vec::new();
+    // Where the current <url> or <sitemap> element starts in `entries`, and its
+    // <lastmod>, which may come before or after the <loc>.
+    let mut entry_start = 0;
+    let mut lastmod = None;
 
     loop {
         match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) if matches!(e.name().as_ref(), b"url" | b"sitemap") => {
+                entry_start = entries.len();
+                lastmod = None;
+            }
             Ok(Event::Start(ref e)) if e.name().as_ref() == b"loc" => {
                 // Read the next text event which contains the URL
                 if let Ok(Event::Text(e)) = reader.read_event_into(&mut buf) {
                     if let Ok(url) = e.unescape() {
-                        urls.push(url.into_owned());
+                        entries.push((url.into_owned(), None));
                     }
                 }
             }
+            Ok(Event::Start(ref e)) if e.name().as_ref() == b"lastmod" => {
+                if let Ok(Event::Text(e)) = reader.read_event_into(&mut buf) {
+                    if let Ok(value) = e.unescape() {
+                        lastmod = Some(value.trim().to_string());
+                    }
+                }
+            }
+            Ok(Event::End(ref e)) if matches!(e.name().as_ref(), b"url" | b"sitemap") => {
+                for entry in &mut entries[entry_start..] {
+                    entry.1 = lastmod.clone();
+                }
+                lastmod = None;
+            }
             Ok(Event::Eof) => break,
             Err(_) => break,
             _ => {}
@@ -177,10 +1160,143 @@ pub fn extract_sitemap_urls(xml: &str) -> Vec<String> {
         buf.clear(); // Clear buffer for the next event
     }
 
-    urls
+    entries
+}
+
+/// Extracts the URLs of a sitemap, recording their `<lastmod>` values in `lastmod`.
+fn extract_urls_and_lastmod(xml: &str, lastmod: &mut HashMap<String, String>) -> Vec<String> {
+    extract_sitemap_entries(xml)
+        .into_iter()
+        .map(|(url, modified)| {
+            if let Some(modified) = modified {
+                lastmod.entry(url.clone()).or_insert(modified);
+            }
+            url
+        })
+        .collect()
+}
+
+/// Parses a sitemap `<lastmod>` value in W3C Datetime format: a date (`2005-01-01`)
+/// or a date and time with a time zone (`2004-12-23T18:00:15+00:00`, seconds are
+/// optional). A date without a time is taken as midnight UTC.
+pub fn parse_lastmod(value: &str) -> Option<DateTime<FixedOffset>> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc().fixed_offset());
+    }
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime);
+    }
+    let value = match value.strip_suffix('Z') {
+        Some(local) => format!("{}+00:00", local),
+        None => value.to_string(),
+    };
+    DateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M%:z").ok()
+}
+
+/// Keeps the URLs modified at or after `since` (`--modified-since`), according to
+/// their sitemap `<lastmod>`. URLs without a `lastmod`, or with one that can't be
+/// parsed, are kept.
+///
+/// Returns the retained URLs and the number of URLs skipped as not modified.
+pub fn filter_modified_since(
+    urls: Vec<String>,
+    lastmod: &HashMap<String, String>,
+    since: &DateTime<FixedOffset>,
+) -> (Vec<String>, usize) {
+    let total = urls.len();
+    let retained: Vec<String> = urls
+        .into_iter()
+        .filter(|u| {
+            lastmod
+                .get(u)
+                .and_then(|value| parse_lastmod(value))
+                .is_none_or(|modified| modified >= *since)
+        })
+        .collect();
+    let skipped = total - retained.len();
+    (retained, skipped)
 }
 // endregion
 
+/// Sizes a run of `urls` without probing them (`--dry-run`).
+///
+/// The time estimate is a range: the lower bound is set by `--rate-limit`, which spaces
+/// out the requests; the upper bound assumes every request and retry runs into
+/// `--request-timeout`, `--concurrency-limit` at a time.
+pub fn dry_run(urls: &[String], sitemaps: &[(String, usize)], options: &Cli) -> DryRun {
+    let requests = urls.len() * (options.repeat + options.warmup) as usize;
+    let min_time = options.rate_limit.map_or(Duration::ZERO, |limit| {
+        Duration::from_secs_f64(requests.saturating_sub(1) as f64 * 60.0 / f64::from(limit))
+    });
+    let rounds = requests.div_ceil(usize::from(options.concurrency_limit.max(1)));
+    let attempts = u64::from(options.retries) + 1;
+    let max_time = Duration::from_secs(rounds as u64 * attempts * options.request_timeout);
+    DryRun {
+        urls: urls.len(),
+        requests,
+        sitemaps: sitemaps.to_vec(),
+        min_time,
+        max_time: max_time.max(min_time),
+    }
+}
+
+/// Returns the order in which `count` probes are sent, as indices into the probe list.
+///
+/// Without `shuffle` this is the sitemap order. With `shuffle`, the order is randomized,
+/// reproducibly if a `seed` is given.
+pub fn probe_order(count: usize, shuffle: bool, seed: Option<u64>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..count).collect();
+    if shuffle {
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        order.shuffle(&mut rng);
+    }
+    order
+}
+
+/// Returns the URL to request for the sitemap URL `u`, and the timeout (in seconds) of
+/// the first matching `--timeout-override` pattern. With `--append-timestamp`, a random
+/// timestamp is appended to the URL, unless the cache is bypassed with a header
+/// (`--cache-bust-header`).
+fn request_target(u: &str, options: &Cli) -> (String, Option<u64>) {
+    let url = if options.append_timestamp && !options.cache_bust_header {
+        utils::cache_bust(u, &options.cache_bust_param)
+    } else {
+        u.to_string()
+    };
+    let timeout_override = options
+        .timeout_overrides
+        .iter()
+        .find(|o| o.pattern.is_match(u))
+        .map(|o| o.seconds);
+    (url, timeout_override)
+}
+
+/// The response recorded for `url` when the request failed inside siteprobe, without a
+/// status from the server: an unexpected request error, or a panicked request task.
+fn failed_response(url: &str, error: String) -> Response {
+    Response {
+        url: url.to_string(),
+        requested_url: url.to_string(),
+        source_url: url.to_string(),
+        status_code: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+        error: Some(error),
+        ..Default::default()
+    }
+}
+
+/// Awaits `future`, giving up at the `deadline` (`--max-duration`) if there is one.
+/// Returns `None` if the deadline passed first.
+async fn until_deadline<F: Future>(deadline: Option<Instant>, future: F) -> Option<F::Output> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
 /// Fetches URLs concurrently from the sitemap and generates a report.
 ///
 /// # Arguments
@@ -189,7 +1305,8 @@ pub fn extract_sitemap_urls(xml: &str) -> Vec<String> {
 /// * `client` - A shared, configured HTTP client.
 /// * `semaphore` - A semaphore controlling the concurrency level.
 /// * `options` - CLI options controlling aspects like output directory and request modifications.
-/// * `start_time` - The time when the fetching started, used to calculate elapsed time.
+/// * `start_time` - The time when the fetching started, used to calculate elapsed time
+///   and the deadline of `--max-duration`.
 ///
 /// # Returns
 ///
@@ -215,70 +1332,172 @@ pub async fn fetch_and_generate_report(
     });
 
     // Setup progress bars.
-    let wrapper_pb = indicatif::MultiProgress::new();
-    if options.json {
-        wrapper_pb.set_draw_target(indicatif::ProgressDrawTarget::hidden());
-    }
-    let loading_pb = wrapper_pb.add(indicatif::ProgressBar::new(urls.len() as u64));
-    loading_pb.set_style(
-        indicatif::ProgressStyle::default_bar()
-            .template(concat!(
-                "\x1b[2m[3/3]\x1b[0m",
-                " 📥 [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} (ETA: {eta_precise}) {msg}"
-            ))
-            .unwrap()
-            .progress_chars("■┄"),
-    );
+    let wrapper_pb = term::multi_progress(options.quiet());
+    let repeat = options.repeat as usize;
+    let loading_pb = wrapper_pb.add(term::overall_progress_bar((urls.len() * repeat) as u64));
+
+    let head = options.head;
+    let max_body_size = options.max_body_size;
+
+    // With --max-duration, the run stops at this deadline with the responses so far.
+    let deadline = options
+        .max_duration
+        .map(|secs| *start_time + Duration::from_secs(secs));
+
+    // Ctrl-C stops sending new requests but lets the running ones finish, so the
+    // report covers everything completed until then. A second Ctrl-C stops right away.
+    let mut ctrl_c = std::pin::pin!(tokio::signal::ctrl_c());
+    let mut interrupted = false;
+
+    // Warm-up phase: send requests whose results are discarded, so cold caches
+    // and connection setup don't skew the measured run.
+    let warmup = options.warmup as usize;
+    let warmup_start = Instant::now();
+    if warmup > 0 {
+        loading_pb.set_message("Warming up...");
+        let warmups = urls
+            .iter()
+            .flat_map(|u| std::iter::repeat_n(u, warmup))
+            .map(|u| {
+                let semaphore = Arc::clone(&semaphore);
+                let rate_limit_setup = Arc::clone(&rate_limit_setup);
+                let client = Arc::clone(client);
+                // The same URL and timeout as the measured request, see `request_target`.
+                let (url, timeout_override) = request_target(u, options);
+                let timeout = timeout_override.map(Duration::from_secs);
+                tokio::spawn(async move {
+                    let Ok(_permit) = semaphore.acquire().await else {
+                        return;
+                    };
+                    if let Some(limiter) = rate_limit_setup.limiter.as_ref() {
+                        limiter.until_ready().await;
+                    }
+                    // Nothing is stored, hashed or checked, the response is discarded.
+                    let _ = get_url_response(
+                        &url,
+                        &client,
+                        head,
+                        max_body_size,
+                        &None,
+                        false,
+                        false,
+                        &ContentChecks::default(),
+                        timeout,
+                    )
+                    .await;
+                })
+            });
+        let warmups: Vec<_> = warmups.collect();
+        let abort_handles: Vec<_> = warmups.iter().map(|h| h.abort_handle()).collect();
+        let completed = tokio::select! {
+            done = until_deadline(deadline, join_all(warmups)) => done.is_some(),
+            _ = &mut ctrl_c => {
+                interrupted = true;
+                semaphore.close();
+                ctrl_c.set(tokio::signal::ctrl_c());
+                false
+            }
+        };
+        if !completed {
+            abort_handles.iter().for_each(|h| h.abort());
+        }
+        loading_pb.set_message("");
+    }
+    let warmup_time = warmup_start.elapsed();
 
     let retries = options.retries;
+    let retry_backoff = Duration::from_millis(options.retry_backoff_ms);
+    let respect_retry_after = !options.no_respect_retry_after;
+    let dump_headers = options.dump_headers;
+    let hash_bodies = options.hash_bodies;
+    let content_checks = Arc::new(ContentChecks {
+        contains: options.expect_contains.clone(),
+        matches: options.expect_matches.clone(),
+        soft_404_markers: options.soft_404_markers.clone(),
+    });
 
-    let fetches = urls.iter().map(|u| {
+    // With --repeat, each URL is probed several times as independent requests,
+    // so every sample respects the concurrency and rate limits.
+    // With --shuffle, they are sent in random order; `i` keeps the position in the
+    // report, so the report order doesn't change.
+    let probes: Vec<&String> = urls
+        .iter()
+        .flat_map(|u| std::iter::repeat_n(u, repeat))
+        .collect();
+    let order = probe_order(probes.len(), options.shuffle, options.seed);
+
+    let fetches = order.into_iter().map(|i| {
+        let u = probes[i];
         let semaphore = Arc::clone(&semaphore);
         let rate_limit_setup = Arc::clone(&rate_limit_setup);
         let client = Arc::clone(client);
-        let output_dir = options.output_dir.clone();
-        let mut url = u.clone();
+        let content_checks = Arc::clone(&content_checks);
+        let output_dir = options.output_dir.clone().map(|path| OutputDir {
+            path,
+            layout: options.storage_layout,
+            store_headers: options.store_headers,
+            errors_only: options.store_errors_only,
+        });
+        let source_url = u.clone();
+        let (url, timeout_override) = request_target(u, options);
+        let timeout = timeout_override.map(Duration::from_secs);
+        let run_start = *start_time;
 
         // Create per-request progress indicators.
         let loading_pb = loading_pb.clone();
-        let line_pb = wrapper_pb.add(indicatif::ProgressBar::new_spinner());
+        let line_pb = wrapper_pb.add(term::spinner());
 
-        // Append a random timestamp if the option is enabled.
-        if options.append_timestamp {
-            url = format!("{}?ts={}", url, utils::generate_random_number(10));
-        }
-
-        tokio::spawn(async move {
-            let _permit = semaphore.acquire().await.expect("Semaphore closed");
+        let handle = tokio::spawn(async move {
+            // The semaphore is closed on Ctrl-C, so requests that haven't started are skipped.
+            let Ok(_permit) = semaphore.acquire().await else {
+                line_pb.finish_and_clear();
+                return None;
+            };
 
-            if rate_limit_setup.limit.is_some() && rate_limit_setup.limiter.is_some() {
+            if let (Some(limit), Some(limiter)) =
+                (rate_limit_setup.limit, rate_limit_setup.limiter.as_ref())
+            {
                 // Set the progress bar message to indicate rate limiting
                 line_pb.set_message(format!(
                     "Waiting for rate limit ({:?}/min): {}",
-                    rate_limit_setup.limit.unwrap(),
+                    limit,
                     &utils::truncate_message(&url, 80)
                 ));
 
                 // Wait until the rate limit is satisfied
-                rate_limit_setup
-                    .limiter
-                    .as_ref()
-                    .unwrap()
-                    .until_ready()
-                    .await;
+                limiter.until_ready().await;
             }
 
             line_pb.set_message(format!("Fetching: {}", utils::truncate_message(&url, 80)));
             line_pb.enable_steady_tick(Duration::from_millis(100));
 
-            let mut result = get_url_response(&url, &client, &output_dir).await;
+            let mut started_at = run_start.elapsed();
+            let mut result = get_url_response(
+                &url,
+                &client,
+                head,
+                max_body_size,
+                &output_dir,
+                dump_headers,
+                hash_bodies,
+                &content_checks,
+                timeout,
+            )
+            .await;
 
-            // Retry logic: retry on network errors or 5xx status codes
+            // Retry logic: retry on network errors or 5xx status codes, with exponential
+            // backoff (--retry-backoff-ms) between attempts. A Retry-After header takes
+            // precedence over the backoff and makes 429 responses retryable. A response
+            // asking to wait longer than MAX_RETRY_AFTER is kept as it is.
             for attempt in 1..=retries {
+                let retry_after = match &result {
+                    Ok(resp) if respect_retry_after => resp.retry_after,
+                    _ => None,
+                };
                 let should_retry = match &result {
-                    Ok(resp) => resp.status_code.is_server_error(),
+                    Ok(resp) => resp.status_code.is_server_error() || retry_after.is_some(),
                     Err(_) => true,
-                };
+                } && retry_after.is_none_or(|wait| wait <= MAX_RETRY_AFTER);
 
                 if !should_retry {
                     break;
@@ -290,33 +1509,170 @@ pub async fn fetch_and_generate_report(
                     retries,
                     utils::truncate_message(&url, 70)
                 ));
-                tokio::time::sleep(Duration::from_secs(1)).await;
-                result = get_url_response(&url, &client, &output_dir).await;
+                let pause =
+                    retry_after.unwrap_or_else(|| utils::retry_backoff(attempt, retry_backoff));
+                tokio::time::sleep(pause).await;
+                // A retry is another request, so it counts against the rate limit too.
+                if let Some(limiter) = rate_limit_setup.limiter.as_ref() {
+                    limiter.until_ready().await;
+                }
+                started_at = run_start.elapsed();
+                result = get_url_response(
+                    &url,
+                    &client,
+                    head,
+                    max_body_size,
+                    &output_dir,
+                    dump_headers,
+                    hash_bodies,
+                    &content_checks,
+                    timeout,
+                )
+                .await;
             }
 
             line_pb.finish_and_clear();
             loading_pb.inc(1);
-            result
-        })
+            Some(result.map(|mut response| {
+                response.source_url = source_url;
+                response.started_at = started_at;
+                response.timeout_override = timeout_override;
+                response
+            }))
+        });
+        (i, handle)
     });
 
-    let results: Vec<_> = join_all(fetches).await;
-    loading_pb.finish_with_message("- 🏁 Complete!");
+    let handles: Vec<_> = fetches.collect();
+    let abort_handles: Vec<_> = handles.iter().map(|(_, h)| h.abort_handle()).collect();
+
+    // Collect the results as they complete, keeping them in probe order. With
+    // --stop-on-first-error, all remaining requests (including in-flight ones)
+    // are aborted as soon as one URL fails.
+    let mut results: Vec<Option<Response>> = vec![None; handles.len()];
+    let mut pending: FuturesUnordered<_> = handles
+        .into_iter()
+        .map(|(i, handle)| async move { (i, handle.await) })
+        .collect();
+    let mut first_error = None;
+    let mut total_bytes: u64 = 0;
+    let mut byte_capped = false;
+    let mut time_capped = false;
+    let mut errors = 0;
+    loop {
+        let next = tokio::select! {
+            next = until_deadline(deadline, pending.next()) => next,
+            _ = &mut ctrl_c => {
+                if interrupted {
+                    abort_handles.iter().for_each(|h| h.abort());
+                    break;
+                }
+                interrupted = true;
+                semaphore.close();
+                ctrl_c.set(tokio::signal::ctrl_c());
+                loading_pb.set_message("Interrupted, waiting for running requests...");
+                continue;
+            }
+        };
+        let Some(next) = next else {
+            time_capped = true;
+            abort_handles.iter().for_each(|h| h.abort());
+            break;
+        };
+        let Some((i, result)) = next else {
+            break;
+        };
+        let response = match result {
+            Ok(Some(Ok(response))) => response,
+            // Skipped after Ctrl-C, neither a response nor a failure.
+            Ok(None) => continue,
+            // An unexpected request error, or a panicked request task.
+            Ok(Some(Err(e))) => failed_response(&urls[i / repeat], e.to_string()),
+            Err(e) => failed_response(&urls[i / repeat], e.to_string()),
+        };
+        total_bytes += response.response_size as u64;
+        // The same failures as the exit code, so the live count matches the report.
+        let failed = response.is_failure(options.redirects_are_errors, options.flag_empty_body);
+
+        // Live error count, for early feedback during long runs.
+        if failed {
+            errors += 1;
+            loading_pb.set_message(format!("{}", style(format!("(errors: {})", errors)).red()));
+        }
+        if options.stop_on_first_error && failed {
+            first_error = Some(response.clone());
+            results[i] = Some(response);
+            abort_handles.iter().for_each(|h| h.abort());
+            break;
+        }
+        results[i] = Some(response);
+
+        // With --max-total-bytes, stop once the byte budget is used up.
+        if options.max_total_bytes.is_some_and(|max| total_bytes > max) {
+            byte_capped = true;
+            abort_handles.iter().for_each(|h| h.abort());
+            break;
+        }
+    }
+
+    if first_error.is_some() {
+        let _ = wrapper_pb.clear();
+        loading_pb.abandon_with_message("- 🛑 Stopped on first error!");
+    } else if byte_capped {
+        let _ = wrapper_pb.clear();
+        loading_pb.abandon_with_message("- 🛑 Stopped at the byte budget!");
+    } else if time_capped {
+        let _ = wrapper_pb.clear();
+        loading_pb.abandon_with_message("- 🛑 Stopped at the time budget!");
+    } else if interrupted {
+        let _ = wrapper_pb.clear();
+        loading_pb.abandon_with_message("- 🛑 Interrupted!");
+    } else {
+        loading_pb.finish_with_message("- 🏁 Complete!");
+    }
 
     // Process the results and aggregate the responses.
     let mut report = Report {
-        sitemap_url: options.sitemap_url.to_string(),
+        sitemap_url: (!options.sitemap_urls.is_empty())
+            .then(|| {
+                options
+                    .sitemap_urls
+                    .iter()
+                    .map(SitemapSource::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .or_else(|| options.urls_file.as_ref().map(|p| p.display().to_string()))
+            .unwrap_or_default(),
         concurrency_limit: options.concurrency_limit,
         rate_limit: options.rate_limit,
-        total_time: start_time.elapsed(),
+        total_time: start_time.elapsed().saturating_sub(warmup_time),
         responses: std::collections::VecDeque::new(),
+        warmup_requests: urls.len() * warmup,
+        empty_body: options.flag_empty_body,
+        redirects_are_errors: options.redirects_are_errors,
+        min_success_rate: options.min_success_rate,
+        max_p95_ms: options.max_p95_ms,
+        max_avg_ms: options.max_avg_ms,
+        max_error_rate: options.max_error_rate,
+        expect_json: options.json_api,
+        hash_bodies: options.hash_bodies,
+        bypass_caching: options.append_timestamp,
+        max_total_bytes: options.max_total_bytes,
+        byte_capped,
+        max_duration: options.max_duration,
+        time_capped,
+        interrupted,
+        request_headers: if options.dump_headers {
+            Some(build_request_headers(options)?)
+        } else {
+            None
+        },
+        ..Default::default()
     };
 
-    report.responses = results
-        .into_iter()
-        .filter_map(Result::ok)
-        .flatten()
-        .collect();
+    report.responses = results.into_iter().flatten().collect();
+    report.first_error = first_error;
 
     Ok(report)
 }
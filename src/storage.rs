@@ -1,42 +1,276 @@
+use crate::options::StorageLayout;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs;
+use std::io;
 use std::path;
+use std::sync::{Mutex, OnceLock};
 
-/// Stores the HTTP response body on disk as an HTML file.
+/// Where and how the downloaded documents are stored (`--output-dir`,
+/// `--storage-layout`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputDir {
+    pub path: path::PathBuf,
+    pub layout: StorageLayout,
+    /// Whether the response headers are stored next to each document
+    /// (`--store-headers`).
+    pub store_headers: bool,
+    /// Whether only 4xx and 5xx responses are stored (`--store-errors-only`).
+    pub errors_only: bool,
+}
+
+impl OutputDir {
+    /// Whether a response with `status` is stored.
+    pub fn stores(&self, status: reqwest::StatusCode) -> bool {
+        !self.errors_only || status.is_client_error() || status.is_server_error()
+    }
+}
+
+/// Output directories that were already prepared for writing during this run.
+static PREPARED_DIRS: OnceLock<Mutex<HashSet<path::PathBuf>>> = OnceLock::new();
+
+/// Creates the output directory on the first write into it. If the directory already
+/// existed, a warning is printed that existing documents will be overwritten.
+fn prepare_output_dir(storage_path: &path::Path) {
+    let mut prepared = PREPARED_DIRS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if !prepared.insert(storage_path.to_path_buf()) {
+        return;
+    }
+
+    if storage_path.is_dir() {
+        eprintln!(
+            "\n⚠️ The output directory '{}' already exists. Existing documents will be overwritten.\n",
+            storage_path.display()
+        );
+    } else if let Err(e) = fs::create_dir_all(storage_path) {
+        eprintln!("❌ Failed to create directory: {}", e);
+    }
+}
+
+/// Query strings longer than this are replaced by their hash in the file name.
+const MAX_QUERY_LENGTH: usize = 100;
+
+/// Appended to a document's file name for the file with its response headers.
+pub const HEADERS_SUFFIX: &str = ".headers.txt";
+
+/// The longest file name (in bytes) most filesystems allow, leaving room for the
+/// [`HEADERS_SUFFIX`].
+const MAX_FILE_NAME_LENGTH: usize = 255 - HEADERS_SUFFIX.len();
+
+/// Device names that Windows doesn't allow as file names, with or without extension.
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Replaces characters that aren't allowed in file names on common filesystems
+/// (Windows being the strictest) with `_`.
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Turns `stem` plus `extension` (empty for directories) into a file name that's valid
+/// on common filesystems:
+///
+/// - Characters that aren't allowed are replaced, see [`sanitize_file_name`].
+/// - A trailing dot or space, which Windows drops, is replaced with `_`.
+/// - Reserved device names like `CON` or `nul.txt` get an `_` appended.
+/// - Names longer than [`MAX_FILE_NAME_LENGTH`] are truncated, with the first 16 hex
+///   digits of the SHA-256 hash of the full stem appended to keep them unique.
+fn safe_file_name(stem: &str, extension: &str) -> String {
+    let mut name = sanitize_file_name(stem);
+    if name.ends_with(['.', ' ']) {
+        name.pop();
+        name.push('_');
+    }
+    let device = name.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| device.eq_ignore_ascii_case(reserved))
+    {
+        name.push('_');
+    }
+
+    let extension = if extension.is_empty() {
+        String::new()
+    } else {
+        format!(".{}", extension)
+    };
+    if name.len() + extension.len() > MAX_FILE_NAME_LENGTH {
+        let hash = format!("{:x}", Sha256::digest(stem));
+        let mut end = MAX_FILE_NAME_LENGTH - extension.len() - 17;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name.truncate(end);
+        name.push('_');
+        name.push_str(&hash[..16]);
+    }
+    name + &extension
+}
+
+/// Returns the file extension (without the dot) for a `Content-Type`, so the stored
+/// documents open with the right application. Unknown or missing types get `bin`.
+pub fn extension_for(content_type: Option<&str>) -> &'static str {
+    let Some(content_type) = content_type else {
+        return "bin";
+    };
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match media_type.as_str() {
+        "text/html" | "application/xhtml+xml" => "html",
+        "image/svg+xml" => "svg",
+        "application/json" => "json",
+        "application/xml" | "text/xml" => "xml",
+        "application/pdf" => "pdf",
+        "text/plain" => "txt",
+        "text/css" => "css",
+        "text/csv" => "csv",
+        "text/javascript" | "application/javascript" => "js",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/avif" => "avif",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        "application/zip" => "zip",
+        "application/gzip" => "gz",
+        t if t.ends_with("+json") => "json",
+        t if t.ends_with("+xml") => "xml",
+        _ => "bin",
+    }
+}
+
+/// Returns the path, relative to the output directory, that the document of `url` is
+/// stored at.
+///
+/// The path is derived from the URL path, see [`StorageLayout`], or `index.<ext>` for
+/// the root. The extension follows the `Content-Type`, see [`extension_for`]; if the
+/// URL already ends in it (`/report.pdf`), it isn't repeated. Empty, `.` and `..`
+/// segments are dropped, so a document can't be written outside the output directory.
+/// A query string is appended after an `_`, so URLs that only differ in their query
+/// are stored separately, e.g. `/catalog?item=12` as `catalog_item=12.html`. If the
+/// query is long or contains characters that aren't allowed in file names, the first
+/// 16 hex digits of its SHA-256 hash are used instead.
+///
+/// Every path segment is made safe for common filesystems, see [`safe_file_name`].
+pub fn document_path(url: &url::Url, layout: StorageLayout, content_type: Option<&str>) -> String {
+    let extension = extension_for(content_type);
+    let mut dirs: Vec<&str> = url
+        .path()
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+        .collect();
+    // Drop an extension the URL already has, it's added back below.
+    let suffix = format!(".{}", extension);
+    let has_extension = dirs.last().is_some_and(|last| {
+        last.len() > suffix.len() && last.to_ascii_lowercase().ends_with(&suffix)
+    });
+    // With `mirror`, pages become directories, unless the URL names a file.
+    let mut stem = match layout {
+        _ if dirs.is_empty() => "index".to_string(),
+        StorageLayout::Mirror if extension == "html" && !has_extension => "index".to_string(),
+        _ => {
+            let last = dirs.pop().unwrap_or_default();
+            let end = if has_extension {
+                last.len() - suffix.len()
+            } else {
+                last.len()
+            };
+            last[..end].to_string()
+        }
+    };
+    if let Some(query) = url.query().filter(|q| !q.is_empty()) {
+        stem.push('_');
+        if query.len() <= MAX_QUERY_LENGTH && sanitize_file_name(query) == query {
+            stem.push_str(query);
+        } else {
+            stem.push_str(&format!("{:x}", Sha256::digest(query))[..16]);
+        }
+    }
+    dirs.iter()
+        .map(|dir| safe_file_name(dir, ""))
+        .chain([safe_file_name(&stem, extension)])
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Formats the status line and response headers as stored with `--store-headers`,
+/// e.g. `HTTP/1.1 200 OK` followed by one `name: value` line per header.
+pub fn format_headers(
+    version: reqwest::Version,
+    status: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+) -> String {
+    let mut text = format!("{:?} {}\n", version, status);
+    for (name, value) in headers {
+        text.push_str(&format!(
+            "{}: {}\n",
+            name,
+            String::from_utf8_lossy(value.as_bytes())
+        ));
+    }
+    text
+}
+
+/// Stores the HTTP response body on disk.
 ///
 /// This function takes the storage path, the URL from which the response was fetched,
 /// and the response body, and writes the body to a file located in the specified
-/// storage path. The file name is generated based on the URL's path. If the URL path
-/// is empty, the file is named `index.html`, otherwise, the file name is derived
-/// from the URL path with `.html` as its extension. Any necessary directories in the
-/// path are created if they don't already exist.
+/// storage path. The file name is generated from the URL, see [`document_path`].
+/// Any necessary directories in the path are created if they don't already exist.
+///
+/// The output directory itself is only created on the first write, so an aborted run
+/// doesn't leave an empty directory behind.
 ///
 /// # Arguments
 ///
-/// * `storage_path` - A reference to the target directory where the response will be stored.
-///   This should be passed as an `&Path` (not `&PathBuf` for efficiency).
+/// * `output_dir` - The target directory where the response will be stored, and its layout.
 /// * `url` - A reference to the URL object representing the source of the response.
+/// * `content_type` - The `Content-Type` of the response, which sets the file extension.
+/// * `headers` - The status line and headers, see [`format_headers`]. Written to a
+///   `<name>.headers.txt` file next to the document, if given.
 /// * `body` - The response body content that will be written to the file.
 ///
-/// # Panics
+/// # Errors
 ///
-/// This function will panic if it fails to write the file to the specified path.
-pub async fn store_response_on_disk(storage_path: &path::Path, url: &url::Url, body: &str) {
-    let document_path = format!(
-        "{}.html",
-        if url.path().trim_matches('/').is_empty() {
-            "index"
-        } else {
-            url.path().trim_matches('/')
-        }
-    );
-    let target_path = storage_path.join(document_path);
+/// Returns the I/O error, naming the path, if the directories or the file can't be
+/// written.
+pub async fn store_response_on_disk(
+    output_dir: &OutputDir,
+    url: &url::Url,
+    content_type: Option<&str>,
+    headers: Option<&str>,
+    body: &[u8],
+) -> Result<path::PathBuf, io::Error> {
+    prepare_output_dir(&output_dir.path);
+    let target_path = output_dir
+        .path
+        .join(document_path(url, output_dir.layout, content_type));
 
+    let with_path =
+        |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", target_path.display(), e));
     if let Some(parent) = target_path.parent() {
-        let _ = fs::create_dir_all(parent);
+        fs::create_dir_all(parent).map_err(with_path)?;
     }
-
-    match fs::write(target_path, body) {
-        Ok(_) => (),
-        Err(e) => eprintln!("❌ Failed to write document to disk: {}", e),
+    fs::write(&target_path, body).map_err(with_path)?;
+    if let Some(headers) = headers {
+        let mut headers_path = target_path.clone().into_os_string();
+        headers_path.push(HEADERS_SUFFIX);
+        fs::write(&headers_path, headers).map_err(with_path)?;
     }
+    Ok(target_path)
 }
@@ -0,0 +1,140 @@
+//! Terminal output helpers: text styling and progress bars.
+//!
+//! With the `cli` feature these are backed by `console` and `indicatif`. Without it,
+//! styling is a no-op and progress bars are not drawn, so the core modules compile
+//! without the terminal dependencies.
+
+#[cfg(feature = "cli")]
+pub use console::style;
+#[cfg(feature = "cli")]
+pub use indicatif::{MultiProgress, ProgressBar};
+
+#[cfg(not(feature = "cli"))]
+pub use plain::{style, MultiProgress, ProgressBar};
+
+/// Creates the container for all progress bars of a run; hidden in `--json` mode.
+pub fn multi_progress(hidden: bool) -> MultiProgress {
+    let multi = MultiProgress::new();
+    #[cfg(feature = "cli")]
+    if hidden {
+        multi.set_draw_target(indicatif::ProgressDrawTarget::hidden());
+    }
+    #[cfg(not(feature = "cli"))]
+    let _ = hidden;
+    multi
+}
+
+/// Creates the overall progress bar for `len` requests.
+pub fn overall_progress_bar(len: u64) -> ProgressBar {
+    let bar = ProgressBar::new(len);
+    #[cfg(feature = "cli")]
+    bar.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template(concat!(
+                "\x1b[2m[3/3]\x1b[0m",
+                " 📥 [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} (ETA: {eta_precise}) {msg}"
+            ))
+            .unwrap()
+            .progress_chars("■┄"),
+    );
+    bar
+}
+
+/// Creates the progress bar for fetching the `len` child sitemaps of a sitemap index;
+/// hidden in `--json` mode.
+pub fn sitemap_progress_bar(len: u64, hidden: bool) -> ProgressBar {
+    let bar = ProgressBar::new(len);
+    #[cfg(feature = "cli")]
+    {
+        if hidden {
+            bar.set_draw_target(indicatif::ProgressDrawTarget::hidden());
+        }
+        bar.set_style(
+            indicatif::ProgressStyle::default_bar()
+                .template("      [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} sitemaps")
+                .unwrap()
+                .progress_chars("■┄"),
+        );
+    }
+    #[cfg(not(feature = "cli"))]
+    let _ = hidden;
+    bar
+}
+
+/// Creates a spinner showing the state of a single request.
+pub fn spinner() -> ProgressBar {
+    ProgressBar::new_spinner()
+}
+
+#[cfg(not(feature = "cli"))]
+mod plain {
+    use std::borrow::Cow;
+    use std::fmt;
+    use std::time::Duration;
+
+    /// Wraps a value without applying any styling.
+    pub fn style<D>(val: D) -> StyledObject<D> {
+        StyledObject(val)
+    }
+
+    /// A value that is displayed as-is. Mirrors the builder methods of
+    /// `console::StyledObject` used in siteprobe.
+    pub struct StyledObject<D>(D);
+
+    macro_rules! noop_styles {
+        ($($name:ident),*) => {
+            impl<D> StyledObject<D> {
+                $(
+                    pub fn $name(self) -> Self {
+                        self
+                    }
+                )*
+            }
+        };
+    }
+
+    noop_styles!(
+        bold, dim, italic, underlined, red, green, yellow, cyan, white, on_red, for_stderr
+    );
+
+    impl<D: fmt::Display> fmt::Display for StyledObject<D> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.fmt(f)
+        }
+    }
+
+    /// A progress bar that draws nothing.
+    #[derive(Clone)]
+    pub struct ProgressBar;
+
+    impl ProgressBar {
+        pub fn new(_len: u64) -> Self {
+            Self
+        }
+        pub fn new_spinner() -> Self {
+            Self
+        }
+        pub fn set_message(&self, _msg: impl Into<Cow<'static, str>>) {}
+        pub fn enable_steady_tick(&self, _interval: Duration) {}
+        pub fn inc(&self, _delta: u64) {}
+        pub fn finish_and_clear(&self) {}
+        pub fn finish_with_message(&self, _msg: impl Into<Cow<'static, str>>) {}
+        pub fn abandon_with_message(&self, _msg: impl Into<Cow<'static, str>>) {}
+    }
+
+    /// A container of progress bars that draws nothing.
+    #[derive(Default)]
+    pub struct MultiProgress;
+
+    impl MultiProgress {
+        pub fn new() -> Self {
+            Self
+        }
+        pub fn add(&self, bar: ProgressBar) -> ProgressBar {
+            bar
+        }
+        pub fn clear(&self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
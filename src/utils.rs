@@ -80,6 +80,87 @@ pub fn generate_random_number(length: u32) -> u64 {
     rand::rng().random_range(range)
 }
 
+/// Appends a random cache buster `param=<random>` to the query string of `url`, as
+/// done by `--append-timestamp`.
+///
+/// # Examples
+/// ```rust
+/// use siteprobe::utils::cache_bust;
+///
+/// assert!(cache_bust("https://example.com/", "ts").starts_with("https://example.com/?ts="));
+/// assert!(cache_bust("https://example.com/?page=2", "cb").starts_with("https://example.com/?page=2&cb="));
+/// ```
+pub fn cache_bust(url: &str, param: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!(
+        "{}{}{}={}",
+        url,
+        separator,
+        param,
+        generate_random_number(10)
+    )
+}
+
+/// Removes a cache buster added by [`cache_bust`] from `url`.
+///
+/// # Examples
+/// ```rust
+/// use siteprobe::utils::strip_cache_buster;
+///
+/// assert_eq!(strip_cache_buster("https://example.com/?ts=1234", "ts"), "https://example.com/");
+/// assert_eq!(strip_cache_buster("https://example.com/?page=2&ts=1234", "ts"), "https://example.com/?page=2");
+/// assert_eq!(strip_cache_buster("https://example.com/?page=2", "ts"), "https://example.com/?page=2");
+/// ```
+pub fn strip_cache_buster<'a>(url: &'a str, param: &str) -> &'a str {
+    url.rsplit_once(&format!("?{}=", param))
+        .or_else(|| url.rsplit_once(&format!("&{}=", param)))
+        .map_or(url, |(base, _)| base)
+}
+
+/// Returns the pause before retry number `attempt` (starting at 1): `base` doubled for
+/// every earlier retry, plus a random jitter of up to half of that, so concurrent
+/// retries don't hit a struggling server at the same time.
+///
+/// # Examples
+/// ```rust
+/// use siteprobe::utils::retry_backoff;
+/// use std::time::Duration;
+///
+/// let base = Duration::from_millis(100);
+/// let third = retry_backoff(3, base);
+/// assert!(third >= Duration::from_millis(400) && third <= Duration::from_millis(600));
+/// ```
+pub fn retry_backoff(attempt: u8, base: Duration) -> Duration {
+    let backoff = base * 2u32.pow(u32::from(attempt.saturating_sub(1)));
+    let jitter = rand::rng().random_range(0.0..=0.5);
+    backoff + backoff.mul_f64(jitter)
+}
+
+/// Renders bucket counts as a sparkline of Unicode block characters, scaled to the
+/// largest bucket. Empty buckets are shown as a space.
+///
+/// # Examples
+/// ```rust
+/// use siteprobe::utils::sparkline;
+///
+/// assert_eq!(sparkline(&[0, 1, 4, 8]), " ▁▄█");
+/// assert_eq!(sparkline(&[]), "");
+/// ```
+pub fn sparkline(buckets: &[usize]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = buckets.iter().copied().max().unwrap_or(0);
+    buckets
+        .iter()
+        .map(|&count| {
+            if count == 0 || max == 0 {
+                ' '
+            } else {
+                BLOCKS[(count * BLOCKS.len()).div_ceil(max) - 1]
+            }
+        })
+        .collect()
+}
+
 /// Validates a basic HTTP authentication string in the format `username:password`.
 ///
 /// # Arguments
@@ -123,6 +204,25 @@ pub fn validate_basic_auth(val: &str) -> Result<String, String> {
     }
 }
 
+/// Validates the name of the `--cache-bust-param` query parameter. It must not be
+/// empty or contain characters with a meaning in the query string.
+///
+/// # Examples
+/// ```rust
+/// use siteprobe::utils::validate_query_param_name;
+///
+/// assert_eq!(validate_query_param_name("cb"), Ok("cb".to_string()));
+/// assert!(validate_query_param_name("a=b").is_err());
+/// ```
+pub fn validate_query_param_name(val: &str) -> Result<String, String> {
+    if val.is_empty() || val.contains(['?', '&', '=', '#', ' ']) {
+        return Err(String::from(
+            "Invalid query parameter name: must not be empty or contain '?', '&', '=', '#' or spaces",
+        ));
+    }
+    Ok(val.to_string())
+}
+
 pub fn kb(bytes: usize) -> String {
     let kilobytes = bytes as f64 / 1024.0;
     format!("{kilobytes:.2}kb")
@@ -0,0 +1,57 @@
+use std::sync::{Arc, Mutex};
+
+/// Collects the warnings raised during a run, e.g. a missing child sitemap or an
+/// invalid value in the config file.
+///
+/// Warnings don't stop the run. They are summarized at the end and included in the
+/// JSON report, so they don't get lost between the progress output. Clones share
+/// the same list, so a collector can be handed to concurrent tasks.
+#[derive(Debug, Clone, Default)]
+pub struct Warnings(Arc<Mutex<Vec<String>>>);
+
+impl Warnings {
+    /// Records a warning.
+    pub fn push(&self, message: impl Into<String>) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(message.into());
+    }
+
+    /// Records several warnings at once.
+    pub fn extend(&self, messages: impl IntoIterator<Item = String>) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(messages);
+    }
+
+    /// Returns the recorded warnings, in the order they were raised.
+    pub fn to_vec(&self) -> Vec<String> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Formats the consolidated warnings shown at the end of a run, e.g.
+///
+/// ```text
+/// ⚠️ 2 warnings
+///   - The referenced sitemap is missing: https://example.com/sitemap2.xml
+///   - Invalid header in config file: ...
+/// ```
+///
+/// Returns `None` if there are no warnings.
+pub fn summary(warnings: &[String]) -> Option<String> {
+    if warnings.is_empty() {
+        return None;
+    }
+    let mut summary = format!(
+        "⚠️ {} warning{}",
+        warnings.len(),
+        if warnings.len() == 1 { "" } else { "s" }
+    );
+    for warning in warnings {
+        summary.push_str(&format!("\n  - {}", warning));
+    }
+    Some(summary)
+}
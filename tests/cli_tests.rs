@@ -95,6 +95,29 @@ fn test_cli_version_short() {
     );
 }
 
+#[test]
+fn test_cli_version_verbose() {
+    // Test --version -v prints build details
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "--version", "-v"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success(), "Version command should succeed");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("siteprobe "));
+    for field in ["commit:", "built:", "rustc:", "features:"] {
+        assert!(
+            stdout.contains(field),
+            "Verbose version should contain '{}'. stdout: {}",
+            field,
+            stdout
+        );
+    }
+    assert!(stdout.contains("cli"), "Should list the cli feature");
+}
+
 #[test]
 fn test_cli_missing_required_argument() {
     // Test running without required sitemap URL
@@ -488,13 +511,37 @@ fn test_cli_tilde_expansion_output_dir() {
         "Tilde should be expanded, directory creation should work"
     );
 
-    // The directory should have been created in the home folder
+    // No literal `~` directory should be created in the working directory
     assert!(
-        std::path::Path::new(&test_path).exists(),
-        "Directory should be created at expanded path: {}",
+        !std::path::Path::new(&output_dir).exists(),
+        "Tilde should be expanded, not used as a literal directory name"
+    );
+
+    // The sitemap can't be probed, so the directory is never written to and
+    // therefore not created
+    assert!(
+        !std::path::Path::new(&test_path).exists(),
+        "Directory should only be created on the first write: {}",
         test_path
     );
 
     // Clean up
     let _ = std::fs::remove_dir_all(&test_path);
 }
+
+#[test]
+fn test_cli_explain_exit_codes() {
+    // --explain-exit-codes works without a sitemap URL
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "--explain-exit-codes"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success(), "Should exit successfully");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("EXIT CODES:"));
+    assert!(stdout.contains("0    All URLs returned 2xx"));
+    assert!(stdout.contains("2    One or more URLs exceeded the slow threshold"));
+    assert!(stdout.contains("130  The run was interrupted with Ctrl-C before probing"));
+}
@@ -0,0 +1,101 @@
+use siteprobe::compare::{compare_reports, StatusChange};
+use siteprobe::options::CompareArgs;
+use std::path::PathBuf;
+use std::process::Command;
+
+const BASELINE: &str = "tests/fixtures/report_baseline.json";
+const CURRENT: &str = "tests/fixtures/report_current.json";
+
+fn compare_args(baseline: &str, current: &str) -> CompareArgs {
+    CompareArgs {
+        baseline: PathBuf::from(baseline),
+        current: PathBuf::from(current),
+        threshold_ms: 250,
+        cache_bust_param: "ts".to_string(),
+    }
+}
+
+#[test]
+fn test_compare_fixture_reports() {
+    let comparison = compare_reports(&compare_args(BASELINE, CURRENT)).unwrap();
+    assert!(comparison.has_regressions());
+
+    // /contact is new and fails its content check, /about started returning 500.
+    assert_eq!(
+        comparison.new_failures,
+        vec![
+            StatusChange {
+                url: "https://example.com/about".to_string(),
+                baseline: Some(200),
+                current: 500,
+            },
+            StatusChange {
+                url: "https://example.com/contact".to_string(),
+                baseline: None,
+                current: 200,
+            },
+        ]
+    );
+    assert_eq!(
+        comparison.fixed,
+        vec![StatusChange {
+            url: "https://example.com/blog".to_string(),
+            baseline: Some(404),
+            current: 200,
+        }]
+    );
+
+    // The homepage is 10ms slower, below the threshold.
+    assert_eq!(comparison.slower.len(), 1);
+    assert_eq!(comparison.slower[0].url, "https://example.com/search");
+    assert_eq!(comparison.slower[0].delta_ms(), 700);
+    assert_eq!(comparison.faster.len(), 1);
+    assert_eq!(comparison.faster[0].url, "https://example.com/shop");
+    assert_eq!(comparison.faster[0].delta_ms(), -1100);
+}
+
+#[test]
+fn test_compare_threshold() {
+    let args = CompareArgs {
+        threshold_ms: 1000,
+        ..compare_args(BASELINE, CURRENT)
+    };
+    let comparison = compare_reports(&args).unwrap();
+    assert!(comparison.slower.is_empty());
+    assert_eq!(comparison.faster.len(), 1);
+}
+
+#[test]
+fn test_compare_same_report() {
+    let comparison = compare_reports(&compare_args(CURRENT, CURRENT)).unwrap();
+    assert!(!comparison.has_regressions());
+    assert!(comparison.fixed.is_empty());
+    assert!(comparison.faster.is_empty());
+}
+
+#[test]
+fn test_compare_invalid_report() {
+    let err =
+        compare_reports(&compare_args(BASELINE, "tests/fixtures/sitemap_valid.xml")).unwrap_err();
+    assert!(err.starts_with("Invalid report"), "{}", err);
+}
+
+#[test]
+fn test_compare_command() {
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "compare", BASELINE, CURRENT])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    assert!(stdout.contains("New Failures:"));
+    assert!(stdout.contains("https://example.com/about (was 200)"));
+    assert!(stdout.contains("Fixed URLs:"));
+    assert!(stdout.contains("+700ms https://example.com/search"));
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "compare", CURRENT, CURRENT])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -1,4 +1,5 @@
 use siteprobe::options::ConfigFile;
+use siteprobe::warnings::Warnings;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process::Command;
@@ -64,7 +65,7 @@ request_timeout = 60
     .expect("Failed to write temp file");
 
     let path = tmp.path().to_path_buf();
-    let config = ConfigFile::load(Some(&path)).expect("Failed to load config");
+    let config = ConfigFile::load(&[path]).expect("Failed to load config");
 
     assert_eq!(config.concurrency_limit, Some(20));
     assert_eq!(config.request_timeout, Some(60));
@@ -75,7 +76,7 @@ request_timeout = 60
 #[test]
 fn test_config_file_load_nonexistent_path() {
     let path = PathBuf::from("/tmp/nonexistent_siteprobe_config_12345.toml");
-    let result = ConfigFile::load(Some(&path));
+    let result = ConfigFile::load(&[path]);
 
     assert!(result.is_err());
     let err = result.unwrap_err();
@@ -86,6 +87,59 @@ fn test_config_file_load_nonexistent_path() {
     );
 }
 
+/// ConfigFile::load() with several paths merges them in order, later files
+/// overriding earlier ones.
+#[test]
+fn test_config_file_load_merges_multiple_files() {
+    let mut base = NamedTempFile::new().expect("Failed to create temp file");
+    writeln!(
+        base,
+        r#"
+concurrency_limit = 20
+request_timeout = 60
+headers = ["X-Team: base"]
+"#
+    )
+    .unwrap();
+    let mut project = NamedTempFile::new().expect("Failed to create temp file");
+    writeln!(
+        project,
+        r#"
+request_timeout = 5
+headers = ["X-Project: site"]
+"#
+    )
+    .unwrap();
+
+    let paths = [base.path().to_path_buf(), project.path().to_path_buf()];
+    let config = ConfigFile::load(&paths).expect("Failed to load config");
+
+    assert_eq!(config.concurrency_limit, Some(20));
+    assert_eq!(config.request_timeout, Some(5));
+    assert_eq!(
+        config.headers.as_deref(),
+        Some(&["X-Project: site".to_string()][..])
+    );
+}
+
+/// ConfigFile::load() fails if any of several paths does not exist.
+#[test]
+fn test_config_file_load_multiple_with_missing_file() {
+    let mut base = NamedTempFile::new().expect("Failed to create temp file");
+    writeln!(base, "concurrency_limit = 20").unwrap();
+
+    let paths = [
+        base.path().to_path_buf(),
+        PathBuf::from("/tmp/nonexistent_siteprobe_config_67890.toml"),
+    ];
+    let err = ConfigFile::load(&paths).unwrap_err();
+    assert!(
+        err.contains("nonexistent_siteprobe_config_67890.toml") && err.contains("not found"),
+        "got: {}",
+        err
+    );
+}
+
 /// Test 4: ConfigFile::load() with no explicit path and no .siteprobe.toml in cwd
 /// should return a default (all-None) config.
 #[test]
@@ -95,7 +149,7 @@ fn test_config_file_load_no_path_no_default_file() {
     let original_dir = std::env::current_dir().expect("Failed to get cwd");
 
     std::env::set_current_dir(tmp_dir.path()).expect("Failed to change dir");
-    let config = ConfigFile::load(None);
+    let config = ConfigFile::load(&[]);
     std::env::set_current_dir(original_dir).expect("Failed to restore dir");
 
     let config = config.expect("Should return default config");
@@ -162,7 +216,7 @@ fn test_apply_config_all_fields() {
     };
 
     let mut cli = Cli::parse_from(["siteprobe", "http://example.com/sitemap.xml"]);
-    cli.apply_config(&config);
+    cli.apply_config(&config, &Warnings::default());
 
     assert_eq!(cli.user_agent, "CustomBot/2.0");
     assert_eq!(cli.concurrency_limit, 20);
@@ -180,7 +234,7 @@ fn test_apply_config_all_fields() {
     assert_eq!(cli.headers, vec!["X-Token: abc".to_string()]);
 }
 
-/// Test 7: apply_config with invalid rate_limit logs warning but doesn't crash.
+/// Test 7: apply_config with invalid rate_limit records a warning but doesn't crash.
 #[test]
 fn test_apply_config_invalid_rate_limit() {
     use clap::Parser;
@@ -192,13 +246,17 @@ fn test_apply_config_invalid_rate_limit() {
     };
 
     let mut cli = Cli::parse_from(["siteprobe", "http://example.com/sitemap.xml"]);
-    cli.apply_config(&config);
+    let warnings = Warnings::default();
+    cli.apply_config(&config, &warnings);
 
     // rate_limit should remain None since the config value was invalid
     assert!(cli.rate_limit.is_none());
+    let warnings = warnings.to_vec();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("Invalid rate_limit in config file"));
 }
 
-/// Test 8: apply_config with invalid header logs warning but doesn't crash.
+/// Test 8: apply_config with invalid header records a warning but doesn't crash.
 #[test]
 fn test_apply_config_invalid_header() {
     use clap::Parser;
@@ -210,10 +268,14 @@ fn test_apply_config_invalid_header() {
     };
 
     let mut cli = Cli::parse_from(["siteprobe", "http://example.com/sitemap.xml"]);
-    cli.apply_config(&config);
+    let warnings = Warnings::default();
+    cli.apply_config(&config, &warnings);
 
     // Only the valid header should be added
     assert_eq!(cli.headers, vec!["Valid: header".to_string()]);
+    let warnings = warnings.to_vec();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("Invalid header in config file"));
 }
 
 /// Test 9: CLI args override config file values.
@@ -231,7 +293,7 @@ fn test_cli_args_override_config_values() {
     writeln!(tmp, "concurrency_limit = 10\nrequest_timeout = 99").unwrap();
 
     let path = tmp.path().to_path_buf();
-    let config = ConfigFile::load(Some(&path)).expect("Failed to load config");
+    let config = ConfigFile::load(&[path]).expect("Failed to load config");
 
     assert_eq!(config.concurrency_limit, Some(10));
     assert_eq!(config.request_timeout, Some(99));
@@ -248,7 +310,7 @@ fn test_cli_args_override_config_values() {
 
     // After applying config, values from config should take effect
     // (since arg_provided checks std::env::args which won't have our flags)
-    cli.apply_config(&config);
+    cli.apply_config(&config, &Warnings::default());
 
     assert_eq!(cli.concurrency_limit, 10);
     assert_eq!(cli.request_timeout, 99);
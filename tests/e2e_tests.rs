@@ -1,5 +1,6 @@
 use std::fs;
-use std::process::Command;
+use std::io::Write;
+use std::process::{Command, Stdio};
 use std::time::Duration;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -160,16 +161,16 @@ async fn test_e2e_valid_sitemap() {
         "JSON should have 5 responses"
     );
 
-    // Verify output directory was created with downloaded pages
-    // Note: Only 2 files because URLs with same path but different query strings
-    // overwrite each other when saved to disk
+    // Verify output directory was created with downloaded pages. URLs with the same
+    // path but different query strings are stored in separate files.
     assert!(output_dir.exists(), "Output directory should be created");
     let downloaded_files: Vec<_> = fs::read_dir(&output_dir)
         .expect("Failed to read output dir")
         .collect();
-    assert!(
-        downloaded_files.len() >= 1,
-        "Should have at least 1 downloaded page"
+    assert_eq!(
+        downloaded_files.len(),
+        5,
+        "Should have one file for the root and each catalog page"
     );
 }
 
@@ -536,10 +537,11 @@ async fn test_e2e_invalid_sitemap_index() {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
 
-    // Should show error messages about missing sitemaps
+    // Should summarize the missing sitemaps as warnings in stderr
     assert!(
-        stderr.contains("ERROR") && stderr.contains("missing"),
-        "Should report errors about missing sitemaps in stderr"
+        stderr.contains("3 warnings") && stderr.contains("The referenced sitemap is missing"),
+        "Should report warnings about missing sitemaps in stderr: {}",
+        stderr
     );
 
     // Report should show 0 requests processed
@@ -765,3 +767,1507 @@ async fn test_e2e_redirect_responses() {
         "HTML should contain redirect status class"
     );
 }
+
+#[tokio::test]
+async fn test_e2e_followed_redirects_count_toward_redirect_rate() {
+    let mock_server = MockServer::start().await;
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/redirect</loc></url>
+  <url><loc>{base}/destination</loc></url>
+</urlset>"#,
+        base = mock_server.uri()
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/redirect"))
+        .respond_with(ResponseTemplate::new(301).append_header("Location", "/destination"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/destination"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--json",
+            "--follow-redirects",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let status = &json["statistics"]["statusCode"];
+    assert_eq!(status["successRatePercentage"], 100.0);
+    assert_eq!(status["redirectRatePercentage"], 50.0);
+}
+
+#[tokio::test]
+async fn test_e2e_discover_falls_back_to_sitemap_index() {
+    let mock_server = MockServer::start().await;
+
+    let index_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>{base}/pages.xml</loc></sitemap>
+</sitemapindex>"#,
+        base = mock_server.uri()
+    );
+    let pages_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/page</loc></url>
+</urlset>"#,
+        base = mock_server.uri()
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/sitemap_index.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(index_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/pages.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(pages_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+
+    // Without --discover, a 404 sitemap is an error.
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &sitemap_url])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert!(!output.status.success());
+
+    // With --discover, the sitemap index is found and used.
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &sitemap_url, "--discover"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Command failed. stdout: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains(&format!("using {}/sitemap_index.xml", mock_server.uri())),
+        "Should log the discovered sitemap URL. stdout: {}",
+        stdout
+    );
+}
+
+#[tokio::test]
+async fn test_e2e_timeout_override_for_matching_urls() {
+    let mock_server = MockServer::start().await;
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/export</loc></url>
+  <url><loc>{base}/page</loc></url>
+</urlset>"#,
+        base = mock_server.uri()
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/export"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(1500)))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--json",
+            "--request-timeout",
+            "1",
+            "--timeout-override",
+            "/export$=5",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success(), "Slow export should not time out");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let responses = json["responses"].as_array().unwrap();
+
+    let export = responses
+        .iter()
+        .find(|r| r["url"].as_str().unwrap().ends_with("/export"))
+        .unwrap();
+    assert_eq!(export["statusCode"], 200);
+    assert_eq!(export["timeoutOverrideSecs"], 5);
+
+    let page = responses
+        .iter()
+        .find(|r| r["url"].as_str().unwrap().ends_with("/page"))
+        .unwrap();
+    assert!(page.get("timeoutOverrideSecs").is_none());
+}
+
+#[tokio::test]
+async fn test_e2e_sitemap_served_as_html_is_reported() {
+    let mock_server = MockServer::start().await;
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/page</loc></url>
+</urlset>"#,
+        base = mock_server.uri()
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(sitemap_xml, "text/html"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/homepage.xml"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw("<!DOCTYPE html><html><body>Home</body></html>", "text/html"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // A valid sitemap served as text/html is probed, with a warning in the report.
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &sitemap_url, "--json"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let warnings = json["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].as_str().unwrap().contains("text/html"));
+
+    // An HTML page instead of a sitemap fails with an explanation.
+    let homepage_url = format!("{}/homepage.xml", mock_server.uri());
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &homepage_url])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("returned an HTML page"),
+        "Should explain the HTML response. stderr: {}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn test_e2e_sections_limit_text_report() {
+    let mock_server = MockServer::start().await;
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/missing</loc></url>
+</urlset>"#,
+        base = mock_server.uri()
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &sitemap_url, "--sections", "errors"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Error Responses:"), "stdout: {}", stdout);
+    assert!(!stdout.contains("Response Time and Performance Statistics:"));
+    assert!(!stdout.contains("Concurrency Limit"));
+}
+
+#[tokio::test]
+async fn test_e2e_changed_since_skips_healthy_urls() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/healthy</loc></url>
+  <url><loc>{base}/moved</loc></url>
+  <url><loc>{base}/broken</loc></url>
+  <url><loc>{base}/new</loc></url>
+</urlset>"#
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    for page in ["/healthy", "/moved"] {
+        Mock::given(method("GET"))
+            .and(path(page))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+    }
+    for page in ["/broken", "/new"] {
+        Mock::given(method("GET"))
+            .and(path(page))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let temp_dir = temp_dir("changed_since");
+    let previous = temp_dir.path().join("previous.json");
+    fs::write(
+        &previous,
+        serde_json::json!({
+            "responses": [
+                {"url": format!("{base}/healthy"), "statusCode": 200},
+                // A followed redirect, with the cache buster of --append-timestamp.
+                {
+                    "url": format!("{base}/moved-here"),
+                    "requestedUrl": format!("{base}/moved?ts=1234567890"),
+                    "statusCode": 200,
+                },
+                {"url": format!("{base}/broken"), "statusCode": 500},
+            ]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let sitemap_url = format!("{}/sitemap.xml", base);
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--json",
+            "--changed-since",
+            previous.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["config"]["skippedUnchanged"], 2);
+    assert_eq!(json["responses"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_e2e_expect_url_count() {
+    let mock_server = MockServer::start().await;
+
+    let sitemap_xml = include_str!("fixtures/sitemap_valid.xml")
+        .replace("http://www.example.com", &mock_server.uri());
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    // The sitemap must not be probed when the count is off.
+    Mock::given(method("GET"))
+        .and(path("/"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--expect-url-count",
+            "100",
+            "--url-count-tolerance",
+            "5",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("expected 100 URLs ±5% (95 to 105)"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn test_e2e_max_total_bytes_stops_probing() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let pages: String = (1..=5)
+        .map(|i| format!("<url><loc>{base}/page{i}</loc></url>"))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{pages}</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    for i in 1..=5 {
+        Mock::given(method("GET"))
+            .and(path(format!("/page{i}")))
+            .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(1000)))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let sitemap_url = format!("{}/sitemap.xml", base);
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--json",
+            "--concurrency-limit",
+            "1",
+            "--max-total-bytes",
+            "2500",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["responses"].as_array().unwrap().len(), 3);
+    assert_eq!(json["config"]["byteCapped"], true);
+    assert_eq!(json["config"]["totalBytes"], 3000);
+    assert!(json["warnings"][0]
+        .as_str()
+        .unwrap()
+        .contains("exceeding --max-total-bytes 2500"));
+}
+
+#[tokio::test]
+async fn test_e2e_max_duration_returns_partial_report() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let pages: String = (1..=4)
+        .map(|i| format!("<url><loc>{base}/page{i}</loc></url>"))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{pages}</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    for i in 2..=4 {
+        Mock::given(method("GET"))
+            .and(path(format!("/page{i}")))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let sitemap_url = format!("{}/sitemap.xml", base);
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--json",
+            "--concurrency-limit",
+            "1",
+            "--max-duration",
+            "2",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let responses = json["responses"].as_array().unwrap();
+    assert_eq!(responses.len(), 1);
+    assert!(responses[0]["url"].as_str().unwrap().ends_with("/page1"));
+    assert_eq!(json["config"]["maxDuration"], 2);
+    assert_eq!(json["config"]["timeCapped"], true);
+    assert!(json["warnings"][0]
+        .as_str()
+        .unwrap()
+        .contains("Stopped probing at --max-duration 2s"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_e2e_ctrl_c_prints_partial_report() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let pages: String = (1..=4)
+        .map(|i| format!("<url><loc>{base}/page{i}</loc></url>"))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{pages}</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    for i in 2..=4 {
+        Mock::given(method("GET"))
+            .and(path(format!("/page{i}")))
+            .respond_with(ResponseTemplate::new(500).set_delay(Duration::from_secs(4)))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let temp_dir = temp_dir("ctrl_c");
+    let status_file = temp_dir.path().join("status.json");
+
+    // Run the binary directly, so the signal reaches siteprobe and not cargo.
+    let child = Command::new(env!("CARGO_BIN_EXE_siteprobe"))
+        .args([
+            &format!("{}/sitemap.xml", base),
+            "--json",
+            "--concurrency-limit",
+            "1",
+            "--status-file",
+            status_file.to_str().unwrap(),
+        ])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute siteprobe binary");
+
+    // Interrupt while /page2 is in flight.
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let status = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .expect("Failed to send SIGINT");
+    assert!(status.success());
+
+    // The exit code reflects the completed responses: /page2 returned a 500.
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+
+    // The running request completes; the remaining ones are not sent.
+    let urls: Vec<&str> = json["responses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["url"].as_str().unwrap())
+        .collect();
+    assert_eq!(urls, [format!("{base}/page1"), format!("{base}/page2")]);
+    assert!(json["warnings"][0]
+        .as_str()
+        .unwrap()
+        .contains("Interrupted with Ctrl-C"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Interrupted after 2 responses: 1 error — exit 1"));
+
+    let status: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&status_file).unwrap()).unwrap();
+    assert_eq!(status["exitCode"], 1);
+    assert_eq!(status["interrupted"], true);
+    assert_eq!(status["partial"], true);
+}
+
+#[tokio::test]
+async fn test_e2e_silent_on_success() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/ok</loc></url>
+</urlset>"#
+    );
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ok"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("OK"))
+        .mount(&mock_server)
+        .await;
+
+    let run = |sitemap: &str| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "--quiet",
+                "--",
+                &format!("{}/{}", base, sitemap),
+                "--silent-on-success",
+            ])
+            .output()
+            .expect("Failed to execute siteprobe binary")
+    };
+
+    // All OK: no output at all.
+    let output = run("sitemap.xml");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+
+    // A failing URL prints the normal report.
+    Mock::given(method("GET"))
+        .and(path("/broken.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/missing</loc></url>
+</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    let output = run("broken.xml");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Error Responses:"), "stdout: {}", stdout);
+    assert!(stdout.contains("exit 1"), "stdout: {}", stdout);
+    assert!(!stdout.contains("Collect all URLs"), "stdout: {}", stdout);
+}
+
+#[tokio::test]
+async fn test_e2e_status_file() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/ok</loc></url>
+  <url><loc>{base}/missing</loc></url>
+</urlset>"#
+    );
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/ok"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+        .mount(&mock_server)
+        .await;
+
+    let tmp = temp_dir("status_file");
+    let status_path = tmp.path().join("status.json");
+    let mut args = build_cli_args(&format!("{base}/sitemap.xml"), None, None, None, None);
+    args.push("--status-file".to_string());
+    args.push(status_path.to_str().unwrap().to_string());
+
+    let output = Command::new("cargo").args(&args).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let status: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&status_path).unwrap()).unwrap();
+    assert_eq!(status["exitCode"], 1);
+    assert_eq!(status["total"], 2);
+    assert_eq!(status["ok"], 1);
+    assert_eq!(status["errors"], 1);
+    assert_eq!(status["slow"], 0);
+    assert_eq!(status["partial"], false);
+    assert!(status["finishedAt"].as_u64().unwrap() > 0);
+
+    // A run that fails before probing still writes a status.
+    let mut args = build_cli_args(&format!("{base}/no-sitemap.xml"), None, None, None, None);
+    args.push("--status-file".to_string());
+    args.push(status_path.to_str().unwrap().to_string());
+
+    let output = Command::new("cargo").args(&args).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let status: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&status_path).unwrap()).unwrap();
+    assert_eq!(status["exitCode"], 1);
+    assert!(status["error"].as_str().unwrap().contains("no-sitemap.xml"));
+}
+
+#[tokio::test]
+async fn test_e2e_url_truncate() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    let long_path = format!("/{}", "very-long-segment/".repeat(6));
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}{long_path}</loc></url>
+</urlset>"#
+    );
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    let run = |extra: &[&str]| {
+        let mut args = vec![
+            "run".to_string(),
+            "--quiet".to_string(),
+            "--".to_string(),
+            format!("{base}/sitemap.xml"),
+        ];
+        args.extend(extra.iter().map(|a| a.to_string()));
+        let output = Command::new("cargo").args(&args).output().unwrap();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    // Not attached to a terminal: full URLs by default.
+    let stdout = run(&[]);
+    assert!(stdout.contains(&format!("{base}{long_path}")), "{}", stdout);
+
+    let stdout = run(&["--url-truncate", "40"]);
+    let truncated: String = format!("{base}{long_path}").chars().take(39).collect();
+    assert!(stdout.contains(&format!("{truncated}… ")), "{}", stdout);
+    assert!(!stdout.contains(&long_path));
+}
+
+#[tokio::test]
+async fn test_e2e_max_urls() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    let urls: String = ["e", "b", "d", "a", "c"]
+        .iter()
+        .map(|p| format!("  <url><loc>{base}/{p}</loc></url>\n"))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+{urls}</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let mut args = build_cli_args(&format!("{base}/sitemap.xml"), None, None, None, None);
+    args.extend(["--json", "--max-urls", "2"].map(String::from));
+
+    let output = Command::new("cargo").args(&args).output().unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let probed: Vec<&str> = json["responses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        // Strip the --append-timestamp query string.
+        .map(|r| r["url"].as_str().unwrap().split('?').next().unwrap())
+        .collect();
+    assert_eq!(probed, vec![format!("{base}/a"), format!("{base}/b")]);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Probing the first 2 URLs (--max-urls), skipping 3 more."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_e2e_max_urls_keeps_urls_file_order() {
+    let temp_dir = temp_dir("max_urls_order");
+    let urls_file = temp_dir.path().join("urls.txt");
+    fs::write(
+        &urls_file,
+        "https://example.com/c\nhttps://example.com/a\nhttps://example.com/b\n",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "--urls-file",
+            urls_file.to_str().unwrap(),
+            "--list-urls",
+            "--max-urls",
+            "2",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        ["https://example.com/c", "https://example.com/a"]
+    );
+}
+
+#[tokio::test]
+async fn test_e2e_list_urls_prints_urls_without_fetching() {
+    let mock_server = MockServer::start().await;
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/a</loc></url>
+  <url><loc>{base}/b</loc></url>
+  <url><loc>{base}/a</loc></url>
+</urlset>"#,
+        base = mock_server.uri()
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    for page in ["/a", "/b"] {
+        Mock::given(path(page))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+    let expected = vec![
+        format!("{}/a", mock_server.uri()),
+        format!("{}/b", mock_server.uri()),
+    ];
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &sitemap_url, "--list-urls"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), expected);
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--list-urls",
+            "--json",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert!(output.status.success());
+    let urls: Vec<String> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(urls, expected);
+}
+
+#[tokio::test]
+async fn test_e2e_dry_run_prints_summary_without_fetching() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap_index.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>{base}/pages.xml</loc></sitemap>
+  <sitemap><loc>{base}/posts.xml</loc></sitemap>
+</sitemapindex>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    for (sitemap, pages) in [("/pages.xml", vec!["a", "b"]), ("/posts.xml", vec!["c"])] {
+        let locs: String = pages
+            .iter()
+            .map(|p| format!("<url><loc>{base}/{p}</loc></url>"))
+            .collect();
+        Mock::given(method("GET"))
+            .and(path(sitemap))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{locs}</urlset>"#
+            )))
+            .mount(&mock_server)
+            .await;
+        for page in pages {
+            Mock::given(path(format!("/{page}")))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(0)
+                .mount(&mock_server)
+                .await;
+        }
+    }
+
+    let sitemap_url = format!("{base}/sitemap_index.xml");
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &sitemap_url, "--dry-run"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains("Dry run: 3 URLs would be probed with 3 requests."));
+    assert!(stdout.contains(&format!("{base}/pages.xml: 2 URLs")));
+    assert!(stdout.contains(&format!("{base}/posts.xml: 1 URLs")));
+    assert!(stdout.contains("Estimated time: up to"));
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &sitemap_url, "--dry-run", "--json"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert!(output.status.success());
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["urls"], 3);
+    assert_eq!(summary["sitemaps"][0]["urls"], 2);
+}
+
+#[tokio::test]
+async fn test_e2e_failures_path_and_urls_file() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/ok</loc></url>
+  <url><loc>{base}/missing</loc></url>
+</urlset>"#
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    // The healthy page is only probed in the first run, the failing one in both.
+    Mock::given(method("GET"))
+        .and(path("/ok"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = temp_dir("failures");
+    let failures = temp_dir.path().join("failures.txt");
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{base}/sitemap.xml"),
+            "--failures-path",
+            failures.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        fs::read_to_string(&failures).unwrap(),
+        format!("{base}/missing\n")
+    );
+
+    let report = temp_dir.path().join("report.json");
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "--urls-file",
+            failures.to_str().unwrap(),
+            "--report-path-json",
+            report.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    let responses = json["responses"].as_array().unwrap();
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0]["url"], format!("{base}/missing"));
+}
+
+#[tokio::test]
+async fn test_e2e_url_list_from_stdin_and_file() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/a"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/b"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+
+    let list = format!("# Pages\n{base}/a\n{base}/b\n");
+
+    let mut child = Command::new("cargo")
+        .args(["run", "--quiet", "--", "-", "--list-urls"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute siteprobe binary");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(list.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert_eq!(stdout, format!("{base}/a\n{base}/b\n"));
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    fs::write(file.path(), &list).unwrap();
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            file.path().to_str().unwrap(),
+            "--json",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        json["responses"].as_array().unwrap().len(),
+        2,
+        "stdout: {}",
+        stdout
+    );
+}
+
+#[tokio::test]
+async fn test_e2e_multiple_sitemaps_merged() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    for (name, pages) in [("blog", ["a", "shared"]), ("shop", ["b", "shared"])] {
+        let urls: String = pages
+            .iter()
+            .map(|page| format!("<url><loc>{base}/{page}</loc></url>"))
+            .collect();
+        Mock::given(method("GET"))
+            .and(path(format!("/{name}.xml")))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{urls}</urlset>"#
+            )))
+            .mount(&mock_server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let blog = format!("{base}/blog.xml");
+    let shop = format!("{base}/shop.xml");
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", &blog, &shop, "--json"])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let mut urls: Vec<&str> = json["responses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["url"].as_str().unwrap())
+        .collect();
+    urls.sort();
+    assert_eq!(
+        urls,
+        vec![
+            format!("{base}/a"),
+            format!("{base}/b"),
+            format!("{base}/shared")
+        ]
+    );
+    assert_eq!(json["config"]["sitemapUrl"], format!("{blog}, {shop}"));
+}
+
+#[test]
+fn test_e2e_local_sitemap_file() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "tests/fixtures/sitemap_valid.xml",
+            "--list-urls",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert_eq!(stdout.lines().count(), 5, "stdout: {}", stdout);
+    assert!(stdout.contains("http://www.example.com/catalog?item=12"));
+}
+
+#[tokio::test]
+async fn test_e2e_requested_and_final_url() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"><url><loc>{base}/old</loc></url></urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/old"))
+        .respond_with(ResponseTemplate::new(301).insert_header("location", "/new"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/new"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let csv_report = temp_dir.path().join("report.csv");
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{base}/sitemap.xml"),
+            "--follow-redirects",
+            "--append-timestamp",
+            "--json",
+            "--report-path",
+            csv_report.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let response = &json["responses"][0];
+    // The requested URL is listed without the cache buster of --append-timestamp.
+    assert_eq!(response["requestedUrl"], format!("{base}/old"));
+    assert_eq!(response["url"], format!("{base}/new"));
+    assert!(response.get("finalUrl").is_none());
+
+    let csv_content = fs::read_to_string(&csv_report).unwrap();
+    let mut lines = csv_content.lines();
+    assert!(lines
+        .next()
+        .unwrap()
+        .starts_with("URL,Response Time (ms),Response Size,Status Code,Requested URL"));
+    let line = lines.next().unwrap();
+    assert!(
+        line.starts_with(&format!("{base}/new,")) && line.ends_with(&format!(",{base}/old")),
+        "{}",
+        line
+    );
+}
+
+#[tokio::test]
+async fn test_e2e_redirects_are_errors() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/old</loc></url>
+  <url><loc>{base}/new</loc></url>
+</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/old"))
+        .respond_with(ResponseTemplate::new(301).insert_header("location", "/new"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/new"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{base}/sitemap.xml");
+    let run = |extra: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--quiet", "--", &sitemap_url, "--json"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute siteprobe binary")
+    };
+
+    assert_eq!(run(&[]).status.code(), Some(0));
+    assert_eq!(run(&["--redirects-are-errors"]).status.code(), Some(1));
+    assert_eq!(
+        run(&["--redirects-are-errors", "--follow-redirects"])
+            .status
+            .code(),
+        Some(1)
+    );
+}
+
+#[tokio::test]
+async fn test_e2e_not_modified_is_not_a_redirect() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/cached</loc></url>
+</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/cached"))
+        .respond_with(ResponseTemplate::new(304))
+        .mount(&mock_server)
+        .await;
+
+    // A conditional request answered with a 304 is a cache hit, not a redirect.
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{base}/sitemap.xml"),
+            "--json",
+            "--header",
+            "If-None-Match: \"abc\"",
+            "--redirects-are-errors",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let status = &json["statistics"]["statusCode"];
+    assert_eq!(status["redirectRatePercentage"], 0.0);
+    assert_eq!(status["cacheHitRatePercentage"], 100.0);
+}
+
+#[tokio::test]
+async fn test_e2e_expect_contains() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/good</loc></url>
+  <url><loc>{base}/soft-error</loc></url>
+  <url><loc>{base}/missing</loc></url>
+</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/good"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string("<main>Hello</main><footer>ACME</footer>"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/soft-error"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Something went wrong"))
+        .mount(&mock_server)
+        .await;
+    // Error responses are listed as such, their body is not checked.
+    Mock::given(method("GET"))
+        .and(path("/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("Not found"))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{base}/sitemap.xml");
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--expect-contains",
+            "<footer>",
+            "--json",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let flagged: Vec<&str> = json["responses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|r| r.get("missingMarkers").is_some())
+        .map(|r| r["url"].as_str().unwrap())
+        .collect();
+    assert_eq!(flagged, vec![format!("{base}/soft-error")]);
+
+    // The text report lists the page under "Content Mismatch".
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--expect-contains",
+            "<footer>",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Content Mismatch:"), "stdout: {}", stdout);
+    assert!(stdout.contains("missing '<footer>'"), "stdout: {}", stdout);
+}
+
+#[tokio::test]
+async fn test_e2e_expect_matches() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/rendered</loc></url>
+  <url><loc>{base}/broken</loc></url>
+</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/rendered"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<title>Welcome</title>"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/broken"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<title>{{ title }}</title>"))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{base}/sitemap.xml");
+    let run = |pattern: &str| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "--quiet",
+                "--",
+                &sitemap_url,
+                "--expect-matches",
+                pattern,
+                "--json",
+            ])
+            .output()
+            .expect("Failed to execute siteprobe binary")
+    };
+
+    // Both bodies match.
+    assert_eq!(run("<title>.+</title>").status.code(), Some(0));
+
+    // The unrendered template placeholder doesn't match.
+    let output = run("<title>[^{]+</title>");
+    assert_eq!(output.status.code(), Some(1));
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.stdout)).unwrap();
+    let flagged: Vec<(&str, &serde_json::Value)> = json["responses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|r| Some((r["url"].as_str()?, r.get("unmatchedPatterns")?)))
+        .collect();
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0].0, format!("{base}/broken"));
+    assert_eq!(flagged[0].1, &serde_json::json!(["<title>[^{]+</title>"]));
+}
+
+#[tokio::test]
+async fn test_e2e_max_redirects_exceeded() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/r1</loc></url>
+</urlset>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    // /r1 -> /r2 -> /r3 -> /r4 -> /final
+    for i in 1..=4 {
+        let next = if i < 4 {
+            format!("/r{}", i + 1)
+        } else {
+            "/final".to_string()
+        };
+        Mock::given(method("GET"))
+            .and(path(format!("/r{i}")))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", next.as_str()))
+            .mount(&mock_server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/final"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{base}/sitemap.xml");
+    let run = |extra: &[&str]| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "--quiet",
+                "--",
+                &sitemap_url,
+                "--follow-redirects",
+                "--max-redirects",
+                "2",
+            ])
+            .args(extra)
+            .output()
+            .expect("Failed to execute siteprobe binary")
+    };
+
+    // The cut-off chain is listed as an error and fails the run.
+    let output = run(&[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    let errors = stdout
+        .split("Error Responses:")
+        .nth(1)
+        .expect("no Error Responses section");
+    assert!(errors.contains(&format!("{base}/r1")), "{}", stdout);
+
+    let output = run(&["--json"]);
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.stdout)).unwrap();
+    let responses = json["responses"].as_array().unwrap();
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0]["statusCode"], 302);
+    assert_eq!(responses[0]["redirectCount"], 2);
+    assert!(responses[0]["error"].is_string());
+}
@@ -1,4 +1,5 @@
 use reqwest::StatusCode;
+use siteprobe::options::EmptyBodyMode;
 use siteprobe::report::{Report, Response};
 use std::collections::VecDeque;
 use std::time::Duration;
@@ -9,6 +10,7 @@ fn make_response(status: u16, response_time_ms: u64) -> Response {
         response_time: Duration::from_millis(response_time_ms),
         response_size: 1024,
         status_code: StatusCode::from_u16(status).unwrap(),
+        ..Default::default()
     }
 }
 
@@ -19,6 +21,7 @@ fn make_report(responses: Vec<Response>) -> Report {
         rate_limit: None,
         total_time: Duration::from_secs(1),
         responses: VecDeque::from(responses),
+        ..Default::default()
     }
 }
 
@@ -71,3 +74,463 @@ fn exit_code_0_when_slow_threshold_is_none() {
     ]);
     assert_eq!(report.exit_code(None), 0u8.into());
 }
+
+#[test]
+fn exit_reason_explains_errors() {
+    let report = make_report(vec![
+        make_response(200, 100),
+        make_response(404, 200),
+        make_response(500, 200),
+    ]);
+    assert_eq!(
+        report.exit_reason(None),
+        "Exiting with code 1 because 2 URL(s) returned 4xx/5xx or failed."
+    );
+}
+
+#[test]
+fn exit_reason_explains_slow_responses() {
+    let report = make_report(vec![make_response(200, 100), make_response(200, 3500)]);
+    assert_eq!(
+        report.exit_reason(Some(2.0)),
+        "Exiting with code 2 because 1 URL(s) exceeded the slow threshold of 2s."
+    );
+}
+
+#[test]
+fn exit_reason_explains_success() {
+    let report = make_report(vec![make_response(200, 100)]);
+    assert!(report.exit_reason(None).starts_with("Exiting with code 0"));
+}
+
+#[test]
+fn summary_line_counts_ok_responses() {
+    let report = make_report(vec![make_response(200, 100), make_response(301, 100)]);
+    assert_eq!(report.summary_line(None), "✅ 1/2 OK — exit 0");
+}
+
+#[test]
+fn summary_line_counts_errors_and_slow_responses() {
+    let report = make_report(vec![
+        make_response(404, 100),
+        make_response(500, 100),
+        make_response(200, 3000),
+    ]);
+    assert_eq!(
+        report.summary_line(Some(1.0)),
+        "❌ 2 errors, 1 slow — exit 1"
+    );
+}
+
+#[test]
+fn summary_line_reports_slow_only_as_exit_2() {
+    let report = make_report(vec![make_response(200, 3000)]);
+    assert_eq!(report.summary_line(Some(1.0)), "❌ 1 slow — exit 2");
+}
+
+fn make_empty_response(status: u16) -> Response {
+    Response {
+        response_size: 0,
+        ..make_response(status, 100)
+    }
+}
+
+#[test]
+fn empty_body_is_ignored_without_flag() {
+    let report = make_report(vec![make_empty_response(200)]);
+    assert_eq!(report.exit_code(None), 0u8.into());
+    assert!(report.empty_responses().is_empty());
+}
+
+#[test]
+fn empty_body_warn_lists_but_exits_0() {
+    let report = Report {
+        empty_body: Some(EmptyBodyMode::Warn),
+        ..make_report(vec![
+            make_empty_response(200),
+            make_empty_response(204),
+            make_response(200, 100),
+        ])
+    };
+    assert_eq!(report.exit_code(None), 0u8.into());
+    let empty = report.empty_responses();
+    assert_eq!(empty.len(), 1, "204 No Content is expected to be empty");
+    assert_eq!(empty[0].status_code, StatusCode::OK);
+}
+
+#[test]
+fn empty_body_error_exits_1() {
+    let report = Report {
+        empty_body: Some(EmptyBodyMode::Error),
+        ..make_report(vec![make_empty_response(200), make_response(200, 100)])
+    };
+    assert_eq!(report.exit_code(None), 1u8.into());
+    assert_eq!(report.summary_line(None), "❌ 1 error — exit 1");
+    assert!(report.exit_reason(None).contains("empty body"));
+}
+
+#[tokio::test]
+async fn stop_on_first_error_aborts_remaining_requests() {
+    use std::process::Command;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/a-broken</loc></url>
+  <url><loc>{base}/b-slow</loc></url>
+  <url><loc>{base}/c-slow</loc></url>
+</urlset>"#
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/a-broken"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+    for slow in ["/b-slow", "/c-slow"] {
+        Mock::given(method("GET"))
+            .and(path(slow))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(30)))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let started = std::time::Instant::now();
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", base),
+            "--stop-on-first-error",
+            "--concurrency-limit",
+            "1",
+            "--request-timeout",
+            "60",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        started.elapsed() < Duration::from_secs(20),
+        "Should not wait for the slow requests"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!(
+            "Stopped on first error: 404 Not Found {}/a-broken",
+            base
+        )),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn stop_on_first_error_stops_on_content_mismatch() {
+    use std::process::Command;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/a-mismatch</loc></url>
+  <url><loc>{base}/b-slow</loc></url>
+  <url><loc>{base}/c-slow</loc></url>
+</urlset>"#
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/a-mismatch"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Something went wrong"))
+        .mount(&mock_server)
+        .await;
+    for slow in ["/b-slow", "/c-slow"] {
+        Mock::given(method("GET"))
+            .and(path(slow))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("Welcome")
+                    .set_delay(Duration::from_secs(30)),
+            )
+            .mount(&mock_server)
+            .await;
+    }
+
+    // A content mismatch fails the run like a 4xx/5xx, so it stops the run too.
+    let started = std::time::Instant::now();
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", base),
+            "--stop-on-first-error",
+            "--expect-contains",
+            "Welcome",
+            "--concurrency-limit",
+            "1",
+            "--request-timeout",
+            "60",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        started.elapsed() < Duration::from_secs(20),
+        "Should not wait for the slow requests"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(&format!(
+            "Stopped on first error: 200 OK {}/a-mismatch",
+            base
+        )),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[tokio::test]
+async fn fail_fast_returns_quickly_with_a_partial_report() {
+    use std::process::Command;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let slow_urls: String = (1..=8)
+        .map(|i| format!("<url><loc>{base}/slow{i}</loc></url>"))
+        .collect();
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{slow_urls}<url><loc>{base}/broken</loc></url></urlset>"#
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/broken"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+    for i in 1..=8 {
+        Mock::given(method("GET"))
+            .and(path(format!("/slow{i}")))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(30)))
+            .mount(&mock_server)
+            .await;
+    }
+
+    // All URLs are in flight at once, the broken one answers first.
+    let started = std::time::Instant::now();
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", base),
+            "--json",
+            "--fail-fast",
+            "--concurrency-limit",
+            "10",
+            "--request-timeout",
+            "60",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        started.elapsed() < Duration::from_secs(20),
+        "Should not wait for the slow requests"
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let responses = json["responses"].as_array().unwrap();
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0]["statusCode"], 500);
+}
+
+fn make_redirect(status: u16) -> Response {
+    Response {
+        redirected: true,
+        ..make_response(status, 100)
+    }
+}
+
+#[test]
+fn redirects_are_ignored_without_flag() {
+    let report = make_report(vec![make_redirect(301), make_response(200, 100)]);
+    assert_eq!(report.exit_code(None), 0u8.into());
+    assert!(report.error_responses().is_empty());
+}
+
+#[test]
+fn redirects_are_errors_exits_1() {
+    // A 301, and a followed redirect that ended in a 200.
+    let report = Report {
+        redirects_are_errors: true,
+        ..make_report(vec![
+            make_redirect(301),
+            make_redirect(200),
+            make_response(200, 100),
+        ])
+    };
+    assert_eq!(report.exit_code(None), 1u8.into());
+    assert_eq!(report.error_responses().len(), 2);
+    assert_eq!(
+        report.exit_reason(None),
+        "Exiting with code 1 because 2 URL(s) redirected (--redirects-are-errors)."
+    );
+}
+
+#[test]
+fn missing_marker_exits_1() {
+    let report = make_report(vec![
+        Response {
+            missing_markers: vec!["Imprint".to_string()],
+            ..make_response(200, 100)
+        },
+        make_response(200, 100),
+    ]);
+    assert_eq!(report.exit_code(None), 1u8.into());
+    assert_eq!(report.content_mismatches().len(), 1);
+    assert!(report.error_responses().is_empty());
+    assert_eq!(
+        report.exit_reason(None),
+        "Exiting with code 1 because 1 URL(s) failed a content check (--expect-contains, --expect-matches)."
+    );
+}
+
+#[test]
+fn soft_404_exits_1() {
+    let report = make_report(vec![
+        Response {
+            soft_404: true,
+            ..make_response(200, 100)
+        },
+        make_response(200, 100),
+    ]);
+    assert_eq!(report.exit_code(None), 1u8.into());
+    assert_eq!(report.soft_404s().len(), 1);
+    assert!(report.error_responses().is_empty());
+    assert_eq!(
+        report.exit_reason(None),
+        "Exiting with code 1 because 1 URL(s) were soft 404s (--soft-404-marker)."
+    );
+}
+
+#[test]
+fn min_success_rate_exits_3() {
+    // 2 of 4 URLs return 2xx: a success rate of 50%.
+    let responses = vec![
+        make_response(200, 100),
+        make_response(200, 100),
+        make_response(301, 100),
+        make_response(404, 100),
+    ];
+    let report = Report {
+        min_success_rate: Some(80.0),
+        ..make_report(responses.clone())
+    };
+    assert_eq!(report.exit_code(None), 3u8.into());
+    assert_eq!(report.success_rate_shortfall(), Some((50.0, 80.0)));
+    assert_eq!(
+        report.exit_reason(None),
+        "Exiting with code 3 because the success rate of 50.00% is below the minimum of 80% (--min-success-rate)."
+    );
+    assert_eq!(report.status_json(None)["exitCode"], 3);
+    assert!(report.summary_line(None).ends_with("— exit 3"));
+
+    // Meeting the minimum leaves the exit code to the individual responses.
+    let report = Report {
+        min_success_rate: Some(50.0),
+        ..make_report(responses)
+    };
+    assert_eq!(report.success_rate_shortfall(), None);
+    assert_eq!(report.exit_code(None), 1u8.into());
+
+    let report = Report {
+        min_success_rate: Some(50.0),
+        ..make_report(vec![make_response(200, 100), make_response(302, 100)])
+    };
+    assert_eq!(report.exit_code(None), 0u8.into());
+}
+
+#[test]
+fn exceeded_p95_budget_exits_4() {
+    // 18 fast and 2 slow responses: the p95 is slow, the average is not.
+    let mut responses: Vec<Response> = (0..18).map(|_| make_response(200, 100)).collect();
+    responses.extend([make_response(200, 1500), make_response(200, 1500)]);
+    let report = Report {
+        max_p95_ms: Some(1000),
+        max_avg_ms: Some(1000),
+        max_error_rate: Some(0.0),
+        ..make_report(responses.clone())
+    };
+    assert_eq!(report.exit_code(None), 4u8.into());
+    assert_eq!(
+        report.exceeded_budgets(),
+        vec!["p95 response time of 1500ms exceeds --max-p95-ms 1000"]
+    );
+    assert_eq!(
+        report.exit_reason(None),
+        "Exiting with code 4 because the performance budget was exceeded: p95 response time of 1500ms exceeds --max-p95-ms 1000."
+    );
+    assert_eq!(report.status_json(None)["exitCode"], 4);
+    assert!(report
+        .summary_line(None)
+        .ends_with("1 budget exceeded — exit 4"));
+
+    // Within budget.
+    let report = Report {
+        max_p95_ms: Some(1500),
+        ..make_report(responses)
+    };
+    assert!(report.exceeded_budgets().is_empty());
+    assert_eq!(report.exit_code(None), 0u8.into());
+}
+
+#[test]
+fn exceeded_error_rate_budget_exits_4() {
+    let report = Report {
+        max_error_rate: Some(10.0),
+        ..make_report(vec![
+            make_response(200, 100),
+            make_response(200, 100),
+            make_response(200, 100),
+            make_response(500, 100),
+        ])
+    };
+    assert_eq!(report.exit_code(None), 4u8.into());
+    assert_eq!(
+        report.exceeded_budgets(),
+        vec!["error rate of 25.00% exceeds --max-error-rate 10"]
+    );
+}
@@ -137,6 +137,378 @@ async fn test_header_overrides_basic_auth() {
     );
 }
 
+#[tokio::test]
+async fn test_accept_header_is_sent() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .and(header("Accept", "application/vnd.api+json"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let output = run_siteprobe(
+        &format!("{}/sitemap.xml", base),
+        &["--accept", "application/vnd.api+json"],
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        json["responses"][0]["statusCode"], 200,
+        "stdout: {}",
+        stdout
+    );
+}
+
+#[tokio::test]
+async fn test_header_overrides_accept() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .and(header("Accept", "text/plain"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let output = run_siteprobe(
+        &format!("{}/sitemap.xml", base),
+        &["--json-api", "-H", "Accept: text/plain"],
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        json["responses"][0]["statusCode"], 200,
+        "stdout: {}",
+        stdout
+    );
+}
+
+#[tokio::test]
+async fn test_json_api_lists_non_json_responses() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/api/items</loc></url>
+  <url><loc>{base}/api/login</loc></url>
+</urlset>"#
+    );
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/items"))
+        .and(header("Accept", "application/json"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("[]", "application/json"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/login"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw("<html></html>", "text/html; charset=utf-8"),
+        )
+        .mount(&server)
+        .await;
+
+    let output = run_siteprobe(&format!("{}/sitemap.xml", base), &["--json-api"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let non_json = json["nonJsonResponses"].as_array().unwrap();
+    assert_eq!(non_json.len(), 1, "stdout: {}", stdout);
+    assert_eq!(non_json[0]["url"], format!("{}/api/login", base));
+    assert_eq!(non_json[0]["contentType"], "text/html; charset=utf-8");
+}
+
+#[test]
+fn test_accept_conflicts_with_json_api() {
+    use clap::Parser;
+    use siteprobe::options::Cli;
+
+    let result = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--json-api",
+        "--accept",
+        "text/html",
+    ]);
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_bearer_token_is_sent() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .and(header("Authorization", "Bearer mytoken"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let output = run_siteprobe(
+        &format!("{}/sitemap.xml", base),
+        &["--bearer-token", "mytoken"],
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["responses"][0]["statusCode"], 200);
+}
+
+#[test]
+fn test_bearer_token_conflicts_with_basic_auth() {
+    use clap::Parser;
+    use siteprobe::options::Cli;
+
+    let result = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--bearer-token",
+        "mytoken",
+        "--basic-auth",
+        "user:pass",
+    ]);
+    assert!(result.is_err());
+
+    let result = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--bearer-token",
+        " ",
+    ]);
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_dump_headers_records_request_and_response_headers() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .respond_with(ResponseTemplate::new(200).insert_header("X-Served-By", "cache-1"))
+        .mount(&server)
+        .await;
+
+    let output = run_siteprobe(
+        &format!("{}/sitemap.xml", base),
+        &[
+            "--dump-headers",
+            "--redact-header",
+            "Authorization",
+            "-H",
+            "Authorization: Bearer mytoken",
+            "-H",
+            "X-Custom: visible",
+        ],
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let response = &json["responses"][0];
+
+    assert_eq!(response["requestHeaders"]["authorization"], "[REDACTED]");
+    assert_eq!(response["requestHeaders"]["x-custom"], "visible");
+    assert_eq!(response["requestHeaders"]["user-agent"], "test-agent");
+    assert_eq!(response["responseHeaders"]["x-served-by"], "cache-1");
+}
+
+#[tokio::test]
+async fn test_headers_not_dumped_by_default() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let output = run_siteprobe(&format!("{}/sitemap.xml", base), &[]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let response = &json["responses"][0];
+
+    assert!(response.get("requestHeaders").is_none());
+    assert!(response.get("responseHeaders").is_none());
+}
+
+#[tokio::test]
+async fn test_capture_headers_lists_headers_of_interest() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Server", "nginx")
+                .insert_header("Cache-Control", "max-age=60")
+                .insert_header("Content-Type", "text/html")
+                .insert_header("X-Cache", "HIT")
+                .insert_header("X-Served-By", "cache-1"),
+        )
+        .mount(&server)
+        .await;
+
+    let sitemap_url = format!("{}/sitemap.xml", base);
+    let output = run_siteprobe(&sitemap_url, &["--capture-headers"]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let headers = &json["responses"][0]["headers"];
+
+    assert_eq!(headers["server"], "nginx");
+    assert_eq!(headers["cache-control"], "max-age=60");
+    assert_eq!(headers["content-type"], "text/html");
+    assert_eq!(headers["x-cache"], "HIT");
+    assert!(headers.get("x-served-by").is_none());
+
+    let output = run_siteprobe(&sitemap_url, &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert!(json["responses"][0].get("headers").is_none());
+}
+
+#[test]
+fn test_server_distribution() {
+    use siteprobe::report::{Report, Response};
+
+    let response = |server: Option<&str>| Response {
+        headers: server
+            .map(|s| [("server".to_string(), s.to_string())].into())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    let report = Report {
+        responses: [
+            response(Some("cloudflare")),
+            response(Some("nginx")),
+            response(None),
+            response(Some("nginx")),
+        ]
+        .into(),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        report.server_distribution(),
+        vec![("nginx".to_string(), 2), ("cloudflare".to_string(), 1)]
+    );
+}
+
+#[test]
+fn test_is_cache_hit() {
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use reqwest::StatusCode;
+    use siteprobe::network::is_cache_hit;
+
+    let headers = |pairs: &[(&'static str, &'static str)]| {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_static(value));
+        }
+        map
+    };
+
+    assert!(is_cache_hit(StatusCode::NOT_MODIFIED, &headers(&[])));
+    assert!(is_cache_hit(
+        StatusCode::OK,
+        &headers(&[("x-cache", "Hit from cloudfront")])
+    ));
+    assert!(is_cache_hit(
+        StatusCode::OK,
+        &headers(&[("cf-cache-status", "HIT")])
+    ));
+    assert!(is_cache_hit(StatusCode::OK, &headers(&[("age", "42")])));
+
+    assert!(!is_cache_hit(StatusCode::OK, &headers(&[])));
+    assert!(!is_cache_hit(
+        StatusCode::OK,
+        &headers(&[("x-cache", "MISS")])
+    ));
+    assert!(!is_cache_hit(StatusCode::OK, &headers(&[("age", "0")])));
+}
+
+#[test]
+fn test_parse_retry_after() {
+    use siteprobe::network::parse_retry_after;
+    use std::time::Duration;
+
+    assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+    assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+        Some(Duration::ZERO)
+    );
+    let future = parse_retry_after("Fri, 31 Dec 9999 23:59:59 GMT").unwrap();
+    assert!(future > Duration::from_secs(3600));
+    assert_eq!(parse_retry_after("soon"), None);
+    assert_eq!(parse_retry_after("-5"), None);
+}
+
 #[test]
 fn test_invalid_header_format_rejected() {
     let output = Command::new("cargo")
@@ -213,3 +585,74 @@ mod validate_header_tests {
         assert!(validate_header(": value").is_err());
     }
 }
+
+#[tokio::test]
+async fn test_cache_bust_param_name() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let output = run_siteprobe(
+        &format!("{}/sitemap.xml", base),
+        &["--append-timestamp", "--cache-bust-param", "cb"],
+    );
+    assert!(output.status.success());
+
+    let requests = server.received_requests().await.unwrap();
+    let page = requests
+        .iter()
+        .find(|r| r.url.path() == "/page1")
+        .expect("page1 was requested");
+    let params: Vec<String> = page
+        .url
+        .query_pairs()
+        .map(|(k, _)| k.into_owned())
+        .collect();
+    assert_eq!(params, vec!["cb"], "url: {}", page.url);
+}
+
+#[tokio::test]
+async fn test_cache_bust_header() {
+    let server = setup_mock_server().await;
+    let base = server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page1"))
+        .and(header("Cache-Control", "no-cache"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let output = run_siteprobe(
+        &format!("{}/sitemap.xml", base),
+        &["--append-timestamp", "--cache-bust-header"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+
+    let requests = server.received_requests().await.unwrap();
+    let page = requests
+        .iter()
+        .find(|r| r.url.path() == "/page1")
+        .expect("page1 was requested");
+    assert_eq!(page.url.query(), None, "The URL is left unchanged");
+}
@@ -154,6 +154,14 @@ async fn test_html_report_contains_key_sections() {
         content.contains("Success Rate"),
         "Should contain Success Rate summary card"
     );
+    assert!(
+        content.contains(r#"<div class="label">Avg Response Time</div>"#),
+        "Should contain Avg Response Time summary card"
+    );
+    assert!(
+        content.contains(r#"<div class="label">p95 Response Time</div>"#),
+        "Should contain p95 Response Time summary card"
+    );
 
     // Response Time Statistics section
     assert!(
@@ -177,6 +185,12 @@ async fn test_html_report_contains_key_sections() {
         "Should contain inline SVG charts"
     );
 
+    // Throughput over time chart (SVG)
+    assert!(
+        content.contains("Throughput Over Time"),
+        "Should contain Throughput Over Time chart heading"
+    );
+
     // Status Code Breakdown chart (SVG)
     assert!(
         content.contains("Status Code Breakdown"),
@@ -237,6 +251,57 @@ async fn test_html_report_contains_url_data() {
     );
 }
 
+#[tokio::test]
+async fn test_html_report_status_chart_tooltips() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/a</loc></url>
+  <url><loc>{base}/b</loc></url>
+  <url><loc>{base}/c</loc></url>
+  <url><loc>{base}/missing</loc></url>
+</urlset>"#
+    );
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+    for page in ["/a", "/b", "/c"] {
+        Mock::given(method("GET"))
+            .and(path(page))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let sitemap_url = format!("{}/sitemap.xml", base);
+    let tmp = temp_dir("status_chart");
+    let html_path = tmp.path().join("report.html");
+
+    let args = build_cli_args(&sitemap_url, html_path.to_str().unwrap());
+    Command::new("cargo")
+        .args(&args)
+        .output()
+        .expect("Failed to execute siteprobe");
+
+    let content = fs::read_to_string(&html_path).unwrap();
+
+    // Each bar carries the data shown in the hover tooltip.
+    assert!(
+        content.contains(r#"data-code="200" data-reason="OK" data-count="3" data-pct="75.0""#),
+        "Status bars should carry count and percentage"
+    );
+    assert!(content.contains(r#"id="chart-tooltip""#));
+
+    // Bars are grouped by status family, colored like the status classes.
+    assert!(content.contains(r#"class="bar status-ok""#));
+    assert!(content.contains(r#"class="bar status-error""#));
+    assert!(content.contains(">2xx</text>") && content.contains(">4xx</text>"));
+}
+
 #[tokio::test]
 async fn test_html_report_tilde_expansion() {
     let (_server, sitemap_url) = setup_mock_server().await;
@@ -253,6 +253,10 @@ async fn test_json_output_structure_fields() {
         config["bypassCaching"].is_boolean(),
         "config.bypassCaching should be a boolean"
     );
+    assert_eq!(
+        config["discovery"],
+        serde_json::json!({"urls": 5, "hosts": 1, "http": 5, "https": 0})
+    );
 
     // Verify statistics sub-objects exist
     let stats = &json["statistics"];
@@ -285,3 +289,111 @@ async fn test_json_output_structure_fields() {
         assert!(size.is_some(), "responseSize should be a number");
     }
 }
+
+#[tokio::test]
+async fn test_json_responses_only_outputs_top_level_array() {
+    let mock_server = setup_mock_server().await;
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+
+    let temp_dir = temp_dir("responses_only");
+    let json_report_path = temp_dir.path().join("report.json");
+
+    let mut args = build_json_cli_args(&sitemap_url);
+    args.push("--json-responses-only".to_string());
+    args.push("--report-path-json".to_string());
+    args.push(json_report_path.to_str().unwrap().to_string());
+
+    let output = Command::new("cargo")
+        .args(&args)
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stdout_json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let responses = stdout_json
+        .as_array()
+        .expect("stdout should be a JSON array");
+    assert!(!responses.is_empty());
+    assert!(responses[0]["statusCode"].is_u64());
+
+    let file_content = fs::read_to_string(&json_report_path).expect("Failed to read JSON report");
+    let file_json: serde_json::Value = serde_json::from_str(&file_content).expect("valid JSON");
+    assert!(file_json.is_array(), "file JSON should be an array");
+}
+
+#[tokio::test]
+async fn test_hash_bodies_adds_hash_to_json_and_csv() {
+    let mock_server = setup_mock_server().await;
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+
+    let temp_dir = temp_dir("hash_bodies");
+    let csv_report_path = temp_dir.path().join("report.csv");
+
+    let mut args = build_json_cli_args(&sitemap_url);
+    args.push("--hash-bodies".to_string());
+    args.push("--report-path".to_string());
+    args.push(csv_report_path.to_str().unwrap().to_string());
+
+    let output = Command::new("cargo")
+        .args(&args)
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    assert!(output.status.success());
+
+    // SHA-256 of the home page body served by the mock server.
+    let home_hash = "386e234792e1e5298dac286981b51f864d533a83bd5b7fdd03d9b5c8057eeee0";
+
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&output.stdout)).expect("valid JSON");
+    let home = json["responses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["url"].as_str().unwrap().contains("/?ts="))
+        .expect("home page response");
+    assert_eq!(home["bodyHash"], home_hash);
+
+    let csv = fs::read_to_string(&csv_report_path).expect("Failed to read CSV report");
+    let mut lines = csv.lines();
+    assert!(lines.next().unwrap().ends_with(",Body Hash"));
+    assert!(csv.contains(home_hash));
+}
+
+#[tokio::test]
+async fn test_jsonl_report_appends_responses_and_summary() {
+    let mock_server = setup_mock_server().await;
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+
+    let temp_dir = temp_dir("jsonl");
+    let jsonl_path = temp_dir.path().join("nested").join("report.jsonl");
+
+    let mut args = build_json_cli_args(&sitemap_url);
+    args.push("--report-path-jsonl".to_string());
+    args.push(jsonl_path.to_str().unwrap().to_string());
+
+    for _ in 0..2 {
+        let output = Command::new("cargo")
+            .args(&args)
+            .output()
+            .expect("Failed to execute siteprobe binary");
+        assert!(output.status.success());
+    }
+
+    let content = fs::read_to_string(&jsonl_path).expect("Failed to read JSON Lines report");
+    let lines: Vec<serde_json::Value> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is valid JSON"))
+        .collect();
+
+    // Two runs of 5 responses and a summary line each.
+    assert_eq!(lines.len(), 12);
+    assert_eq!(lines[0]["statusCode"], 200);
+    assert!(lines[0]["url"].is_string());
+    let summary = &lines[5]["summary"];
+    assert_eq!(summary["exitCode"], 0);
+    assert_eq!(summary["total"], 5);
+    assert_eq!(summary["sitemapUrl"], sitemap_url);
+    assert!(lines[11]["summary"].is_object());
+}
@@ -0,0 +1,112 @@
+use siteprobe::options::parse_login_field;
+use std::process::Command;
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SITEMAP_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{BASE}/private</loc></url>
+</urlset>"#;
+
+#[test]
+fn test_parse_login_field() {
+    let field = parse_login_field("password=a=b").unwrap();
+    assert_eq!(field.name, "password");
+    assert_eq!(field.value, "a=b");
+
+    assert!(parse_login_field("username").is_err());
+    assert!(parse_login_field("=value").is_err());
+}
+
+#[tokio::test]
+async fn test_login_session_cookie_is_sent() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .and(body_string_contains("username=alice"))
+        .and(body_string_contains("password=secret"))
+        .respond_with(
+            ResponseTemplate::new(302)
+                .append_header("Location", "/dashboard")
+                .append_header("Set-Cookie", "session=abc123; Path=/"),
+        )
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .and(header("cookie", "session=abc123"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(SITEMAP_XML.replace("{BASE}", &base)),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/private"))
+        .and(header("cookie", "session=abc123"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{}/sitemap.xml", base);
+    let login_url = format!("{}/login", base);
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--json",
+            "--login-url",
+            &login_url,
+            "--login-data",
+            "username=alice",
+            "--login-data",
+            "password=secret",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["responses"][0]["statusCode"], 200);
+}
+
+#[tokio::test]
+async fn test_login_failure_is_reported() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&mock_server)
+        .await;
+
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+    let login_url = format!("{}/login", mock_server.uri());
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &sitemap_url,
+            "--login-url",
+            &login_url,
+            "--login-data",
+            "username=alice",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Login failed"), "stderr: {}", stderr);
+    assert!(stderr.contains("401"), "stderr: {}", stderr);
+}
@@ -1,4 +1,9 @@
-use siteprobe::options::parse_rate_limit;
+use clap::Parser;
+use siteprobe::network::{build_client, build_default_headers};
+use siteprobe::options::{
+    parse_percent, parse_rate_limit, parse_resolve_override, parse_sitemap_source,
+    parse_timeout_override, Cli, EmptyBodyMode, ReportFormat, ReportSection, SitemapSource,
+};
 use std::process::Command;
 
 #[test]
@@ -184,3 +189,893 @@ fn test_slow_threshold_negative_via_cli() {
         stderr
     );
 }
+
+#[test]
+fn test_default_matches_cli_defaults() {
+    let parsed = Cli::try_parse_from(["siteprobe", "http://example.com/sitemap.xml"]).unwrap();
+    let default = Cli::default();
+    assert!(default.sitemap_urls.is_empty());
+    assert_eq!(default.concurrency_limit, parsed.concurrency_limit);
+    assert_eq!(default.sitemap_concurrency, parsed.sitemap_concurrency);
+    assert_eq!(default.request_timeout, parsed.request_timeout);
+    assert_eq!(default.user_agent, parsed.user_agent);
+    assert_eq!(default.slow_num, parsed.slow_num);
+    assert_eq!(default.retries, parsed.retries);
+    assert_eq!(default.throughput_interval, parsed.throughput_interval);
+    assert_eq!(default.repeat, parsed.repeat);
+    assert_eq!(default.warmup, parsed.warmup);
+    assert_eq!(default.follow_redirects, parsed.follow_redirects);
+    assert_eq!(default.json, parsed.json);
+}
+
+#[test]
+fn test_pool_options_parse() {
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--pool-max-idle-per-host",
+        "8",
+        "--pool-idle-timeout",
+        "30",
+    ])
+    .unwrap();
+    assert_eq!(cli.pool_max_idle_per_host, Some(8));
+    assert_eq!(cli.pool_idle_timeout, Some(30));
+
+    let cli = Cli::try_parse_from(["siteprobe", "http://example.com/sitemap.xml"]).unwrap();
+    assert_eq!(cli.pool_max_idle_per_host, None);
+    assert_eq!(cli.pool_idle_timeout, None);
+}
+
+#[test]
+fn test_no_keepalive_sets_connection_close() {
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--no-keepalive",
+    ])
+    .unwrap();
+    assert!(cli.no_keepalive);
+    let headers = build_default_headers(&cli).unwrap();
+    assert_eq!(headers.get("connection").unwrap(), "close");
+
+    let cli = Cli::try_parse_from(["siteprobe", "http://example.com/sitemap.xml"]).unwrap();
+    assert!(!cli.no_keepalive);
+    assert!(build_default_headers(&cli)
+        .unwrap()
+        .get("connection")
+        .is_none());
+}
+
+#[test]
+fn test_seed_requires_shuffle() {
+    let result =
+        Cli::try_parse_from(["siteprobe", "http://example.com/sitemap.xml", "--seed", "7"]);
+    assert!(result.is_err());
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--shuffle",
+        "--seed",
+        "7",
+    ])
+    .unwrap();
+    assert!(cli.shuffle);
+    assert_eq!(cli.seed, Some(7));
+}
+
+#[test]
+fn test_flag_empty_body_parse() {
+    let parse = |args: &[&str]| {
+        let mut all = vec!["siteprobe", "http://example.com/sitemap.xml"];
+        all.extend_from_slice(args);
+        Cli::try_parse_from(all).unwrap().flag_empty_body
+    };
+    assert_eq!(parse(&[]), None);
+    assert_eq!(parse(&["--flag-empty-body"]), Some(EmptyBodyMode::Warn));
+    assert_eq!(
+        parse(&["--flag-empty-body", "error"]),
+        Some(EmptyBodyMode::Error)
+    );
+    assert_eq!(
+        parse(&["--flag-empty-body=warn"]),
+        Some(EmptyBodyMode::Warn)
+    );
+}
+
+#[test]
+fn test_sections_parse() {
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--sections",
+        "summary,errors",
+    ])
+    .unwrap();
+    assert_eq!(
+        cli.sections,
+        vec![ReportSection::Summary, ReportSection::Errors]
+    );
+
+    let result = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--sections",
+        "summary,nope",
+    ]);
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_request_timeout_zero_disables_timeout() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--request-timeout",
+        "0",
+    ])
+    .unwrap();
+    assert_eq!(cli.request_timeout, 0);
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(1200)))
+        .mount(&mock_server)
+        .await;
+
+    // A 1 second timeout is hit, no timeout is not.
+    let url = format!("{}/slow", mock_server.uri());
+    let one_second = Cli {
+        request_timeout: 1,
+        ..Cli::default()
+    };
+    let client = build_client(&one_second).unwrap();
+    assert!(client.get(&url).send().await.unwrap_err().is_timeout());
+
+    let client = build_client(&cli).unwrap();
+    assert_eq!(client.get(&url).send().await.unwrap().status(), 200);
+}
+
+#[test]
+fn test_url_count_tolerance_requires_expected_count() {
+    let result = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--url-count-tolerance",
+        "10",
+    ]);
+    assert!(result.is_err());
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--expect-url-count",
+        "250",
+    ])
+    .unwrap();
+    assert_eq!(cli.expect_url_count, Some(250));
+    assert_eq!(cli.url_count_tolerance, 0.0);
+}
+
+#[test]
+fn test_parse_resolve_override() {
+    let entry = parse_resolve_override("Example.com:443:203.0.113.7").unwrap();
+    assert_eq!(entry.host, "example.com");
+    assert_eq!(entry.port, 443);
+    assert_eq!(entry.ip.to_string(), "203.0.113.7");
+
+    let entry = parse_resolve_override("example.com:443:[2001:db8::1]").unwrap();
+    assert_eq!(entry.ip.to_string(), "2001:db8::1");
+    let entry = parse_resolve_override("example.com:80:::1").unwrap();
+    assert_eq!(entry.ip.to_string(), "::1");
+
+    assert!(parse_resolve_override("example.com:443").is_err());
+    assert!(parse_resolve_override(":443:127.0.0.1").is_err());
+    assert!(parse_resolve_override("example.com:https:127.0.0.1").is_err());
+    assert!(parse_resolve_override("example.com:443:localhost").is_err());
+}
+
+#[tokio::test]
+async fn test_resolve_override_sends_original_host() {
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let port = mock_server.address().port();
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(header(
+            "host",
+            format!("www.siteprobe.invalid:{}", port).as_str(),
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--resolve",
+        &format!("www.siteprobe.invalid:{}:127.0.0.1", port),
+    ])
+    .unwrap();
+    let client = build_client(&cli).unwrap();
+    let response = client
+        .get(format!("http://www.siteprobe.invalid:{}/page", port))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[test]
+fn test_insecure() {
+    let parse =
+        |flag: &str| Cli::try_parse_from(["siteprobe", "http://example.com/sitemap.xml", flag]);
+    assert!(!Cli::default().insecure);
+    for flag in ["--insecure", "--danger-accept-invalid-certs"] {
+        let cli = parse(flag).unwrap();
+        assert!(cli.insecure);
+        assert!(build_client(&cli).is_ok());
+    }
+}
+
+#[test]
+fn test_local_address() {
+    let parse = |address: &str| {
+        Cli::try_parse_from([
+            "siteprobe",
+            "http://example.com/sitemap.xml",
+            "--local-address",
+            address,
+        ])
+    };
+    assert!(parse("not-an-ip").is_err());
+    assert!(build_client(&parse("127.0.0.1").unwrap()).is_ok());
+
+    // 203.0.113.0/24 is reserved for documentation and not assigned to this host.
+    let err = build_client(&parse("203.0.113.9").unwrap()).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Unable to use local address 203.0.113.9"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_pool_idle_timeout_out_of_range() {
+    let result = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--pool-idle-timeout",
+        "0",
+    ]);
+    assert!(result.is_err());
+
+    let result = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--pool-max-idle-per-host",
+        "5000",
+    ]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_parse_timeout_override() {
+    let result = parse_timeout_override("/export/=60").unwrap();
+    assert!(result
+        .pattern
+        .is_match("https://example.com/export/all.csv"));
+    assert_eq!(result.seconds, 60);
+
+    // Split at the last '=' so patterns may contain '='
+    let result = parse_timeout_override(r"\?format=csv=30").unwrap();
+    assert!(result
+        .pattern
+        .is_match("https://example.com/report?format=csv"));
+    assert_eq!(result.seconds, 30);
+}
+
+#[test]
+fn test_parse_timeout_override_invalid() {
+    assert!(parse_timeout_override("/export/").is_err());
+    assert!(parse_timeout_override("/export/=abc").is_err());
+    assert!(parse_timeout_override("/export/=0").is_err());
+    assert!(parse_timeout_override("([=10").is_err());
+}
+
+#[tokio::test]
+async fn test_same_host_redirects_only() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    let port = mock_server.address().port();
+    Mock::given(method("GET"))
+        .and(path("/old"))
+        .respond_with(ResponseTemplate::new(301).insert_header("location", "/new"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/new"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    // `localhost` is another host than `127.0.0.1`, though it reaches the same server.
+    let sso = format!("http://localhost:{}/login", port);
+    Mock::given(method("GET"))
+        .and(path("/private"))
+        .respond_with(ResponseTemplate::new(302).insert_header("location", sso.as_str()))
+        .mount(&mock_server)
+        .await;
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--same-host-redirects-only",
+    ])
+    .unwrap();
+    let client = build_client(&cli).unwrap();
+
+    let followed = get_url_response(
+        &format!("{base}/old"),
+        &client,
+        false,
+        None,
+        &None,
+        false,
+        false,
+        &ContentChecks::default(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(followed.status_code, 200);
+    assert_eq!(followed.url, format!("{base}/new"));
+    assert!(followed.redirected);
+
+    let stopped = get_url_response(
+        &format!("{base}/private"),
+        &client,
+        false,
+        None,
+        &None,
+        false,
+        false,
+        &ContentChecks::default(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(stopped.status_code, 302);
+    assert_eq!(stopped.url, format!("{base}/private"));
+    assert_eq!(stopped.redirect_target, Some(sso));
+}
+
+#[test]
+fn test_url_truncate() {
+    let parse = |cols: &str| {
+        Cli::try_parse_from([
+            "siteprobe",
+            "http://example.com/sitemap.xml",
+            "--url-truncate",
+            cols,
+        ])
+        .map(|cli| cli.url_truncate)
+    };
+    assert_eq!(parse("0").unwrap(), Some(0));
+    assert_eq!(parse("60").unwrap(), Some(60));
+    assert!(parse("5").is_err());
+    assert!(parse("wide").is_err());
+}
+
+#[test]
+fn test_report_dir_formats() {
+    use std::path::PathBuf;
+
+    let mut cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--report-dir",
+        "/tmp/reports",
+    ])
+    .unwrap();
+    cli.apply_report_dir();
+    assert_eq!(
+        cli.report_path,
+        Some(PathBuf::from("/tmp/reports/report.csv"))
+    );
+    assert_eq!(
+        cli.report_path_json,
+        Some(PathBuf::from("/tmp/reports/report.json"))
+    );
+    assert_eq!(
+        cli.report_path_html,
+        Some(PathBuf::from("/tmp/reports/report.html"))
+    );
+    assert_eq!(cli.report_path_jsonl, None);
+
+    // An explicit per-format path wins over the directory.
+    let mut cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--report-dir",
+        "/tmp/reports",
+        "--report-formats",
+        "json,jsonl",
+        "--report-path-json",
+        "/tmp/custom.json",
+    ])
+    .unwrap();
+    assert_eq!(
+        cli.report_formats,
+        vec![ReportFormat::Json, ReportFormat::Jsonl]
+    );
+    cli.apply_report_dir();
+    assert_eq!(cli.report_path, None);
+    assert_eq!(
+        cli.report_path_json,
+        Some(PathBuf::from("/tmp/custom.json"))
+    );
+    assert_eq!(
+        cli.report_path_jsonl,
+        Some(PathBuf::from("/tmp/reports/report.jsonl"))
+    );
+    assert_eq!(cli.report_path_html, None);
+
+    // --report-formats needs a directory to write to.
+    assert!(Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--report-formats",
+        "csv",
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_modified_since() {
+    let parse = |value: &str| {
+        Cli::try_parse_from([
+            "siteprobe",
+            "http://example.com/sitemap.xml",
+            "--modified-since",
+            value,
+        ])
+        .map(|cli| cli.modified_since.map(|d| d.to_rfc3339()))
+    };
+    assert_eq!(
+        parse("2024-01-01").unwrap(),
+        Some("2024-01-01T00:00:00+00:00".to_string())
+    );
+    assert_eq!(
+        parse("2024-01-01T12:00:00+02:00").unwrap(),
+        Some("2024-01-01T12:00:00+02:00".to_string())
+    );
+    assert!(parse("01/01/2024").is_err());
+}
+
+#[test]
+fn test_include_exclude_patterns() {
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--include",
+        "/blog/",
+        "--include",
+        "/news/",
+        "--exclude",
+        "/drafts/",
+    ])
+    .unwrap();
+    assert_eq!(cli.include_patterns.len(), 2);
+    assert!(cli.exclude_patterns[0].is_match("https://example.com/blog/drafts/x"));
+
+    let err = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--include",
+        "/blog/(",
+    ])
+    .unwrap_err();
+    assert!(err.to_string().contains("Invalid pattern '/blog/('"));
+}
+
+#[tokio::test]
+async fn test_head_requests_take_size_from_content_length() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("HEAD"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(1234)))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let cli =
+        Cli::try_parse_from(["siteprobe", "http://example.com/sitemap.xml", "--head"]).unwrap();
+    assert!(cli.head);
+    let client = build_client(&cli).unwrap();
+
+    let response = get_url_response(
+        &format!("{}/page", mock_server.uri()),
+        &client,
+        true,
+        None,
+        &None,
+        false,
+        false,
+        &ContentChecks::default(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.response_size, 1234);
+
+    // There is no body to store or hash.
+    for flag in ["--output-dir=/tmp/out", "--hash-bodies"] {
+        assert!(Cli::try_parse_from([
+            "siteprobe",
+            "http://example.com/sitemap.xml",
+            "--head",
+            flag
+        ])
+        .is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_max_body_size_truncates_large_bodies() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/large"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(1_000_000)))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/small"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(100)))
+        .mount(&mock_server)
+        .await;
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--max-body-size",
+        "1024",
+    ])
+    .unwrap();
+    assert_eq!(cli.max_body_size, Some(1024));
+    let client = build_client(&cli).unwrap();
+
+    let fetch = |page: &'static str| {
+        let url = format!("{}/{page}", mock_server.uri());
+        let client = client.clone();
+        async move {
+            get_url_response(
+                &url,
+                &client,
+                false,
+                Some(1024),
+                &None,
+                false,
+                false,
+                &ContentChecks::default(),
+                None,
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    let large = fetch("large").await;
+    assert_eq!(large.response_size, 1024);
+    assert!(large.truncated);
+
+    let small = fetch("small").await;
+    assert_eq!(small.response_size, 100);
+    assert!(!small.truncated);
+
+    assert!(Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--max-body-size",
+        "0"
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_parse_sitemap_source() {
+    assert_eq!(parse_sitemap_source("-"), Ok(SitemapSource::Stdin));
+    assert_eq!(
+        parse_sitemap_source("https://example.com/sitemap.xml"),
+        Ok(SitemapSource::Url(
+            "https://example.com/sitemap.xml".parse().unwrap()
+        ))
+    );
+
+    // A local file becomes an absolute file:// URL.
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    let canonical = std::fs::canonicalize(file.path()).unwrap();
+    assert_eq!(
+        parse_sitemap_source(path),
+        Ok(SitemapSource::Url(
+            url::Url::from_file_path(canonical).unwrap()
+        ))
+    );
+
+    let err = parse_sitemap_source("/nonexistent/urls.txt").unwrap_err();
+    assert!(err.contains("neither a URL"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_max_redirects() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    // /r1 -> /r2 -> /r3 -> /r4 -> /final
+    for i in 1..=4 {
+        let next = if i < 4 {
+            format!("/r{}", i + 1)
+        } else {
+            "/final".to_string()
+        };
+        Mock::given(method("GET"))
+            .and(path(format!("/r{i}")))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", next.as_str()))
+            .mount(&mock_server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/final"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let fetch = |args: &'static [&'static str]| {
+        let base = base.clone();
+        async move {
+            let cli = Cli::try_parse_from(
+                [
+                    "siteprobe",
+                    "http://example.com/sitemap.xml",
+                    "--follow-redirects",
+                ]
+                .iter()
+                .chain(args),
+            )
+            .unwrap();
+            let client = build_client(&cli).unwrap();
+            get_url_response(
+                &format!("{base}/r1"),
+                &client,
+                false,
+                None,
+                &None,
+                false,
+                false,
+                &ContentChecks::default(),
+                None,
+            )
+            .await
+        }
+    };
+
+    // A low limit stops before reaching the end of the chain, and fails at its last
+    // redirect.
+    let cut_off = fetch(&["--max-redirects", "2"]).await.unwrap();
+    assert_eq!(cut_off.status_code, 302);
+    assert_eq!(cut_off.redirect_count, 2);
+    assert!(cut_off.redirected);
+    assert!(cut_off.is_error());
+    assert!(cut_off.error.is_some());
+    let requests = mock_server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|r| r.url.path() != "/final"));
+
+    // The default limit of 10 follows the whole chain.
+    let followed = fetch(&[]).await.unwrap();
+    assert_eq!(followed.status_code, 200);
+    assert_eq!(followed.url, format!("{base}/final"));
+
+    // 0 doesn't follow redirects at all.
+    let stopped = fetch(&["--max-redirects", "0"]).await.unwrap();
+    assert_eq!(stopped.status_code, 302);
+    assert_eq!(stopped.url, format!("{base}/r1"));
+}
+
+#[tokio::test]
+async fn test_redirect_count_and_loop() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    // A 3-hop chain: /hop1 -> /hop2 -> /hop3 -> /done
+    for (from, to) in [
+        ("/hop1", "/hop2"),
+        ("/hop2", "/hop3"),
+        ("/hop3", "/done"),
+        ("/loop-a", "/loop-b"),
+        ("/loop-b", "/loop-a"),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(from))
+            .respond_with(ResponseTemplate::new(301).insert_header("location", to))
+            .mount(&mock_server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/done"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--follow-redirects",
+    ])
+    .unwrap();
+    let client = build_client(&cli).unwrap();
+    let fetch = |url: String| {
+        let client = client.clone();
+        async move {
+            get_url_response(
+                &url,
+                &client,
+                false,
+                None,
+                &None,
+                false,
+                false,
+                &ContentChecks::default(),
+                None,
+            )
+            .await
+            .unwrap()
+        }
+    };
+
+    let chain = fetch(format!("{base}/hop1")).await;
+    assert_eq!(chain.status_code, 200);
+    assert_eq!(chain.redirect_count, 3);
+    assert!(!chain.redirect_loop);
+
+    let looped = fetch(format!("{base}/loop-a")).await;
+    assert_eq!(looped.status_code, 301);
+    assert_eq!(looped.redirect_count, 1);
+    assert!(looped.redirect_loop);
+    assert_eq!(looped.redirect_target, Some(format!("{base}/loop-a")));
+
+    let direct = fetch(format!("{base}/done")).await;
+    assert_eq!(direct.redirect_count, 0);
+}
+
+#[test]
+fn test_expect_matches_content_checks() {
+    use siteprobe::network::ContentChecks;
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--expect-matches",
+        "<title>.+</title>",
+        "--expect-contains",
+        "<footer>",
+    ])
+    .unwrap();
+    let checks = ContentChecks {
+        contains: cli.expect_contains,
+        matches: cli.expect_matches,
+        ..Default::default()
+    };
+
+    let (missing, unmatched) =
+        checks.check(b"<title>Home</title><main>Hi</main><footer>ACME</footer>");
+    assert!(missing.is_empty());
+    assert!(unmatched.is_empty());
+
+    // An empty title means the template failed to render it.
+    let (missing, unmatched) = checks.check(b"<title></title><main>Hi</main>");
+    assert_eq!(missing, vec!["<footer>"]);
+    assert_eq!(unmatched, vec!["<title>.+</title>"]);
+
+    let err = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--expect-matches",
+        "<title>(",
+    ])
+    .unwrap_err();
+    assert!(err.to_string().contains("Invalid pattern"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_soft_404_marker() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    let not_found_page = "<h1>Page not found</h1>";
+    Mock::given(method("GET"))
+        .and(path("/soft"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(not_found_page))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/hard"))
+        .respond_with(ResponseTemplate::new(404).set_body_string(not_found_page))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/found"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<h1>Welcome</h1>"))
+        .mount(&mock_server)
+        .await;
+
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "http://example.com/sitemap.xml",
+        "--soft-404-marker",
+        "Page not found",
+    ])
+    .unwrap();
+    let client = build_client(&cli).unwrap();
+    let checks = ContentChecks {
+        soft_404_markers: cli.soft_404_markers.clone(),
+        ..Default::default()
+    };
+    // A real 404 is already an error and not a soft one.
+    for (page, soft_404) in [("/soft", true), ("/hard", false), ("/found", false)] {
+        let response = get_url_response(
+            &format!("{base}{page}"),
+            &client,
+            false,
+            None,
+            &None,
+            false,
+            false,
+            &checks,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.soft_404, soft_404, "{page}");
+    }
+}
+
+#[test]
+fn test_parse_percent() {
+    assert_eq!(parse_percent("99"), Ok(99.0));
+    assert_eq!(parse_percent("99.5"), Ok(99.5));
+    assert_eq!(parse_percent("0"), Ok(0.0));
+    assert_eq!(parse_percent("100"), Ok(100.0));
+    assert!(parse_percent("100.1").is_err());
+    assert!(parse_percent("-1").is_err());
+    assert!(parse_percent("abc").is_err());
+}
@@ -0,0 +1,97 @@
+use std::process::Command;
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Run siteprobe via `cargo run` with --json output and return the process Output
+/// along with how long it took.
+fn run_siteprobe(sitemap_url: &str, extra_args: &[&str]) -> (std::process::Output, Duration) {
+    let start = Instant::now();
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", sitemap_url, "--json"])
+        .args(extra_args)
+        .output()
+        .expect("Failed to execute siteprobe binary");
+    (output, start.elapsed())
+}
+
+#[tokio::test]
+async fn test_rate_limit_spaces_out_requests() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let urls: String = (1..=4)
+        .map(|i| format!("  <url><loc>{base}/page-{i}</loc></url>\n"))
+        .collect();
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+{urls}</urlset>"#
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // 120 requests per minute allow one request every 500ms. Despite the
+    // concurrency limit of 4, the 4 requests must take at least 1.5s.
+    let (output, elapsed) = run_siteprobe(
+        &format!("{base}/sitemap.xml"),
+        &["--rate-limit", "120/1m", "--concurrency-limit", "4"],
+    );
+    assert!(output.status.success());
+    assert!(
+        elapsed >= Duration::from_millis(1500),
+        "4 requests at 120/1m took only {elapsed:?}"
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["responses"].as_array().unwrap().len(), 4);
+    assert_eq!(json["config"]["rateLimit"], 120);
+}
+
+#[tokio::test]
+async fn test_rate_limit_applies_to_retries() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{base}/page</loc></url>
+</urlset>"#
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    // 30 requests per minute allow one request every 2s, which is longer than
+    // the 1s pause between retries.
+    let (output, elapsed) = run_siteprobe(
+        &format!("{base}/sitemap.xml"),
+        &["--rate-limit", "30/1m", "--retries", "1"],
+    );
+    assert!(!output.stdout.is_empty());
+    assert!(
+        elapsed >= Duration::from_secs(2),
+        "a request and its retry at 30/1m took only {elapsed:?}"
+    );
+}
@@ -0,0 +1,222 @@
+use reqwest::StatusCode;
+use siteprobe::report::{Report, Response};
+use std::collections::VecDeque;
+use std::process::Command;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn make_response(source_url: &str, url: &str, response_time_ms: u64) -> Response {
+    Response {
+        url: url.to_string(),
+        source_url: source_url.to_string(),
+        response_time: Duration::from_millis(response_time_ms),
+        status_code: StatusCode::OK,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_url_aggregates_groups_by_source_url() {
+    let report = Report {
+        responses: VecDeque::from(vec![
+            make_response("https://example.com/a", "https://example.com/a?ts=1", 100),
+            make_response("https://example.com/b", "https://example.com/b?ts=2", 50),
+            make_response("https://example.com/a", "https://example.com/a?ts=3", 300),
+            make_response("https://example.com/a", "https://example.com/a?ts=4", 200),
+        ]),
+        ..Default::default()
+    };
+
+    let aggregates = report.url_aggregates();
+    assert_eq!(aggregates.len(), 2);
+
+    let a = &aggregates[0];
+    assert_eq!(a.url, "https://example.com/a");
+    assert_eq!(a.samples, 3);
+    assert_eq!(a.avg_response_time, Duration::from_millis(200));
+    assert_eq!(a.min_response_time, Duration::from_millis(100));
+    assert_eq!(a.max_response_time, Duration::from_millis(300));
+
+    let b = &aggregates[1];
+    assert_eq!(b.url, "https://example.com/b");
+    assert_eq!(b.samples, 1);
+}
+
+#[test]
+fn test_url_aggregates_falls_back_to_url() {
+    let report = Report {
+        responses: VecDeque::from(vec![
+            make_response("", "https://example.com/a", 100),
+            make_response("", "https://example.com/a", 300),
+        ]),
+        ..Default::default()
+    };
+
+    let aggregates = report.url_aggregates();
+    assert_eq!(aggregates.len(), 1);
+    assert_eq!(aggregates[0].samples, 2);
+}
+
+#[tokio::test]
+async fn test_repeat_probes_each_url_n_times() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{}/page</loc></url>
+</urlset>"#,
+        base
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", base),
+            "--json",
+            "--repeat",
+            "3",
+            "--append-timestamp",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+
+    assert_eq!(json["responses"].as_array().unwrap().len(), 3);
+    assert_eq!(json["config"]["repeat"], 3);
+
+    let url_stats = json["urlStatistics"].as_array().unwrap();
+    assert_eq!(url_stats.len(), 1);
+    assert_eq!(url_stats[0]["url"], format!("{}/page", base));
+    assert_eq!(url_stats[0]["samples"], 3);
+}
+
+#[tokio::test]
+async fn test_warmup_requests_are_excluded_from_report() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{}/page</loc></url>
+</urlset>"#,
+        base
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    // 2 warm-up requests + 1 measured request
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", base),
+            "--json",
+            "--warmup",
+            "2",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+
+    assert_eq!(json["responses"].as_array().unwrap().len(), 1);
+    assert_eq!(json["config"]["warmupRequests"], 2);
+    assert_eq!(json["statistics"]["performance"]["totalRequests"], 1);
+}
+
+#[tokio::test]
+async fn test_warmup_requests_are_cache_busted() {
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let sitemap_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>{}/page</loc></url>
+</urlset>"#,
+        base
+    );
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", base),
+            "--warmup",
+            "2",
+            "--append-timestamp",
+            "--cache-bust-param",
+            "cb",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    assert!(output.status.success());
+
+    // The warm-up requests carry the cache buster too, like the measured request.
+    let requests = mock_server.received_requests().await.unwrap();
+    let pages: Vec<_> = requests
+        .iter()
+        .filter(|r| r.url.path() == "/page")
+        .collect();
+    assert_eq!(pages.len(), 3);
+    for request in pages {
+        assert!(
+            request.url.query_pairs().any(|(k, _)| k == "cb"),
+            "{}",
+            request.url
+        );
+    }
+}
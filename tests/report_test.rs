@@ -1,6 +1,9 @@
 use prettytable::{Cell, Row, Table};
 use serde_json::json;
 use siteprobe::metrics::{Entry, Metrics, CLEAN_FORMAT};
+use siteprobe::report::{
+    layout_tables, load_json_report, load_previous_failures, Report, Response,
+};
 
 #[test]
 fn test_visual_alignment() {
@@ -114,3 +117,405 @@ fn test_visual_alignment() {
     table.add_row(Row::new(vec![Cell::new(metrics.build_table().as_str())]));
     println!("\n{}", table);
 }
+
+fn sample_metrics(label: &'static str) -> Metrics {
+    Metrics(vec![Entry {
+        label,
+        value: "100ms".to_string(),
+        json_label: "sample",
+        json_value: json!(100),
+    }])
+}
+
+#[test]
+fn test_layout_tables_side_by_side_when_wide() {
+    let left = sample_metrics("⏰ Average Response Time").build_table();
+    let right = sample_metrics("✅ Success Rate").build_table();
+    let output = layout_tables(&left, &right, 200);
+
+    // Both tables share the same lines
+    let first_line = output.lines().next().unwrap();
+    assert_eq!(first_line.matches('┌').count(), 2);
+}
+
+#[test]
+fn test_layout_tables_stacked_when_narrow() {
+    let left = sample_metrics("⏰ Average Response Time").build_table();
+    let right = sample_metrics("✅ Success Rate").build_table();
+    let output = layout_tables(&left, &right, 40);
+
+    assert!(output.lines().all(|line| line.matches('┌').count() <= 1));
+    assert_eq!(output.matches('┌').count(), 2);
+}
+
+#[test]
+fn test_load_previous_failures_full_report() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let report = json!({
+        "config": {},
+        "responses": [
+            {"url": "https://example.com/", "statusCode": 200},
+            {"url": "https://example.com/flaky?ts=1234567890", "statusCode": 200},
+            {"url": "https://example.com/flaky?ts=9876543210", "statusCode": 502},
+        ],
+    });
+    std::fs::write(file.path(), report.to_string()).unwrap();
+
+    let failures = load_previous_failures(file.path(), "ts").unwrap();
+    assert_eq!(failures.len(), 2);
+    assert!(!failures["https://example.com/"]);
+    assert!(failures["https://example.com/flaky"]);
+}
+
+#[test]
+fn test_load_previous_failures_responses_only() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let responses = json!([{"url": "https://example.com/a", "statusCode": 404}]);
+    std::fs::write(file.path(), responses.to_string()).unwrap();
+
+    let failures = load_previous_failures(file.path(), "ts").unwrap();
+    assert!(failures["https://example.com/a"]);
+}
+
+#[test]
+fn test_load_previous_failures_keys_on_requested_url() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let responses = json!([
+        {
+            "url": "https://example.com/new",
+            "requestedUrl": "https://example.com/old?ts=1234567890",
+            "statusCode": 200,
+        },
+        {"url": "https://example.com/soft", "statusCode": 200, "soft404": true},
+        {
+            "url": "https://example.com/marker",
+            "statusCode": 200,
+            "missingMarkers": ["Welcome"],
+        },
+    ]);
+    std::fs::write(file.path(), responses.to_string()).unwrap();
+
+    let failures = load_previous_failures(file.path(), "ts").unwrap();
+    // A followed redirect is keyed by the sitemap URL, not where it redirected to.
+    assert_eq!(failures.get("https://example.com/old"), Some(&false));
+    assert!(!failures.contains_key("https://example.com/new"));
+    // A soft 404 or a content mismatch is a failure despite the 200.
+    assert!(failures["https://example.com/soft"]);
+    assert!(failures["https://example.com/marker"]);
+}
+
+#[test]
+fn test_load_previous_failures_invalid() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "{\"config\": {}}").unwrap();
+    assert!(load_previous_failures(file.path(), "ts")
+        .unwrap_err()
+        .contains("no responses found"));
+
+    let missing = std::path::Path::new("/nonexistent/siteprobe/report.json");
+    assert!(load_previous_failures(missing, "ts")
+        .unwrap_err()
+        .starts_with("Unable to read previous report"));
+}
+
+#[test]
+fn test_load_json_report_round_trip() {
+    use reqwest::StatusCode;
+    use siteprobe::options::Cli;
+    use std::time::Duration;
+
+    let responses = vec![
+        Response {
+            url: "https://example.com/new".to_string(),
+            requested_url: "https://example.com/old?ts=1234567890".to_string(),
+            source_url: "https://example.com/old".to_string(),
+            response_time: Duration::from_millis(320),
+            ttfb: Duration::from_millis(110),
+            response_size: 2048,
+            status_code: StatusCode::OK,
+            redirect_count: 1,
+            missing_markers: vec!["<footer>".to_string()],
+            ..Default::default()
+        },
+        Response {
+            url: "https://example.com/gone".to_string(),
+            requested_url: "https://example.com/gone".to_string(),
+            source_url: "https://example.com/gone".to_string(),
+            response_time: Duration::from_millis(45),
+            status_code: StatusCode::NOT_FOUND,
+            soft_404: true,
+            ..Default::default()
+        },
+    ];
+    let report = Report {
+        responses: responses.clone().into(),
+        ..Default::default()
+    };
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), report.to_json_string(&Cli::default()).unwrap()).unwrap();
+
+    let loaded = load_json_report(file.path()).unwrap();
+    assert_eq!(loaded.len(), 2);
+    for (loaded, original) in loaded.iter().zip(&responses) {
+        assert_eq!(loaded.url, original.url);
+        // The report lists the requested URL without the cache buster.
+        assert_eq!(loaded.requested_url, original.source_url);
+        assert_eq!(loaded.response_time, original.response_time);
+        assert_eq!(loaded.ttfb, original.ttfb);
+        assert_eq!(loaded.response_size, original.response_size);
+        assert_eq!(loaded.status_code, original.status_code);
+        assert_eq!(loaded.redirect_count, original.redirect_count);
+        assert_eq!(loaded.missing_markers, original.missing_markers);
+        assert_eq!(loaded.soft_404, original.soft_404);
+    }
+    assert!(loaded[0].redirected);
+    assert!(!loaded[1].redirected);
+}
+
+#[test]
+fn test_response_is_json() {
+    let with_type = |ct: Option<&str>| Response {
+        content_type: ct.map(String::from),
+        ..Default::default()
+    };
+    assert!(with_type(Some("application/json")).is_json());
+    assert!(with_type(Some("application/json; charset=utf-8")).is_json());
+    assert!(with_type(Some("application/problem+json")).is_json());
+    assert!(with_type(Some("Application/JSON")).is_json());
+    assert!(!with_type(Some("text/html")).is_json());
+    assert!(!with_type(Some("application/jsonp")).is_json());
+    assert!(!with_type(None).is_json());
+}
+
+#[test]
+fn test_write_junit_report() {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    let response = |url: &str, status: u16| Response {
+        url: url.to_string(),
+        status_code: StatusCode::from_u16(status).unwrap(),
+        response_time: Duration::from_millis(250),
+        response_size: 100,
+        ..Default::default()
+    };
+    let report = Report {
+        sitemap_url: "https://example.com/sitemap.xml".to_string(),
+        total_time: Duration::from_secs(2),
+        responses: [
+            response("https://example.com/", 200),
+            response("https://example.com/missing?a=1&b=2", 404),
+            response("https://example.com/broken", 503),
+            // A followed redirect, with the cache buster of --append-timestamp.
+            Response {
+                source_url: "https://example.com/old".to_string(),
+                ..response("https://example.com/new?ts=1234567890", 200)
+            },
+        ]
+        .into(),
+        ..Default::default()
+    };
+    let file = tempfile::NamedTempFile::new().unwrap();
+    report
+        .write_junit_report(&file.path().to_path_buf(), true)
+        .unwrap();
+
+    let xml = std::fs::read_to_string(file.path()).unwrap();
+    let mut reader = Reader::from_str(&xml);
+    let (mut suite, mut testcases, mut failures) = (Vec::new(), Vec::new(), 0);
+    loop {
+        match reader.read_event().unwrap() {
+            Event::Start(e) | Event::Empty(e) => {
+                let attrs: Vec<(String, String)> = e
+                    .attributes()
+                    .map(|a| {
+                        let a = a.unwrap();
+                        (
+                            String::from_utf8_lossy(a.key.as_ref()).into_owned(),
+                            a.unescape_value().unwrap().into_owned(),
+                        )
+                    })
+                    .collect();
+                match e.name().as_ref() {
+                    b"testsuite" => suite = attrs,
+                    b"testcase" => testcases.push(attrs),
+                    b"failure" => failures += 1,
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let attr = |attrs: &[(String, String)], key: &str| {
+        attrs.iter().find(|(k, _)| k == key).unwrap().1.clone()
+    };
+    assert_eq!(attr(&suite, "tests"), "4");
+    assert_eq!(attr(&suite, "failures"), "2");
+    assert_eq!(attr(&suite, "time"), "2.000");
+    assert_eq!(failures, 2);
+    assert_eq!(testcases.len(), 4);
+    assert_eq!(
+        attr(&testcases[1], "name"),
+        "https://example.com/missing?a=1&b=2"
+    );
+    assert_eq!(attr(&testcases[1], "time"), "0.250");
+    assert_eq!(attr(&testcases[3], "name"), "https://example.com/old");
+}
+
+#[test]
+fn test_write_markdown_report() {
+    use reqwest::StatusCode;
+    use siteprobe::options::Cli;
+    use std::time::Duration;
+
+    let response = |url: &str, status: u16, ms: u64| Response {
+        url: url.to_string(),
+        status_code: StatusCode::from_u16(status).unwrap(),
+        response_time: Duration::from_millis(ms),
+        ..Default::default()
+    };
+    let report = Report {
+        sitemap_url: "https://example.com/sitemap.xml".to_string(),
+        total_time: Duration::from_secs(2),
+        responses: [
+            response("https://example.com/", 200, 100),
+            response("https://example.com/slow", 200, 1500),
+            response("https://example.com/missing", 404, 50),
+            response("https://example.com/a|b", 500, 2000),
+        ]
+        .into(),
+        bypass_caching: true,
+        warmup_requests: 3,
+        ..Default::default()
+    };
+    let options = Cli {
+        slow_threshold: Some(1.0),
+        json: true,
+        ..Default::default()
+    };
+    let file = tempfile::NamedTempFile::new().unwrap();
+    report
+        .write_markdown_report(&options, &file.path().to_path_buf())
+        .unwrap();
+
+    let md = std::fs::read_to_string(file.path()).unwrap();
+    assert!(md.starts_with("# Statistics for https://example.com/sitemap.xml\n"));
+    assert!(md.contains("**❌ 2 errors, 2 slow — exit 1**"));
+    for header in [
+        "## Summary",
+        "## Response Time and Performance Statistics",
+        "## Error Responses",
+        "## Slow Responses (>=1s)",
+    ] {
+        assert!(md.contains(header), "missing {header}:\n{md}");
+    }
+    assert!(md.contains("| Concurrency Limit | 0 |"));
+    // The summary matches the text report, including its optional entries.
+    assert!(md.contains("| Bypass Caching | Yes |"));
+    assert!(md.contains("| Warm-up Requests | 3 |"));
+    assert!(md.contains("| ⚡️ Total Requests Processed | 4 |"));
+
+    // Error rows first, then slow rows; pipes in URLs are escaped.
+    let rows: Vec<&str> = md
+        .lines()
+        .filter(|line| {
+            line.starts_with("| 200 ") || line.starts_with("| 404 ") || line.starts_with("| 500 ")
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            "| 500 Internal Server Error | `https://example.com/a\\|b` | 2000ms |",
+            "| 404 Not Found | `https://example.com/missing` | 50ms |",
+            "| 500 Internal Server Error | `https://example.com/a\\|b` | 2000ms |",
+            "| 200 OK | `https://example.com/slow` | 1500ms |",
+        ]
+    );
+}
+
+#[test]
+fn test_write_failures() {
+    use reqwest::StatusCode;
+
+    let response = |source_url: &str, url: &str, status: u16| Response {
+        source_url: source_url.to_string(),
+        url: url.to_string(),
+        status_code: StatusCode::from_u16(status).unwrap(),
+        ..Default::default()
+    };
+    let report = Report {
+        responses: [
+            response("https://example.com/", "https://example.com/", 200),
+            response(
+                "https://example.com/missing",
+                "https://example.com/missing?ts=123",
+                404,
+            ),
+            response("https://example.com/slow", "https://example.com/slow", 408),
+            // A repeated probe of the same URL is listed once.
+            response(
+                "https://example.com/missing",
+                "https://example.com/missing?ts=456",
+                404,
+            ),
+            response("", "https://example.com/broken", 502),
+        ]
+        .into(),
+        ..Default::default()
+    };
+    let file = tempfile::NamedTempFile::new().unwrap();
+    report
+        .write_failures(&file.path().to_path_buf(), true)
+        .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(file.path()).unwrap(),
+        "https://example.com/missing\nhttps://example.com/slow\nhttps://example.com/broken\n"
+    );
+}
+
+#[test]
+fn test_excessive_redirects() {
+    let response = |url: &str, redirect_count: usize, redirect_loop: bool| Response {
+        url: url.to_string(),
+        requested_url: url.to_string(),
+        redirect_count,
+        redirect_loop,
+        ..Default::default()
+    };
+    let report = Report {
+        responses: vec![
+            response("https://example.com/c", 3, false),
+            response("https://example.com/a", 1, false),
+            response("https://example.com/b", 1, true),
+            response("https://example.com/c", 3, false),
+        ]
+        .into(),
+        ..Default::default()
+    };
+
+    let urls = |threshold| {
+        report
+            .excessive_redirects(threshold)
+            .into_iter()
+            .map(|r| r.requested_url)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(urls(None), vec!["https://example.com/b"]);
+    assert_eq!(
+        urls(Some(2)),
+        vec!["https://example.com/b", "https://example.com/c"]
+    );
+    assert_eq!(
+        urls(Some(0)),
+        vec![
+            "https://example.com/a",
+            "https://example.com/b",
+            "https://example.com/c"
+        ]
+    );
+}
@@ -1,6 +1,8 @@
 use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use wiremock::matchers::{method, path};
-use wiremock::{Mock, MockServer, ResponseTemplate};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
 
 /// Helper: create a minimal sitemap XML with a single URL.
 fn single_url_sitemap(url: &str) -> String {
@@ -166,7 +168,184 @@ async fn test_no_retry_on_4xx() {
 }
 
 // ---------------------------------------------------------------------------
-// Test 4: CLI validation rejects --retries 11 (max is 10)
+// Test 4: The pause between retries grows with every attempt
+// ---------------------------------------------------------------------------
+
+/// Responds with a 500 and records when each request arrived.
+struct RecordTimes(Arc<Mutex<Vec<Instant>>>);
+
+impl Respond for RecordTimes {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        self.0.lock().unwrap().push(Instant::now());
+        ResponseTemplate::new(500)
+    }
+}
+
+#[tokio::test]
+async fn test_retry_backoff_grows_between_attempts() {
+    let mock_server = MockServer::start().await;
+
+    let page_url = format!("{}/page", mock_server.uri());
+    let sitemap_xml = single_url_sitemap(&page_url);
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(&sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    let times = Arc::new(Mutex::new(Vec::new()));
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(RecordTimes(Arc::clone(&times)))
+        .expect(4)
+        .mount(&mock_server)
+        .await;
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", mock_server.uri()),
+            "--json",
+            "--retries",
+            "3",
+            "--retry-backoff-ms",
+            "200",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    // The last attempt is recorded.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).expect("Output should be valid JSON");
+    assert_eq!(json["responses"][0]["statusCode"], 500);
+
+    // Pauses of 200-300ms, 400-600ms and 800-1200ms.
+    let times = times.lock().unwrap();
+    let gaps: Vec<Duration> = times.windows(2).map(|w| w[1] - w[0]).collect();
+    assert_eq!(gaps.len(), 3);
+    assert!(gaps[0] >= Duration::from_millis(200), "gaps: {gaps:?}");
+    assert!(
+        gaps[0] < gaps[1] && gaps[1] < gaps[2],
+        "pauses between retries should grow: {gaps:?}"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Test 5: A 429 with Retry-After is retried after the indicated time
+// ---------------------------------------------------------------------------
+
+/// Responds with a 429 and `Retry-After: 1` first, then with a 200, and records
+/// when each request arrived.
+struct TooManyRequestsOnce(Arc<Mutex<Vec<Instant>>>);
+
+impl Respond for TooManyRequestsOnce {
+    fn respond(&self, _request: &Request) -> ResponseTemplate {
+        let mut times = self.0.lock().unwrap();
+        times.push(Instant::now());
+        if times.len() == 1 {
+            ResponseTemplate::new(429).insert_header("Retry-After", "1")
+        } else {
+            ResponseTemplate::new(200)
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_retry_after_is_respected() {
+    let mock_server = MockServer::start().await;
+
+    let page_url = format!("{}/page", mock_server.uri());
+    let sitemap_xml = single_url_sitemap(&page_url);
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(&sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    let times = Arc::new(Mutex::new(Vec::new()));
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(TooManyRequestsOnce(Arc::clone(&times)))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    // Without Retry-After, the retry would be sent right away.
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", mock_server.uri()),
+            "--json",
+            "--retries",
+            "1",
+            "--retry-backoff-ms",
+            "0",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).expect("Output should be valid JSON");
+    assert_eq!(json["responses"][0]["statusCode"], 200);
+
+    let times = times.lock().unwrap();
+    let pause = times[1] - times[0];
+    assert!(pause >= Duration::from_secs(1), "retried after {pause:?}");
+}
+
+// ---------------------------------------------------------------------------
+// Test 6: --no-respect-retry-after doesn't retry 429 responses
+// ---------------------------------------------------------------------------
+#[tokio::test]
+async fn test_no_respect_retry_after_does_not_retry_429() {
+    let mock_server = MockServer::start().await;
+
+    let page_url = format!("{}/page", mock_server.uri());
+    let sitemap_xml = single_url_sitemap(&page_url);
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(&sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            &format!("{}/sitemap.xml", mock_server.uri()),
+            "--json",
+            "--retries",
+            "2",
+            "--no-respect-retry-after",
+        ])
+        .output()
+        .expect("Failed to execute siteprobe binary");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).expect("Output should be valid JSON");
+    assert_eq!(json["responses"][0]["statusCode"], 429);
+}
+
+// ---------------------------------------------------------------------------
+// Test 7: CLI validation rejects --retries 11 (max is 10)
 // ---------------------------------------------------------------------------
 #[test]
 fn test_retries_max_validation() {
@@ -194,3 +373,37 @@ fn test_retries_max_validation() {
         stderr
     );
 }
+
+// ---------------------------------------------------------------------------
+// Test 7: A Retry-After longer than the cap is not waited for
+// ---------------------------------------------------------------------------
+#[tokio::test]
+async fn test_long_retry_after_is_not_retried() {
+    let mock_server = MockServer::start().await;
+
+    let page_url = format!("{}/page", mock_server.uri());
+    let sitemap_xml = single_url_sitemap(&page_url);
+
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(&sitemap_xml))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "86400"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let start = Instant::now();
+    let output = run_siteprobe(&format!("{}/sitemap.xml", mock_server.uri()), 2);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout).expect("Output should be valid JSON");
+    assert_eq!(json["responses"][0]["statusCode"], 503);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(start.elapsed() < Duration::from_secs(60));
+}
@@ -1,6 +1,13 @@
 use siteprobe::sitemap::{
-    decompress_gzip, extract_sitemap_urls, identify_sitemap_type, is_gzip_content, SitemapType,
+    check_sitemap_content_type, check_url_count, check_url_locations, classify_duplicate_urls,
+    decode_sitemap, decompress_gzip, describe_sitemap_error, discovery_candidates, dry_run,
+    extract_html_links, extract_sitemap_entries, extract_sitemap_urls, filter_changed_urls,
+    filter_modified_since, filter_urls_by_host, filter_urls_by_pattern, get_sitemap_urls,
+    identify_sitemap_type, is_gzip_content, merge_sitemap_urls, parse_lastmod,
+    parse_robots_sitemaps, parse_url_list, probe_order, read_urls_file, strip_query_params,
+    url_overview, url_structure, DuplicateUrls, SitemapType, SitemapUrls,
 };
+use siteprobe::warnings::Warnings;
 
 // ===========================================================================================
 // identify_sitemap_type Tests
@@ -357,7 +364,7 @@ fn test_decompress_gzip_valid() {
     encoder.write_all(xml.as_bytes()).unwrap();
     let compressed = encoder.finish().unwrap();
 
-    let decompressed = decompress_gzip(&compressed).unwrap();
+    let decompressed = String::from_utf8(decompress_gzip(&compressed).unwrap()).unwrap();
     assert_eq!(decompressed, xml);
 
     // Verify the decompressed XML can be parsed
@@ -401,7 +408,7 @@ fn test_gzip_roundtrip_sitemap_index() {
     encoder.write_all(xml.as_bytes()).unwrap();
     let compressed = encoder.finish().unwrap();
 
-    let decompressed = decompress_gzip(&compressed).unwrap();
+    let decompressed = String::from_utf8(decompress_gzip(&compressed).unwrap()).unwrap();
     let sitemap_type = identify_sitemap_type(&decompressed);
     assert_eq!(sitemap_type, SitemapType::SitemapIndex);
 
@@ -410,3 +417,1131 @@ fn test_gzip_roundtrip_sitemap_index() {
     assert_eq!(urls[0], "http://www.example.com/sitemap1.xml.gz");
     assert_eq!(urls[1], "http://www.example.com/sitemap2.xml.gz");
 }
+
+// ===========================================================================================
+// filter_urls_by_host Tests
+// ===========================================================================================
+
+fn host_filter_urls() -> Vec<String> {
+    vec![
+        "https://example.com/".to_string(),
+        "https://blog.example.com/post".to_string(),
+        "https://cdn.example.net/asset".to_string(),
+    ]
+}
+
+#[test]
+fn test_filter_urls_by_host_no_filters_keeps_all() {
+    let (urls, dropped) = filter_urls_by_host(host_filter_urls(), &[], &[]);
+    assert_eq!(urls.len(), 3);
+    assert_eq!(dropped, 0);
+}
+
+#[test]
+fn test_filter_urls_by_host_allowlist() {
+    let allow = vec!["example.com".to_string(), "BLOG.example.com".to_string()];
+    let (urls, dropped) = filter_urls_by_host(host_filter_urls(), &allow, &[]);
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/".to_string(),
+            "https://blog.example.com/post".to_string()
+        ]
+    );
+    assert_eq!(dropped, 1);
+}
+
+#[test]
+fn test_filter_urls_by_host_deny_takes_precedence() {
+    let allow = vec!["example.com".to_string(), "blog.example.com".to_string()];
+    let deny = vec!["blog.example.com".to_string()];
+    let (urls, dropped) = filter_urls_by_host(host_filter_urls(), &allow, &deny);
+    assert_eq!(urls, vec!["https://example.com/".to_string()]);
+    assert_eq!(dropped, 2);
+}
+
+// ===========================================================================================
+// filter_urls_by_pattern Tests
+// ===========================================================================================
+
+fn pattern_filter_urls() -> Vec<String> {
+    urls(&[
+        "https://example.com/",
+        "https://example.com/blog/",
+        "https://example.com/blog/post",
+        "https://example.com/blog/drafts/wip",
+        "https://example.com/shop/item?id=1",
+    ])
+}
+
+fn patterns(list: &[&str]) -> Vec<regex::Regex> {
+    list.iter().map(|p| regex::Regex::new(p).unwrap()).collect()
+}
+
+#[test]
+fn test_filter_urls_by_pattern_no_filters_keeps_all() {
+    let (urls, dropped) = filter_urls_by_pattern(pattern_filter_urls(), &[], &[]);
+    assert_eq!(urls, pattern_filter_urls());
+    assert_eq!(dropped, 0);
+}
+
+#[test]
+fn test_filter_urls_by_pattern_include_any() {
+    let include = patterns(&["/blog/post$", r"\?id="]);
+    let (urls, dropped) = filter_urls_by_pattern(pattern_filter_urls(), &include, &[]);
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/blog/post".to_string(),
+            "https://example.com/shop/item?id=1".to_string(),
+        ]
+    );
+    assert_eq!(dropped, 3);
+}
+
+#[test]
+fn test_filter_urls_by_pattern_exclude_takes_precedence() {
+    let include = patterns(&["/blog/"]);
+    let exclude = patterns(&["/blog/drafts/"]);
+    let (urls, dropped) = filter_urls_by_pattern(pattern_filter_urls(), &include, &exclude);
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/blog/".to_string(),
+            "https://example.com/blog/post".to_string(),
+        ]
+    );
+    assert_eq!(dropped, 3);
+
+    let (urls, _) = filter_urls_by_pattern(pattern_filter_urls(), &[], &exclude);
+    assert_eq!(urls.len(), 4);
+}
+
+// ===========================================================================================
+// describe_sitemap_error Tests
+// ===========================================================================================
+
+#[tokio::test]
+async fn test_describe_sitemap_error_connection_refused() {
+    // Port 1 on localhost is reserved and not expected to accept connections.
+    let url = "http://127.0.0.1:1/sitemap.xml";
+    let err = reqwest::get(url).await.unwrap_err();
+    let message = describe_sitemap_error(url, &err);
+    assert!(
+        message.starts_with("Connection refused by '127.0.0.1'"),
+        "Unexpected message: {}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn test_describe_sitemap_error_host_not_found() {
+    // The `.invalid` TLD is reserved and guaranteed never to resolve.
+    let url = "http://siteprobe-test.invalid/sitemap.xml";
+    let err = reqwest::get(url).await.unwrap_err();
+    let message = describe_sitemap_error(url, &err);
+    assert!(
+        message.starts_with("Host not found: could not resolve 'siteprobe-test.invalid'"),
+        "Unexpected message: {}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn test_describe_sitemap_error_tls() {
+    use wiremock::MockServer;
+
+    // A plain HTTP server fails the TLS handshake of an https request.
+    let mock_server = MockServer::start().await;
+    let url = format!(
+        "https://127.0.0.1:{}/sitemap.xml",
+        mock_server.address().port()
+    );
+    let err = reqwest::get(&url).await.unwrap_err();
+    let message = describe_sitemap_error(&url, &err);
+    assert!(
+        message.starts_with("TLS error while connecting to '127.0.0.1'"),
+        "Unexpected message: {}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn test_describe_sitemap_error_tls_in_url_is_not_a_tls_error() {
+    // The connection is closed without a response. The error message includes the
+    // URL, which mentions TLS, but there is no TLS involved.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((socket, _)) = listener.accept().await {
+            drop(socket);
+        }
+    });
+    let url = format!("http://127.0.0.1:{}/tls-certificate-handshake.xml", port);
+    let err = reqwest::get(&url).await.unwrap_err();
+    let message = describe_sitemap_error(&url, &err);
+    assert!(
+        message.starts_with("Unable to fetch sitemap"),
+        "Unexpected message: {}",
+        message
+    );
+}
+
+#[test]
+fn test_describe_sitemap_error_fallback() {
+    let err: Box<dyn std::error::Error> = "invalid utf-8".into();
+    let message = describe_sitemap_error("https://example.com/sitemap.xml", err.as_ref());
+    assert_eq!(message, "Unable to fetch sitemap: invalid utf-8");
+}
+
+// ===========================================================================================
+// classify_duplicate_urls Tests
+// ===========================================================================================
+
+fn urls(list: &[&str]) -> Vec<String> {
+    list.iter().map(|u| u.to_string()).collect()
+}
+
+#[test]
+fn test_classify_duplicate_urls_none() {
+    let input = vec![
+        ("sitemap1.xml".to_string(), urls(&["/a", "/b"])),
+        ("sitemap2.xml".to_string(), urls(&["/c"])),
+    ];
+    let duplicates = classify_duplicate_urls(&input);
+    assert!(duplicates.is_empty());
+}
+
+#[test]
+fn test_classify_duplicate_urls_within_and_across() {
+    let input = vec![
+        ("sitemap1.xml".to_string(), urls(&["/a", "/b", "/a"])),
+        ("sitemap2.xml".to_string(), urls(&["/b", "/c", "/c"])),
+    ];
+    let duplicates = classify_duplicate_urls(&input);
+    assert_eq!(duplicates.within_sitemap, urls(&["/a", "/c"]));
+    assert_eq!(duplicates.across_sitemaps, urls(&["/b"]));
+}
+
+#[test]
+fn test_classify_duplicate_urls_both_kinds() {
+    let input = vec![
+        ("sitemap1.xml".to_string(), urls(&["/a", "/a"])),
+        ("sitemap2.xml".to_string(), urls(&["/a"])),
+    ];
+    let duplicates = classify_duplicate_urls(&input);
+    assert_eq!(duplicates.within_sitemap, urls(&["/a"]));
+    assert_eq!(duplicates.across_sitemaps, urls(&["/a"]));
+}
+
+// ===========================================================================================
+// Sitemap discovery Tests
+// ===========================================================================================
+
+#[test]
+fn test_discovery_candidates_excludes_given_url() {
+    let candidates = discovery_candidates("https://example.com/sitemap.xml");
+    assert_eq!(
+        candidates,
+        vec![
+            "https://example.com/sitemap_index.xml".to_string(),
+            "https://example.com/sitemap1.xml".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_discovery_candidates_uses_site_root() {
+    let candidates = discovery_candidates("https://example.com/en/custom-sitemap.xml");
+    assert_eq!(candidates.len(), 3);
+    assert_eq!(candidates[0], "https://example.com/sitemap_index.xml");
+}
+
+#[test]
+fn test_parse_robots_sitemaps() {
+    let robots = "User-agent: *\nDisallow: /admin\n\nSitemap: https://example.com/a.xml\nsitemap:https://example.com/b.xml\nSitemap:\n";
+    assert_eq!(
+        parse_robots_sitemaps(robots),
+        vec![
+            "https://example.com/a.xml".to_string(),
+            "https://example.com/b.xml".to_string()
+        ]
+    );
+}
+
+// ===========================================================================================
+// Sitemap Content-Type Tests
+// ===========================================================================================
+
+const URLSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>https://example.com/</loc></url>
+</urlset>"#;
+
+#[test]
+fn test_check_sitemap_content_type_xml_is_fine() {
+    let url = "https://example.com/sitemap.xml";
+    assert_eq!(
+        check_sitemap_content_type(url, Some("application/xml"), URLSET),
+        Ok(None)
+    );
+    assert_eq!(
+        check_sitemap_content_type(url, Some("text/plain"), URLSET),
+        Ok(None)
+    );
+    assert_eq!(check_sitemap_content_type(url, None, URLSET), Ok(None));
+}
+
+#[test]
+fn test_check_sitemap_content_type_html_with_xml_body_warns() {
+    let warning = check_sitemap_content_type(
+        "https://example.com/sitemap.xml",
+        Some("text/html; charset=utf-8"),
+        URLSET,
+    )
+    .unwrap()
+    .expect("expected a warning");
+    assert!(warning.contains("text/html"));
+    assert!(warning.contains("https://example.com/sitemap.xml"));
+}
+
+#[test]
+fn test_check_sitemap_content_type_html_with_html_body_errors() {
+    let body = "<!DOCTYPE html>\n<html><head><title>Home</title></head><body></body></html>";
+    let err =
+        check_sitemap_content_type("https://example.com/sitemap.xml", Some("text/html"), body)
+            .unwrap_err();
+    assert!(err.contains("HTML page"));
+}
+
+// ===========================================================================================
+// read_urls_file Tests
+// ===========================================================================================
+
+#[test]
+fn test_read_urls_file_skips_comments_and_duplicates() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(
+        file.path(),
+        "# Failed URLs\nhttps://example.com/b\n\n  https://example.com/a  \nhttps://example.com/b\n",
+    )
+    .unwrap();
+
+    let result = read_urls_file(file.path()).unwrap();
+    assert_eq!(
+        result.urls,
+        vec!["https://example.com/b", "https://example.com/a"]
+    );
+    assert_eq!(
+        result.sitemaps,
+        vec![(file.path().display().to_string(), 2)]
+    );
+}
+
+#[test]
+fn test_read_urls_file_reports_invalid_lines() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "https://example.com/\nnot a url\n").unwrap();
+
+    let err = read_urls_file(file.path()).unwrap_err().to_string();
+    assert!(err.contains("line 2"), "{}", err);
+    assert!(err.contains("not a url"), "{}", err);
+
+    let err = read_urls_file(std::path::Path::new("/nonexistent/urls.txt"))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Unable to read the URLs file"), "{}", err);
+}
+
+// ===========================================================================================
+// probe_order Tests
+// ===========================================================================================
+
+#[test]
+fn test_probe_order_keeps_sitemap_order_by_default() {
+    assert_eq!(probe_order(4, false, Some(1)), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_probe_order_shuffle_is_reproducible_with_seed() {
+    let first = probe_order(50, true, Some(42));
+    assert_eq!(first, probe_order(50, true, Some(42)));
+    assert_ne!(first, (0..50).collect::<Vec<_>>());
+
+    let mut sorted = first.clone();
+    sorted.sort_unstable();
+    assert_eq!(
+        sorted,
+        (0..50).collect::<Vec<_>>(),
+        "every probe is sent once"
+    );
+}
+
+// ===========================================================================================
+// dry_run Tests
+// ===========================================================================================
+
+#[test]
+fn test_dry_run_estimates_time_from_concurrency_and_rate_limit() {
+    use clap::Parser;
+    use siteprobe::options::Cli;
+    use std::time::Duration;
+
+    let urls: Vec<String> = (0..10)
+        .map(|i| format!("https://example.com/{i}"))
+        .collect();
+    let sitemaps = vec![("https://example.com/sitemap.xml".to_string(), 12)];
+
+    // 20 requests, 5 at a time: 4 rounds of up to 2 attempts of 10s each.
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "https://example.com/sitemap.xml",
+        "--repeat=2",
+        "--concurrency-limit=5",
+        "--request-timeout=10",
+        "--retries=1",
+    ])
+    .unwrap();
+    let summary = dry_run(&urls, &sitemaps, &cli);
+    assert_eq!(summary.urls, 10);
+    assert_eq!(summary.requests, 20);
+    assert_eq!(summary.sitemaps, sitemaps);
+    assert_eq!(summary.min_time, Duration::ZERO);
+    assert_eq!(summary.max_time, Duration::from_secs(80));
+    assert!(summary.to_string().contains("up to 80s"));
+
+    // At 60 requests per minute, 10 requests are spaced 1s apart.
+    let cli = Cli::try_parse_from([
+        "siteprobe",
+        "https://example.com/sitemap.xml",
+        "--rate-limit=60/1m",
+        "--request-timeout=1",
+    ])
+    .unwrap();
+    let summary = dry_run(&urls, &sitemaps, &cli);
+    assert_eq!(summary.min_time, Duration::from_secs(9));
+    assert!(summary
+        .to_string()
+        .contains("at least 9s with --rate-limit"));
+}
+
+// ===========================================================================================
+// get_sitemap_urls Tests
+// ===========================================================================================
+
+#[tokio::test]
+async fn test_get_sitemap_urls_fetches_child_sitemaps_concurrently() {
+    use std::time::{Duration, Instant};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let children: String = (1..=4)
+        .map(|i| format!("<sitemap><loc>{base}/sitemap{i}.xml</loc></sitemap>"))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/sitemap_index.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{children}</sitemapindex>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    for i in 1..=4 {
+        Mock::given(method("GET"))
+            .and(path(format!("/sitemap{i}.xml")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(format!(
+                        r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"><url><loc>{base}/page{i}</loc></url></urlset>"#
+                    ))
+                    .set_delay(Duration::from_millis(500)),
+            )
+            .mount(&mock_server)
+            .await;
+    }
+
+    let client = reqwest::Client::new();
+    let started = Instant::now();
+    let result = get_sitemap_urls(
+        &format!("{base}/sitemap_index.xml"),
+        &client,
+        true,
+        false,
+        false,
+        4,
+        &Warnings::default(),
+    )
+    .await
+    .unwrap();
+
+    assert!(
+        started.elapsed() < Duration::from_millis(1500),
+        "Child sitemaps should be fetched in parallel"
+    );
+    let expected: Vec<String> = (1..=4).map(|i| format!("{base}/page{i}")).collect();
+    assert_eq!(result.urls, expected);
+    let sitemaps: Vec<_> = (1..=4)
+        .map(|i| (format!("{base}/sitemap{i}.xml"), 1))
+        .collect();
+    assert_eq!(result.sitemaps, sitemaps);
+}
+
+#[tokio::test]
+async fn test_get_sitemap_urls_reports_missing_child_sitemaps() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    let children: String = (1..=5)
+        .map(|i| format!("<sitemap><loc>{base}/sitemap{i}.xml</loc></sitemap>"))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/sitemap_index.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{children}</sitemapindex>"#
+        )))
+        .mount(&mock_server)
+        .await;
+    // sitemap3.xml is not mounted, so it returns a 404.
+    for i in [1, 2, 4, 5] {
+        Mock::given(method("GET"))
+            .and(path(format!("/sitemap{i}.xml")))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"><url><loc>{base}/page{i}</loc></url></urlset>"#
+            )))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let client = reqwest::Client::new();
+    let warnings = Warnings::default();
+    let result = get_sitemap_urls(
+        &format!("{base}/sitemap_index.xml"),
+        &client,
+        true,
+        false,
+        false,
+        3,
+        &warnings,
+    )
+    .await
+    .unwrap();
+
+    let expected: Vec<String> = [1, 2, 4, 5]
+        .iter()
+        .map(|i| format!("{base}/page{i}"))
+        .collect();
+    assert_eq!(result.urls, expected);
+    assert_eq!(
+        warnings.to_vec()[0],
+        format!("The referenced sitemap is missing: {base}/sitemap3.xml")
+    );
+}
+
+// ===========================================================================================
+// check_url_locations Tests
+// ===========================================================================================
+
+#[test]
+fn test_check_url_locations_clean() {
+    let warnings = check_url_locations(
+        "https://example.com/sitemap.xml",
+        &urls(&["https://example.com/a", "https://EXAMPLE.com/b"]),
+    );
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_check_url_locations_off_host_and_insecure() {
+    let warnings = check_url_locations(
+        "https://example.com/sitemap.xml",
+        &urls(&[
+            "https://example.com/a",
+            "http://example.com/b",
+            "https://cdn.example.org/c",
+            "https://cdn.example.org/d",
+        ]),
+    );
+    assert_eq!(
+        warnings,
+        vec![
+            "2 URL(s) are on another host than the sitemap, e.g. https://cdn.example.org/c",
+            "1 URL(s) use http:// although the sitemap is served over https://, e.g. http://example.com/b",
+        ]
+    );
+}
+
+#[test]
+fn test_check_url_locations_http_sitemap() {
+    let warnings = check_url_locations(
+        "http://example.com/sitemap.xml",
+        &urls(&["http://example.com/a"]),
+    );
+    assert!(warnings.is_empty());
+}
+
+// ===========================================================================================
+// filter_changed_urls Tests
+// ===========================================================================================
+
+#[test]
+fn test_filter_changed_urls_skips_healthy_urls() {
+    let previous: std::collections::HashMap<String, bool> = [
+        ("https://example.com/ok".to_string(), false),
+        ("https://example.com/moved".to_string(), false),
+        ("https://example.com/missing".to_string(), true),
+        ("https://example.com/broken".to_string(), true),
+    ]
+    .into_iter()
+    .collect();
+
+    let (urls, skipped) = filter_changed_urls(
+        urls(&[
+            "https://example.com/ok",
+            "https://example.com/moved",
+            "https://example.com/missing",
+            "https://example.com/broken",
+            "https://example.com/new",
+        ]),
+        &previous,
+    );
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/missing",
+            "https://example.com/broken",
+            "https://example.com/new",
+        ]
+    );
+    assert_eq!(skipped, 2);
+}
+
+// ===========================================================================================
+// lastmod Tests
+// ===========================================================================================
+
+#[test]
+fn test_extract_sitemap_entries_with_lastmod() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+   <url>
+      <loc>https://example.com/date</loc>
+      <lastmod>2005-01-01</lastmod>
+   </url>
+   <url>
+      <lastmod> 2004-12-23T18:00:15+00:00 </lastmod>
+      <loc>https://example.com/datetime</loc>
+   </url>
+   <url>
+      <loc>https://example.com/none</loc>
+   </url>
+</urlset>"#;
+
+    assert_eq!(
+        extract_sitemap_entries(xml),
+        vec![
+            (
+                "https://example.com/date".to_string(),
+                Some("2005-01-01".to_string())
+            ),
+            (
+                "https://example.com/datetime".to_string(),
+                Some("2004-12-23T18:00:15+00:00".to_string())
+            ),
+            ("https://example.com/none".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_lastmod_formats() {
+    let parsed = |value: &str| parse_lastmod(value).map(|d| d.to_rfc3339());
+
+    assert_eq!(
+        parsed("2005-01-01"),
+        Some("2005-01-01T00:00:00+00:00".to_string())
+    );
+    assert_eq!(
+        parsed("2004-12-23T18:00:15+00:00"),
+        Some("2004-12-23T18:00:15+00:00".to_string())
+    );
+    assert_eq!(
+        parsed("2004-12-23T18:00:15.5Z"),
+        Some("2004-12-23T18:00:15.500+00:00".to_string())
+    );
+    assert_eq!(
+        parsed("2004-12-23T18:00+01:00"),
+        Some("2004-12-23T18:00:00+01:00".to_string())
+    );
+    assert_eq!(
+        parsed("2004-12-23T18:00Z"),
+        Some("2004-12-23T18:00:00+00:00".to_string())
+    );
+    assert_eq!(parsed("yesterday"), None);
+    assert_eq!(parsed("2004-13-01"), None);
+}
+
+#[test]
+fn test_filter_modified_since() {
+    let lastmod: std::collections::HashMap<String, String> = [
+        ("https://example.com/old-date", "2023-12-31"),
+        ("https://example.com/new-date", "2024-01-01"),
+        (
+            "https://example.com/old-datetime",
+            "2024-01-01T00:30:00+01:00",
+        ),
+        (
+            "https://example.com/new-datetime",
+            "2024-01-01T00:30:00+00:00",
+        ),
+        ("https://example.com/invalid", "last tuesday"),
+    ]
+    .into_iter()
+    .map(|(url, value)| (url.to_string(), value.to_string()))
+    .collect();
+    let since = parse_lastmod("2024-01-01").unwrap();
+
+    let (urls, skipped) = filter_modified_since(
+        urls(&[
+            "https://example.com/old-date",
+            "https://example.com/new-date",
+            "https://example.com/old-datetime",
+            "https://example.com/new-datetime",
+            "https://example.com/invalid",
+            "https://example.com/no-lastmod",
+        ]),
+        &lastmod,
+        &since,
+    );
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/new-date",
+            "https://example.com/new-datetime",
+            "https://example.com/invalid",
+            "https://example.com/no-lastmod",
+        ]
+    );
+    assert_eq!(skipped, 2);
+}
+
+// ===========================================================================================
+// check_url_count Tests
+// ===========================================================================================
+
+#[test]
+fn test_check_url_count_exact() {
+    assert!(check_url_count(100, 100, 0.0).is_ok());
+    assert_eq!(
+        check_url_count(99, 100, 0.0).unwrap_err(),
+        "The sitemap lists 99 URLs, expected 100 URLs."
+    );
+}
+
+#[test]
+fn test_check_url_count_tolerance() {
+    assert!(check_url_count(90, 100, 10.0).is_ok());
+    assert!(check_url_count(110, 100, 10.0).is_ok());
+    assert_eq!(
+        check_url_count(12, 100, 10.0).unwrap_err(),
+        "The sitemap lists 12 URLs, expected 100 URLs ±10% (90 to 110)."
+    );
+    assert!(check_url_count(111, 100, 10.0).is_err());
+}
+
+// ===========================================================================================
+// url_structure Tests
+// ===========================================================================================
+
+#[test]
+fn test_url_structure() {
+    let structure = url_structure(
+        &urls(&[
+            "https://example.com/",
+            "https://example.com/blog/",
+            "https://example.com/blog/2024/05/post",
+            "https://example.com/shop/a/b/c?color=red&size=xl",
+            "not a url",
+        ]),
+        2,
+    );
+    assert_eq!(structure.max_depth, 4);
+    assert_eq!(structure.avg_depth, 2.25);
+    assert_eq!(
+        structure.deepest,
+        vec![
+            ("https://example.com/blog/2024/05/post".to_string(), 4),
+            (
+                "https://example.com/shop/a/b/c?color=red&size=xl".to_string(),
+                4
+            ),
+        ]
+    );
+    assert_eq!(
+        structure.longest,
+        vec![
+            (
+                "https://example.com/shop/a/b/c?color=red&size=xl".to_string(),
+                48
+            ),
+            ("https://example.com/blog/2024/05/post".to_string(), 37),
+        ]
+    );
+}
+
+#[test]
+fn test_url_structure_empty() {
+    let structure = url_structure(&[], 10);
+    assert_eq!(structure.max_depth, 0);
+    assert!(structure.deepest.is_empty());
+}
+
+// HTML sitemap Tests
+
+#[test]
+fn test_extract_html_links_keeps_same_origin_links() {
+    let html = r##"<!DOCTYPE html>
+<html><body>
+  <a href="/about">About</a>
+  <a class="nav" href='products/?page=2&amp;sort=asc'>Products</a>
+  <A HREF=https://example.com/contact#form>Contact</A>
+  <a href="/about#team">Team</a>
+  <a href="https://other.example.org/">Partner</a>
+  <a href="mailto:info@example.com">Mail</a>
+  <a name="top">Top</a>
+</body></html>"##;
+
+    let links = extract_html_links(html, "https://example.com/en/sitemap/");
+    assert_eq!(
+        links,
+        vec![
+            "https://example.com/about",
+            "https://example.com/en/sitemap/products/?page=2&sort=asc",
+            "https://example.com/contact",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_get_sitemap_urls_html_sitemap() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+    Mock::given(method("GET"))
+        .and(path("/sitemap/"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/html; charset=utf-8")
+                .set_body_string(
+                    r#"<html><body><a href="/b">B</a><a href="/a">A</a></body></html>"#,
+                ),
+        )
+        .mount(&mock_server)
+        .await;
+    let client = reqwest::Client::new();
+    let sitemap_url = format!("{base}/sitemap/");
+
+    let err = get_sitemap_urls(
+        &sitemap_url,
+        &client,
+        true,
+        false,
+        false,
+        4,
+        &Warnings::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("--html-sitemap"), "got: {}", err);
+
+    let result = get_sitemap_urls(
+        &sitemap_url,
+        &client,
+        true,
+        false,
+        true,
+        4,
+        &Warnings::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.urls, vec![format!("{base}/a"), format!("{base}/b")]);
+}
+
+// Sitemap encoding Tests
+
+#[test]
+fn test_decode_sitemap_uses_xml_declaration() {
+    let bytes = include_bytes!("fixtures/sitemap_latin1.xml");
+    assert!(String::from_utf8(bytes.to_vec()).is_err());
+
+    let (content, encoding) = decode_sitemap(bytes, Some("application/xml")).unwrap();
+    assert_eq!(encoding, Some("windows-1252"));
+    assert_eq!(
+        extract_sitemap_urls(&content),
+        vec![
+            "http://www.example.com/café",
+            "http://www.example.com/grüße?q=ä"
+        ]
+    );
+}
+
+#[test]
+fn test_decode_sitemap_content_type_charset_takes_precedence() {
+    let bytes = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?><urlset><url><loc>https://example.com/\xe9t\xe9</loc></url></urlset>";
+    let (content, encoding) =
+        decode_sitemap(bytes, Some("text/xml; charset=\"ISO-8859-1\"")).unwrap();
+    assert_eq!(encoding, Some("windows-1252"));
+    assert_eq!(
+        extract_sitemap_urls(&content),
+        vec!["https://example.com/été"]
+    );
+}
+
+#[test]
+fn test_decode_sitemap_utf8() {
+    let (content, encoding) = decode_sitemap("<loc>é</loc>".as_bytes(), None).unwrap();
+    assert_eq!(content, "<loc>é</loc>");
+    assert_eq!(encoding, None);
+
+    assert!(decode_sitemap(b"<loc>\xe9</loc>", None).is_err());
+}
+
+#[tokio::test]
+async fn test_get_sitemap_urls_warns_about_non_utf8_sitemap() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/sitemap.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            include_bytes!("fixtures/sitemap_latin1.xml").to_vec(),
+            "text/xml",
+        ))
+        .mount(&mock_server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let warnings = Warnings::default();
+    let sitemap_url = format!("{}/sitemap.xml", mock_server.uri());
+    let result = get_sitemap_urls(&sitemap_url, &client, true, false, false, 4, &warnings)
+        .await
+        .unwrap();
+
+    assert_eq!(result.urls[0], "http://www.example.com/café");
+    assert_eq!(
+        warnings.to_vec()[0],
+        format!(
+            "The sitemap {} is encoded as windows-1252. Sitemaps should be UTF-8.",
+            sitemap_url
+        )
+    );
+}
+
+// strip_query_params Tests
+
+fn tracked_urls() -> Vec<String> {
+    vec![
+        "https://example.com/page?utm_source=x".to_string(),
+        "https://example.com/page?utm_source=y".to_string(),
+        "https://example.com/list?page=2&utm_source=x&sort=asc".to_string(),
+        "https://example.com/list?page=2&sort=asc".to_string(),
+        "https://example.com/about".to_string(),
+    ]
+}
+
+#[test]
+fn test_strip_query_params_named() {
+    let names = vec!["utm_source".to_string()];
+    let (urls, collapsed) = strip_query_params(tracked_urls(), &names, false);
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/about",
+            "https://example.com/list?page=2&sort=asc",
+            "https://example.com/page",
+        ]
+    );
+    assert_eq!(collapsed, 2);
+}
+
+#[test]
+fn test_strip_query_params_all() {
+    let (urls, collapsed) = strip_query_params(tracked_urls(), &[], true);
+    assert_eq!(
+        urls,
+        vec![
+            "https://example.com/about",
+            "https://example.com/list",
+            "https://example.com/page",
+        ]
+    );
+    assert_eq!(collapsed, 2);
+}
+
+#[test]
+fn test_strip_query_params_unknown_name_keeps_urls() {
+    let names = vec!["ref".to_string()];
+    let (urls, collapsed) = strip_query_params(tracked_urls(), &names, false);
+    assert_eq!(urls.len(), 5);
+    assert_eq!(collapsed, 0);
+    assert!(urls.contains(&"https://example.com/list?page=2&utm_source=x&sort=asc".to_string()));
+}
+
+#[test]
+fn test_url_overview_counts_hosts_and_schemes() {
+    let urls: Vec<String> = [
+        "https://example.com/",
+        "https://EXAMPLE.com/about",
+        "http://example.com/legacy",
+        "https://cdn.example.net/file.pdf",
+        "not a url",
+    ]
+    .iter()
+    .map(|u| u.to_string())
+    .collect();
+
+    let overview = url_overview(&urls);
+    assert_eq!(overview.urls, 5);
+    assert_eq!(overview.hosts, 2);
+    assert_eq!(overview.https, 3);
+    assert_eq!(overview.http, 1);
+}
+
+#[test]
+fn test_parse_url_list() {
+    let urls = parse_url_list("# Pages\nhttps://example.com/a\n\n  http://example.com/b\n");
+    assert_eq!(
+        urls,
+        Ok(vec![
+            "https://example.com/a".to_string(),
+            "http://example.com/b".to_string()
+        ])
+    );
+
+    let err = parse_url_list("https://example.com/\nftp://example.com/file\n").unwrap_err();
+    assert!(err.contains("line 2"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_get_sitemap_urls_plain_text_list() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    let base = mock_server.uri();
+
+    Mock::given(method("GET"))
+        .and(path("/urls.txt"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Type", "text/plain")
+                .set_body_string(format!("{base}/a\n# comment\n{base}/b\n")),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let result = get_sitemap_urls(
+        &format!("{base}/urls.txt"),
+        &client,
+        true,
+        false,
+        false,
+        4,
+        &Warnings::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.urls, vec![format!("{base}/a"), format!("{base}/b")]);
+}
+
+// ===========================================================================================
+// merge_sitemap_urls Tests
+// ===========================================================================================
+
+#[test]
+fn test_merge_sitemap_urls() {
+    let first = SitemapUrls {
+        urls: vec!["https://a.com/1".to_string(), "https://a.com/2".to_string()],
+        duplicates: DuplicateUrls {
+            within_sitemap: vec!["https://a.com/1".to_string()],
+            across_sitemaps: vec![],
+        },
+        lastmod: [("https://a.com/2".to_string(), "2024-01-01".to_string())].into(),
+        sitemaps: vec![("https://a.com/sitemap.xml".to_string(), 3)],
+    };
+    let second = SitemapUrls {
+        urls: vec!["https://b.com/1".to_string(), "https://a.com/2".to_string()],
+        lastmod: [("https://a.com/2".to_string(), "2025-01-01".to_string())].into(),
+        sitemaps: vec![("https://b.com/sitemap.xml".to_string(), 2)],
+        ..Default::default()
+    };
+
+    let merged = merge_sitemap_urls(vec![first, second]);
+    assert_eq!(
+        merged.urls,
+        vec!["https://a.com/1", "https://a.com/2", "https://b.com/1"]
+    );
+    assert_eq!(merged.duplicates.within_sitemap, vec!["https://a.com/1"]);
+    assert_eq!(merged.duplicates.across_sitemaps, vec!["https://a.com/2"]);
+    assert_eq!(merged.lastmod["https://a.com/2"], "2024-01-01");
+    assert_eq!(
+        merged.sitemaps,
+        vec![
+            ("https://a.com/sitemap.xml".to_string(), 3),
+            ("https://b.com/sitemap.xml".to_string(), 2)
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_get_sitemap_urls_local_file() {
+    let path = std::fs::canonicalize("tests/fixtures/sitemap_valid.xml").unwrap();
+    let file_url = url::Url::from_file_path(path).unwrap();
+
+    let warnings = Warnings::default();
+    let result = get_sitemap_urls(
+        file_url.as_str(),
+        &reqwest::Client::new(),
+        true,
+        false,
+        false,
+        4,
+        &warnings,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.urls.len(), 5);
+    assert!(result.urls.contains(&"http://www.example.com/".to_string()));
+    assert_eq!(result.sitemaps, vec![(file_url.to_string(), 5)]);
+    assert!(warnings.to_vec().is_empty(), "{:?}", warnings.to_vec());
+
+    let missing = url::Url::from_file_path("/nonexistent/sitemap.xml").unwrap();
+    let err = get_sitemap_urls(
+        missing.as_str(),
+        &reqwest::Client::new(),
+        true,
+        false,
+        false,
+        4,
+        &Warnings::default(),
+    )
+    .await
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("Unable to read the sitemap file"), "{}", err);
+}
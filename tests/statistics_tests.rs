@@ -0,0 +1,434 @@
+use reqwest::StatusCode;
+use siteprobe::options::Cli;
+use siteprobe::report::{Report, Response};
+use siteprobe::sitemap::url_structure;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+fn make_response(started_at_ms: u64, response_time_ms: u64) -> Response {
+    Response {
+        url: "https://example.com/".to_string(),
+        response_time: Duration::from_millis(response_time_ms),
+        response_size: 1024,
+        status_code: StatusCode::OK,
+        started_at: Duration::from_millis(started_at_ms),
+        ..Default::default()
+    }
+}
+
+fn make_report(responses: Vec<Response>) -> Report {
+    Report {
+        sitemap_url: "https://example.com/sitemap.xml".to_string(),
+        concurrency_limit: 1,
+        total_time: Duration::from_secs(3),
+        responses: VecDeque::from(responses),
+        ..Default::default()
+    }
+}
+
+// ===========================================================================================
+// throughput Tests
+// ===========================================================================================
+
+#[test]
+fn test_throughput_empty_report() {
+    let report = make_report(vec![]);
+    assert!(report.throughput(Duration::from_secs(1)).is_empty());
+}
+
+#[test]
+fn test_throughput_per_second_buckets() {
+    let report = make_report(vec![
+        make_response(500, 10),
+        make_response(700, 10),
+        make_response(1200, 10),
+        // nothing in the third second
+        make_response(3400, 10),
+    ]);
+    // Buckets are relative to the first request (500ms)
+    assert_eq!(
+        report.throughput(Duration::from_secs(1)),
+        vec![3.0, 0.0, 1.0]
+    );
+}
+
+#[test]
+fn test_throughput_custom_interval() {
+    let report = make_report(vec![
+        make_response(0, 10),
+        make_response(1000, 10),
+        make_response(2500, 10),
+    ]);
+    assert_eq!(report.throughput(Duration::from_secs(2)), vec![1.0, 0.5]);
+}
+
+// ===========================================================================================
+// Redirect rate Tests
+// ===========================================================================================
+
+#[test]
+fn test_redirect_rate_counts_followed_redirects() {
+    let redirected = Response {
+        redirected: true,
+        ..make_response(0, 10)
+    };
+    let moved = Response {
+        status_code: StatusCode::MOVED_PERMANENTLY,
+        ..make_response(0, 10)
+    };
+    // A 304 answers a conditional request and doesn't redirect.
+    let not_modified = Response {
+        status_code: StatusCode::NOT_MODIFIED,
+        ..make_response(0, 10)
+    };
+    let report = make_report(vec![redirected, moved, not_modified, make_response(0, 10)]);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&report.to_json_string(&Cli::default()).unwrap()).unwrap();
+    let status = &json["statistics"]["statusCode"];
+    assert_eq!(status["redirectRatePercentage"], 50.0);
+    assert_eq!(status["successRatePercentage"], 50.0);
+}
+
+#[test]
+fn test_cache_hit_rate() {
+    let hit = Response {
+        cache_hit: true,
+        ..make_response(0, 10)
+    };
+    let report = make_report(vec![
+        hit,
+        make_response(0, 10),
+        make_response(0, 10),
+        make_response(0, 10),
+    ]);
+
+    assert_eq!(report.statistics(None).cache_hit_rate, 25.0);
+    let json: serde_json::Value =
+        serde_json::from_str(&report.to_json_string(&Cli::default()).unwrap()).unwrap();
+    assert_eq!(
+        json["statistics"]["statusCode"]["cacheHitRatePercentage"],
+        25.0
+    );
+}
+
+// ===========================================================================================
+// statistics Tests
+// ===========================================================================================
+
+#[test]
+fn test_statistics_typed_fields() {
+    let not_found = Response {
+        status_code: StatusCode::NOT_FOUND,
+        response_size: 0,
+        ..make_response(0, 300)
+    };
+    let report = make_report(vec![
+        make_response(0, 100),
+        make_response(0, 200),
+        not_found,
+    ]);
+
+    let stats = report.statistics(Some(0.25));
+    assert_eq!(stats.total_requests, 3);
+    assert_eq!(stats.requests_per_second, 1.0);
+    assert_eq!(stats.avg_response_time, Duration::from_millis(200));
+    assert_eq!(stats.min_response_time, Duration::from_millis(100));
+    assert_eq!(stats.max_response_time, Duration::from_millis(300));
+    assert!((stats.success_rate - 200.0 / 3.0).abs() < 1e-9);
+    assert!((stats.error_rate - 100.0 / 3.0).abs() < 1e-9);
+    assert_eq!(stats.redirect_rate, 0.0);
+    assert!((stats.slow_rate.unwrap() - 100.0 / 3.0).abs() < 1e-9);
+    assert_eq!(stats.min_response_size, 0);
+    assert_eq!(stats.max_response_size, 1024);
+
+    assert_eq!(report.statistics(None).slow_rate, None);
+}
+
+#[test]
+fn test_statistics_percentiles_ignore_completion_order() {
+    // 1..=20 x 10ms, in the shuffled order the requests might complete in.
+    let times = [
+        130, 20, 170, 60, 200, 10, 90, 150, 40, 110, 190, 70, 30, 180, 120, 50, 160, 80, 140, 100,
+    ];
+    let report = make_report(times.iter().map(|&ms| make_response(0, ms)).collect());
+
+    let stats = report.statistics(None);
+    assert_eq!(stats.median_response_time, Duration::from_millis(105));
+    assert_eq!(stats.p90_response_time, Duration::from_millis(180));
+    assert_eq!(stats.p95_response_time, Duration::from_millis(190));
+    assert_eq!(stats.p99_response_time, Duration::from_millis(200));
+    assert_eq!(stats.min_response_time, Duration::from_millis(10));
+    assert_eq!(stats.max_response_time, Duration::from_millis(200));
+
+    // With an odd number of samples, the median is the middle one.
+    let report = make_report(vec![
+        make_response(0, 300),
+        make_response(0, 100),
+        make_response(0, 200),
+    ]);
+    assert_eq!(
+        report.statistics(None).median_response_time,
+        Duration::from_millis(200)
+    );
+}
+
+#[test]
+fn test_statistics_ttfb() {
+    let report = make_report(
+        [40, 10, 30, 20]
+            .iter()
+            .map(|&ms| Response {
+                ttfb: Duration::from_millis(ms),
+                ..make_response(0, 500)
+            })
+            .collect(),
+    );
+
+    let stats = report.statistics(None);
+    assert_eq!(stats.avg_ttfb, Duration::from_millis(25));
+    assert_eq!(stats.median_ttfb, Duration::from_millis(25));
+    assert_eq!(stats.p95_ttfb, Duration::from_millis(40));
+
+    let json = report.to_json_string(&Cli::default()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["statistics"]["responseTime"]["avgTtfbMs"], 25);
+    assert_eq!(json["statistics"]["responseTime"]["p95TtfbMs"], 40);
+    assert_eq!(json["responses"][0]["ttfb"], 40);
+}
+
+#[tokio::test]
+async fn test_ttfb_excludes_body_download() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Sends the headers right away and the body 500ms later.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 1024];
+        let _ = socket.read(&mut request).await;
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        socket.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        socket.write_all(b"hello").await.unwrap();
+    });
+
+    let client = reqwest::Client::new();
+    let response = get_url_response(
+        &format!("http://{addr}/"),
+        &client,
+        false,
+        None,
+        &None,
+        false,
+        false,
+        &ContentChecks::default(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response.response_size, 5);
+    assert!(response.response_time >= Duration::from_millis(500));
+    assert!(
+        response.ttfb + Duration::from_millis(400) < response.response_time,
+        "TTFB {:?} should exclude the body download ({:?} total)",
+        response.ttfb,
+        response.response_time
+    );
+}
+
+#[tokio::test]
+async fn test_response_size_prefers_content_length() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // The server announces 4 bytes but hangs up after sending 2.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 1024];
+        let _ = socket.read(&mut request).await;
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        socket.write_all(b"he").await.unwrap();
+    });
+
+    let client = reqwest::Client::new();
+    let response = get_url_response(
+        &format!("http://{addr}/"),
+        &client,
+        false,
+        None,
+        &None,
+        false,
+        false,
+        &ContentChecks::default(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response.response_size, 4);
+}
+
+#[tokio::test]
+async fn test_large_body_is_counted_without_content_length() {
+    use sha2::Digest;
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // 20 MiB without a Content-Length header, delimited by closing the connection,
+    // so the size has to come from counting the streamed bytes.
+    const SIZE: usize = 20 * 1024 * 1024;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 1024];
+        let _ = socket.read(&mut request).await;
+        socket
+            .write_all(b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let chunk = vec![b'x'; 64 * 1024];
+        for _ in 0..SIZE / chunk.len() {
+            socket.write_all(&chunk).await.unwrap();
+        }
+    });
+
+    let client = reqwest::Client::new();
+    let response = get_url_response(
+        &format!("http://{addr}/"),
+        &client,
+        false,
+        None,
+        &None,
+        false,
+        true,
+        &ContentChecks::default(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response.response_size, SIZE);
+    assert!(!response.truncated);
+    // The hash is computed while streaming, without holding the body.
+    assert_eq!(
+        response.body_hash.unwrap(),
+        format!("{:x}", sha2::Sha256::digest(vec![b'x'; SIZE]))
+    );
+}
+
+#[test]
+fn test_statistics_empty_report() {
+    let stats = make_report(vec![]).statistics(None);
+    assert_eq!(stats.total_requests, 0);
+    assert_eq!(stats.success_rate, 0.0);
+    assert_eq!(stats.p95_response_time, Duration::ZERO);
+}
+
+// ===========================================================================================
+// Slow responses by status Tests
+// ===========================================================================================
+
+fn make_slow_response(url: &str, status: StatusCode, response_time_ms: u64) -> Response {
+    Response {
+        url: url.to_string(),
+        status_code: status,
+        ..make_response(0, response_time_ms)
+    }
+}
+
+#[test]
+fn test_slowest_responses_by_status() {
+    let report = make_report(vec![
+        make_slow_response("https://example.com/a", StatusCode::OK, 1500),
+        make_slow_response("https://example.com/b", StatusCode::OK, 2500),
+        make_slow_response("https://example.com/c", StatusCode::OK, 1200),
+        make_slow_response("https://example.com/d", StatusCode::OK, 100),
+        make_slow_response("https://example.com/e", StatusCode::BAD_GATEWAY, 3000),
+        make_slow_response("https://example.com/f", StatusCode::NOT_FOUND, 50),
+    ]);
+
+    let groups = report.slowest_responses_by_status(1.0, 2);
+    let summary: Vec<(&str, Vec<&str>)> = groups
+        .iter()
+        .map(|(class, responses)| {
+            (
+                class.as_str(),
+                responses.iter().map(|r| r.url.as_str()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "2xx",
+                vec!["https://example.com/b", "https://example.com/a"]
+            ),
+            ("5xx", vec!["https://example.com/e"]),
+        ]
+    );
+}
+
+#[test]
+fn test_slow_responses_json() {
+    let report = make_report(vec![
+        make_slow_response("https://example.com/a", StatusCode::OK, 1500),
+        make_slow_response("https://example.com/e", StatusCode::BAD_GATEWAY, 3000),
+    ]);
+
+    let options = Cli {
+        slow_threshold: Some(1.0),
+        ..Cli::default()
+    };
+    let json: serde_json::Value =
+        serde_json::from_str(&report.to_json_string(&options).unwrap()).unwrap();
+    assert_eq!(json["slowResponses"][0]["url"], "https://example.com/e");
+    assert!(json.get("slowResponsesByStatus").is_none());
+
+    let options = Cli {
+        slow_by_status: true,
+        ..options
+    };
+    let json: serde_json::Value =
+        serde_json::from_str(&report.to_json_string(&options).unwrap()).unwrap();
+    let by_status = &json["slowResponsesByStatus"];
+    assert_eq!(by_status["2xx"][0]["url"], "https://example.com/a");
+    assert_eq!(by_status["5xx"][0]["statusCode"], 502);
+    assert!(by_status.get("4xx").is_none());
+}
+
+// ===========================================================================================
+// URL structure Tests
+// ===========================================================================================
+
+#[test]
+fn test_url_structure_json() {
+    let report = make_report(vec![make_response(0, 10)]);
+    let json: serde_json::Value =
+        serde_json::from_str(&report.to_json_string(&Cli::default()).unwrap()).unwrap();
+    assert!(json.get("urlStructure").is_none());
+
+    let report = Report {
+        url_structure: Some(url_structure(&["https://example.com/a/b".to_string()], 10)),
+        ..report
+    };
+    let json: serde_json::Value =
+        serde_json::from_str(&report.to_json_string(&Cli::default()).unwrap()).unwrap();
+    let structure = &json["urlStructure"];
+    assert_eq!(structure["maxDepth"], 2);
+    assert_eq!(structure["deepest"][0]["url"], "https://example.com/a/b");
+    assert_eq!(structure["longest"][0]["length"], 23);
+}
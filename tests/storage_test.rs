@@ -0,0 +1,406 @@
+use siteprobe::options::StorageLayout;
+use siteprobe::storage::{document_path, extension_for, store_response_on_disk, OutputDir};
+use url::Url;
+
+const HTML: Option<&str> = Some("text/html; charset=utf-8");
+
+fn path_of(url: &str) -> String {
+    document_path(&Url::parse(url).unwrap(), StorageLayout::Flat, HTML)
+}
+
+fn mirror_path_of(url: &str) -> String {
+    document_path(&Url::parse(url).unwrap(), StorageLayout::Mirror, HTML)
+}
+
+#[test]
+fn test_document_path_from_url_path() {
+    assert_eq!(path_of("https://example.com/"), "index.html");
+    assert_eq!(path_of("https://example.com/about"), "about.html");
+    assert_eq!(path_of("https://example.com/blog/post/"), "blog/post.html");
+}
+
+#[test]
+fn test_document_path_includes_query_string() {
+    assert_eq!(
+        path_of("https://example.com/catalog?item=12&desc=hawaii"),
+        "catalog_item=12&desc=hawaii.html"
+    );
+    assert_eq!(path_of("https://example.com/?page=2"), "index_page=2.html");
+    assert_ne!(
+        path_of("https://example.com/catalog?item=12"),
+        path_of("https://example.com/catalog?item=73")
+    );
+}
+
+#[test]
+fn test_document_path_hashes_unsafe_or_long_queries() {
+    let path = path_of("https://example.com/search?q=a/b:c");
+    assert!(path.starts_with("search_"), "{}", path);
+    assert_eq!(path.len(), "search_".len() + 16 + ".html".len());
+    assert_ne!(path, path_of("https://example.com/search?q=a/b:d"));
+
+    let long = format!("https://example.com/search?q={}", "x".repeat(200));
+    assert_eq!(path_of(&long).len(), "search_".len() + 16 + ".html".len());
+}
+
+#[test]
+fn test_document_path_sanitizes_path_segments() {
+    assert_eq!(path_of("https://example.com/a:b/c*d"), "a_b/c_d.html");
+}
+
+#[test]
+fn test_document_path_mirror_layout() {
+    assert_eq!(mirror_path_of("https://example.com/"), "index.html");
+    assert_eq!(
+        mirror_path_of("https://example.com/a/b/c"),
+        "a/b/c/index.html"
+    );
+    assert_eq!(
+        mirror_path_of("https://example.com/a/b/c/"),
+        "a/b/c/index.html"
+    );
+    assert_eq!(
+        mirror_path_of("https://example.com/catalog?item=12"),
+        "catalog/index_item=12.html"
+    );
+}
+
+#[test]
+fn test_document_path_stays_inside_output_dir() {
+    for url in [
+        "https://example.com/a/../../../etc/passwd",
+        "https://example.com/%2e%2e/%2e%2e/etc/passwd",
+        "https://example.com//etc/passwd",
+    ] {
+        for layout in [StorageLayout::Flat, StorageLayout::Mirror] {
+            let path = document_path(&Url::parse(url).unwrap(), layout, HTML);
+            assert!(
+                !path.starts_with('/') && !path.split('/').any(|s| s == ".."),
+                "{} -> {}",
+                url,
+                path
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_store_response_on_disk_mirror_layout_creates_deep_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = OutputDir {
+        path: dir.path().to_path_buf(),
+        layout: StorageLayout::Mirror,
+        store_headers: false,
+        errors_only: false,
+    };
+    for path in ["", "docs", "docs/guide/install/linux"] {
+        let url = Url::parse(&format!("https://example.com/{path}")).unwrap();
+        store_response_on_disk(&output_dir, &url, HTML, None, path.as_bytes())
+            .await
+            .unwrap();
+    }
+
+    assert!(dir.path().join("index.html").is_file());
+    assert!(dir.path().join("docs/index.html").is_file());
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("docs/guide/install/linux/index.html")).unwrap(),
+        "docs/guide/install/linux"
+    );
+}
+
+#[tokio::test]
+async fn test_store_response_on_disk_keeps_urls_differing_in_query() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = OutputDir {
+        path: dir.path().to_path_buf(),
+        layout: StorageLayout::Flat,
+        store_headers: false,
+        errors_only: false,
+    };
+    for item in ["12", "73"] {
+        let url = Url::parse(&format!("https://example.com/catalog?item={item}")).unwrap();
+        store_response_on_disk(&output_dir, &url, HTML, None, item.as_bytes())
+            .await
+            .unwrap();
+    }
+
+    let mut files: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["catalog_item=12.html", "catalog_item=73.html"]);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("catalog_item=73.html")).unwrap(),
+        "73"
+    );
+}
+
+#[test]
+fn test_extension_for_content_type() {
+    assert_eq!(extension_for(HTML), "html");
+    assert_eq!(extension_for(Some("application/PDF")), "pdf");
+    assert_eq!(extension_for(Some("application/ld+json")), "json");
+    assert_eq!(extension_for(Some("image/svg+xml")), "svg");
+    assert_eq!(extension_for(Some("application/rss+xml")), "xml");
+    assert_eq!(extension_for(Some("application/octet-stream")), "bin");
+    assert_eq!(extension_for(None), "bin");
+}
+
+#[test]
+fn test_document_path_uses_content_type_extension() {
+    let path = |url: &str, layout, content_type| {
+        document_path(&Url::parse(url).unwrap(), layout, Some(content_type))
+    };
+    for layout in [StorageLayout::Flat, StorageLayout::Mirror] {
+        assert_eq!(
+            path(
+                "https://example.com/files/report",
+                layout,
+                "application/pdf"
+            ),
+            "files/report.pdf"
+        );
+        // An extension the URL already has isn't repeated.
+        assert_eq!(
+            path(
+                "https://example.com/files/report.pdf",
+                layout,
+                "application/pdf"
+            ),
+            "files/report.pdf"
+        );
+        assert_eq!(
+            path(
+                "https://example.com/api/items?page=2",
+                layout,
+                "application/json"
+            ),
+            "api/items_page=2.json"
+        );
+        assert_eq!(
+            path("https://example.com/page.html", layout, "text/html"),
+            "page.html"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_pdf_response_is_stored_with_pdf_extension() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/brochure"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_raw(b"%PDF-1.7".to_vec(), "application/pdf"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = Some(OutputDir {
+        path: dir.path().to_path_buf(),
+        layout: StorageLayout::Flat,
+        store_headers: false,
+        errors_only: false,
+    });
+    get_url_response(
+        &format!("{}/brochure", mock_server.uri()),
+        &reqwest::Client::new(),
+        false,
+        None,
+        &output_dir,
+        false,
+        false,
+        &ContentChecks::default(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read(dir.path().join("brochure.pdf")).unwrap(),
+        b"%PDF-1.7"
+    );
+    assert!(!dir.path().join("brochure.html").exists());
+}
+
+#[test]
+fn test_document_path_renames_reserved_windows_names() {
+    assert_eq!(path_of("https://example.com/con"), "con_.html");
+    assert_eq!(path_of("https://example.com/NUL.txt"), "NUL.txt_.html");
+    assert_eq!(path_of("https://example.com/aux/page"), "aux_/page.html");
+    assert_eq!(path_of("https://example.com/lpt1/"), "lpt1_.html");
+    assert_eq!(path_of("https://example.com/console"), "console.html");
+}
+
+#[test]
+fn test_document_path_replaces_trailing_dots_and_spaces() {
+    assert_eq!(
+        path_of("https://example.com/dir./page%20"),
+        "dir_/page%20.html"
+    );
+    assert_eq!(path_of("https://example.com/a:b|c"), "a_b_c.html");
+}
+
+#[test]
+fn test_document_path_limits_file_name_length() {
+    let long = "x".repeat(300);
+    let first = path_of(&format!("https://example.com/{long}a"));
+    let second = path_of(&format!("https://example.com/{long}b"));
+    assert_eq!(first.len(), 255 - ".headers.txt".len());
+    assert!(first.ends_with(".html"));
+    assert_ne!(first, second, "truncated names stay unique");
+
+    // Multi-byte characters are not split.
+    let path = path_of(&format!("https://example.com/{}", "ä".repeat(200)));
+    assert!(path.len() <= 255);
+
+    // Each directory is limited on its own.
+    let path = path_of(&format!("https://example.com/{long}/{long}"));
+    assert!(path.split('/').all(|name| name.len() <= 255), "{}", path);
+}
+
+#[tokio::test]
+async fn test_store_response_on_disk_writes_hostile_urls() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = OutputDir {
+        path: dir.path().to_path_buf(),
+        layout: StorageLayout::Mirror,
+        store_headers: false,
+        errors_only: false,
+    };
+    for url in [
+        format!("https://example.com/{}", "a".repeat(1000)),
+        "https://example.com/c:/windows/con".to_string(),
+        "https://example.com/%2e%2e/%2e%2e/secret".to_string(),
+        format!("https://example.com/search?q={}", "<>|".repeat(100)),
+    ] {
+        let url = Url::parse(&url).unwrap();
+        let path = store_response_on_disk(&output_dir, &url, HTML, None, b"ok")
+            .await
+            .unwrap();
+        assert!(path.starts_with(dir.path()), "{}", path.display());
+        assert!(path.is_file());
+    }
+}
+
+#[tokio::test]
+async fn test_store_response_on_disk_returns_write_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    // A file where the directory for `blocked/page` would go.
+    std::fs::write(dir.path().join("blocked"), "").unwrap();
+    let output_dir = OutputDir {
+        path: dir.path().to_path_buf(),
+        layout: StorageLayout::Flat,
+        store_headers: false,
+        errors_only: false,
+    };
+    let url = Url::parse("https://example.com/blocked/page").unwrap();
+    let err = store_response_on_disk(&output_dir, &url, HTML, None, b"ok")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("blocked"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_store_headers_writes_sidecar_file() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/gone"))
+        .respond_with(
+            ResponseTemplate::new(410)
+                .insert_header("x-cache", "HIT")
+                .set_body_raw("<p>Gone</p>", "text/html"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    for store_headers in [false, true] {
+        let output_dir = Some(OutputDir {
+            path: dir.path().join(store_headers.to_string()),
+            layout: StorageLayout::Flat,
+            store_headers,
+            errors_only: false,
+        });
+        get_url_response(
+            &format!("{}/gone", mock_server.uri()),
+            &reqwest::Client::new(),
+            false,
+            None,
+            &output_dir,
+            false,
+            false,
+            &ContentChecks::default(),
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    assert!(dir.path().join("false/gone.html").is_file());
+    assert!(!dir.path().join("false/gone.html.headers.txt").exists());
+
+    let headers = std::fs::read_to_string(dir.path().join("true/gone.html.headers.txt")).unwrap();
+    assert!(headers.starts_with("HTTP/1.1 410 Gone\n"), "{}", headers);
+    assert!(headers.contains("x-cache: HIT\n"), "{}", headers);
+    assert!(headers.contains("content-type: text/html\n"), "{}", headers);
+}
+
+#[tokio::test]
+async fn test_store_errors_only_skips_successful_responses() {
+    use siteprobe::network::{get_url_response, ContentChecks};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    let mock_server = MockServer::start().await;
+    for (page, status) in [("/ok", 200), ("/missing", 404), ("/broken", 500)] {
+        Mock::given(method("GET"))
+            .and(path(page))
+            .respond_with(ResponseTemplate::new(status).set_body_raw(page, "text/html"))
+            .mount(&mock_server)
+            .await;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let output_dir = Some(OutputDir {
+        path: dir.path().to_path_buf(),
+        layout: StorageLayout::Flat,
+        store_headers: false,
+        errors_only: true,
+    });
+    for page in ["/ok", "/missing", "/broken"] {
+        get_url_response(
+            &format!("{}{page}", mock_server.uri()),
+            &reqwest::Client::new(),
+            false,
+            None,
+            &output_dir,
+            false,
+            false,
+            &ContentChecks::default(),
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    let mut files: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["broken.html", "missing.html"]);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("missing.html")).unwrap(),
+        "/missing"
+    );
+}
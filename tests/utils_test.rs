@@ -1,5 +1,8 @@
-use siteprobe::utils::{generate_random_number, truncate_message, validate_basic_auth};
+use siteprobe::utils::{
+    generate_random_number, retry_backoff, sparkline, truncate_message, validate_basic_auth,
+};
 use std::collections::HashSet;
+use std::time::Duration;
 
 // ===========================================================================================
 // truncate_message Tests
@@ -69,6 +72,24 @@ fn test_invalid_basic_auth() {
     assert!(validate_basic_auth(":pass").is_err());
 }
 
+// ===========================================================================================
+// retry_backoff Tests
+// ===========================================================================================
+
+#[test]
+fn test_retry_backoff_doubles_with_jitter() {
+    let base = Duration::from_millis(100);
+    for (attempt, min_ms) in [(1, 100), (2, 200), (3, 400), (4, 800)] {
+        let backoff = retry_backoff(attempt, base);
+        assert!(
+            backoff >= Duration::from_millis(min_ms)
+                && backoff <= Duration::from_millis(min_ms * 3 / 2),
+            "retry {attempt} waited {backoff:?}"
+        );
+    }
+    assert_eq!(retry_backoff(3, Duration::ZERO), Duration::ZERO);
+}
+
 // ===========================================================================================
 // generate_random_number Tests
 // ===========================================================================================
@@ -119,3 +140,19 @@ fn test_generate_random_number_zero_length() {
     // Passing a length of zero should panic because `10u64.pow(length - 1)` will underflow
     generate_random_number(0);
 }
+
+// ===========================================================================================
+// sparkline Tests
+// ===========================================================================================
+
+#[test]
+fn test_sparkline_scales_to_largest_bucket() {
+    assert_eq!(sparkline(&[1, 2, 3, 4, 5, 6, 7, 8]), "▁▂▃▄▅▆▇█");
+    assert_eq!(sparkline(&[2, 0, 10]), "▂ █");
+}
+
+#[test]
+fn test_sparkline_empty_and_zero_buckets() {
+    assert_eq!(sparkline(&[]), "");
+    assert_eq!(sparkline(&[0, 0, 0]), "   ");
+}
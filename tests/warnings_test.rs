@@ -0,0 +1,28 @@
+use siteprobe::warnings::{summary, Warnings};
+
+#[test]
+fn test_warnings_are_shared_between_clones() {
+    let warnings = Warnings::default();
+    let task = warnings.clone();
+    task.push("first");
+    warnings.extend(vec!["second".to_string()]);
+    assert_eq!(warnings.to_vec(), vec!["first", "second"]);
+    assert_eq!(task.to_vec(), warnings.to_vec());
+}
+
+#[test]
+fn test_summary_empty() {
+    assert_eq!(summary(&[]), None);
+}
+
+#[test]
+fn test_summary_lists_warnings() {
+    assert_eq!(
+        summary(&["only one".to_string()]).unwrap(),
+        "⚠️ 1 warning\n  - only one"
+    );
+    assert_eq!(
+        summary(&["a".to_string(), "b".to_string()]).unwrap(),
+        "⚠️ 2 warnings\n  - a\n  - b"
+    );
+}